@@ -404,6 +404,9 @@ export function DashboardPage() {
                                   ["CPU", typeof t?.cpuPercent === "number" ? `${t.cpuPercent.toFixed(0)}%` : "—"],
                                   ["Viewer RAM", typeof t?.rssBytes === "number" ? formatBytes(t.rssBytes) : "—"],
                                   ["Free on device", typeof t?.memAvailableBytes === "number" ? formatBytes(t.memAvailableBytes) : "—"],
+                                  ["Cache", typeof t?.cacheBytes === "number" ? `${formatBytes(t.cacheBytes)}${typeof t.cacheMaxBytes === "number" ? ` / ${formatBytes(t.cacheMaxBytes)}` : ""}` : "—"],
+                                  ["Disk free", typeof t?.diskFreeBytes === "number" ? `${formatBytes(t.diskFreeBytes)}${t.diskPressure ? " (low)" : ""}` : "—"],
+                                  ["Sync", t?.syncPhase ? `${t.syncPhase} · ${t.syncCached ?? 0}/${t.syncTotal ?? 0} cached${t.syncRetrying ? `, ${t.syncRetrying} retrying` : ""}` : "—"],
                                   ["Uptime", typeof t?.uptimeSecs === "number" ? formatDuration(t.uptimeSecs) : "—"],
                                 ]}
                               />
@@ -54,15 +54,132 @@ type Device = {
     interval?: number;
     transition?: string;
     transitionDuration?: number;
+    transitionEasing?: string;
+    transitions?: WeightedTransition[];
+    layoutTransitions?: Record<string, WeightedTransition[]>;
     blur?: boolean;
+    kenBurns?: boolean;
     shuffle?: boolean;
     showClock?: boolean;
     clockOffsetX?: number;
     clockOffsetY?: number;
+    clockFormat?: string;
+    clockDateFormat?: string;
+    clockTimezone?: string;
     displayMode?: string;
+    pinnedTags?: string[];
+    quietMode?: string;
+    quietHours?: Record<string, string>;
+    quietExceptions?: QuietException[];
+    dimming?: DimmingPoint[];
+    latitude?: number | null;
+    longitude?: number | null;
+    rotation?: number;
+    burnInShift?: number;
+    burnInRefreshMins?: number;
+    maxStaticSecs?: number;
   };
 };
 
+type QuietException = { date: string; hours: string };
+
+type DimmingPoint = { at: string; brightness: number; warmth?: number };
+
+const QUIET_DAYS = [
+  ["mon", "Mon"],
+  ["tue", "Tue"],
+  ["wed", "Wed"],
+  ["thu", "Thu"],
+  ["fri", "Fri"],
+  ["sat", "Sat"],
+  ["sun", "Sun"],
+] as const;
+
+type WeightedTransition = string | { name: string; weight?: number };
+
+const DEFAULT_PINNED_TAGS = ["favorite", "pinned"];
+
+/** Split a comma-separated tag list, dropping blanks. */
+function parseTagList(text: string): string[] {
+  return text.split(",").map((t) => t.trim()).filter((t) => t.length > 0);
+}
+
+/** Render a transition set as the editable "name:weight, name" text form. */
+function formatTransitionMix(list?: WeightedTransition[]): string {
+  return (list ?? [])
+    .map((t) => {
+      if (typeof t === "string") return t;
+      return (t.weight ?? 1) === 1 ? t.name : `${t.name}:${t.weight}`;
+    })
+    .join(", ");
+}
+
+/** Parse "fade:2, slide-left" into the config's weighted transition list. */
+function parseTransitionMix(text: string): { name: string; weight: number }[] {
+  return text
+    .split(",")
+    .map((part) => part.trim())
+    .filter(Boolean)
+    .map((part) => {
+      const [name, weight] = part.split(":").map((s) => s.trim());
+      const w = Number(weight);
+      return { name, weight: weight && Number.isFinite(w) && w >= 0 ? Math.round(w) : 1 };
+    });
+}
+
+/** Render quiet-hours exceptions as "2026-12-24 01:00-06:00, 2026-12-31 off". */
+function formatQuietExceptions(list?: QuietException[]): string {
+  return (list ?? []).map((x) => `${x.date} ${x.hours.trim() || "off"}`).join(", ");
+}
+
+/** Parse the text form back; "off" (or no window) means no quiet hours that night. */
+function parseQuietExceptions(text: string): QuietException[] {
+  return text
+    .split(",")
+    .map((part) => part.trim())
+    .filter(Boolean)
+    .map((part) => {
+      const [date, hours = ""] = part.split(/\s+/);
+      return { date, hours: hours.toLowerCase() === "off" ? "" : hours };
+    });
+}
+
+/** Drop empty days so the stored config only lists nights with quiet hours. */
+function compactQuietHours(hours: Record<string, string>): Record<string, string> {
+  return Object.fromEntries(
+    Object.entries(hours)
+      .map(([day, window]) => [day, window.trim()])
+      .filter(([, window]) => window),
+  );
+}
+
+/** Render the dimming curve as "sunrise=100, sunset+30=60/40, 22:00=25/80" (brightness/warmth %). */
+function formatDimming(list?: DimmingPoint[]): string {
+  return (list ?? [])
+    .map((p) => `${p.at}=${p.brightness}${p.warmth ? `/${p.warmth}` : ""}`)
+    .join(", ");
+}
+
+/** Parse the text form back, dropping points without a usable brightness. */
+function parseDimming(text: string): DimmingPoint[] {
+  return text
+    .split(",")
+    .map((part) => part.trim())
+    .filter(Boolean)
+    .map((part) => {
+      const [at, levels = ""] = part.split("=").map((s) => s.trim());
+      const [brightness, warmth] = levels.split("/").map((s) => Number(s.trim()));
+      return { at, brightness, warmth: Number.isFinite(warmth) ? warmth : 0 };
+    })
+    .filter((p) => p.at && Number.isFinite(p.brightness));
+}
+
+/** Parse a coordinate input; blank (or not a number) means unset. */
+function parseCoordinate(text: string): number | null {
+  const value = Number(text.trim());
+  return text.trim() && Number.isFinite(value) ? value : null;
+}
+
 type PendingDevice = {
   session_id: string;
   hostname: string;
@@ -82,14 +199,31 @@ function DeviceCard({ device, onRefresh, showMessage, showError }: DeviceCardPro
   const [slideInterval, setSlideInterval] = useState(cfg.interval ?? 8000);
   const [transition, setTransition] = useState(cfg.transition ?? "fade");
   const [transitionDuration, setTransitionDuration] = useState(cfg.transitionDuration ?? 1000);
+  const [transitionEasing, setTransitionEasing] = useState(cfg.transitionEasing ?? "");
+  const [transitionMix, setTransitionMix] = useState(formatTransitionMix(cfg.transitions));
   const [blur, setBlur] = useState(cfg.blur ?? true);
+  const [kenBurns, setKenBurns] = useState(cfg.kenBurns ?? false);
   const [shuffle, setShuffle] = useState(cfg.shuffle ?? false);
   const [showClock, setShowClock] = useState(cfg.showClock ?? true);
   const [clockOffsetX, setClockOffsetX] = useState(cfg.clockOffsetX ?? 0);
   const [clockOffsetY, setClockOffsetY] = useState(cfg.clockOffsetY ?? 0);
+  const [clockFormat, setClockFormat] = useState(cfg.clockFormat ?? "12h");
+  const [clockDateFormat, setClockDateFormat] = useState(cfg.clockDateFormat ?? "");
+  const [clockTimezone, setClockTimezone] = useState(cfg.clockTimezone ?? "");
   const [showInfo, setShowInfo] = useState(cfg.showInfo ?? false);
   const [showLocationInfo, setShowLocationInfo] = useState(cfg.showLocationInfo ?? false);
   const [displayMode, setDisplayMode] = useState(cfg.displayMode ?? "single");
+  const [pinnedTags, setPinnedTags] = useState((cfg.pinnedTags ?? DEFAULT_PINNED_TAGS).join(", "));
+  const [quietMode, setQuietMode] = useState(cfg.quietMode ?? "off");
+  const [quietHours, setQuietHours] = useState<Record<string, string>>(cfg.quietHours ?? {});
+  const [quietExceptions, setQuietExceptions] = useState(formatQuietExceptions(cfg.quietExceptions));
+  const [dimming, setDimming] = useState(formatDimming(cfg.dimming));
+  const [latitude, setLatitude] = useState(cfg.latitude?.toString() ?? "");
+  const [longitude, setLongitude] = useState(cfg.longitude?.toString() ?? "");
+  const [rotation, setRotation] = useState(cfg.rotation ?? 0);
+  const [burnInShift, setBurnInShift] = useState(cfg.burnInShift ?? 0);
+  const [burnInRefreshMins, setBurnInRefreshMins] = useState(cfg.burnInRefreshMins ?? 0);
+  const [maxStaticSecs, setMaxStaticSecs] = useState(cfg.maxStaticSecs ?? 0);
   const [saveSuccess, setSaveSuccess] = useState(false);
   const [editing, setEditing] = useState(false);
   const [editName, setEditName] = useState(device.name);
@@ -100,18 +234,36 @@ function DeviceCard({ device, onRefresh, showMessage, showError }: DeviceCardPro
     (cfg.interval ?? 8000) !== slideInterval ||
     (cfg.transition ?? "fade") !== transition ||
     (cfg.transitionDuration ?? 1000) !== transitionDuration ||
+    (cfg.transitionEasing ?? "") !== transitionEasing ||
+    formatTransitionMix(cfg.transitions) !== formatTransitionMix(parseTransitionMix(transitionMix)) ||
     (cfg.blur ?? true) !== blur ||
+    (cfg.kenBurns ?? false) !== kenBurns ||
     (cfg.shuffle ?? false) !== shuffle ||
     (cfg.showClock ?? true) !== showClock ||
     (cfg.clockOffsetX ?? 0) !== clockOffsetX ||
     (cfg.clockOffsetY ?? 0) !== clockOffsetY ||
+    (cfg.clockFormat ?? "12h") !== clockFormat ||
+    (cfg.clockDateFormat ?? "") !== clockDateFormat ||
+    (cfg.clockTimezone ?? "") !== clockTimezone ||
     (cfg.showInfo ?? false) !== showInfo ||
     (cfg.showLocationInfo ?? false) !== showLocationInfo ||
-    (cfg.displayMode ?? "single") !== displayMode;
+    (cfg.displayMode ?? "single") !== displayMode ||
+    (cfg.pinnedTags ?? DEFAULT_PINNED_TAGS).join(",") !== parseTagList(pinnedTags).join(",") ||
+    (cfg.quietMode ?? "off") !== quietMode ||
+    JSON.stringify(compactQuietHours(cfg.quietHours ?? {})) !== JSON.stringify(compactQuietHours(quietHours)) ||
+    formatQuietExceptions(cfg.quietExceptions) !== formatQuietExceptions(parseQuietExceptions(quietExceptions)) ||
+    formatDimming(cfg.dimming) !== formatDimming(parseDimming(dimming)) ||
+    (cfg.latitude ?? null) !== parseCoordinate(latitude) ||
+    (cfg.longitude ?? null) !== parseCoordinate(longitude) ||
+    (cfg.rotation ?? 0) !== rotation ||
+    (cfg.burnInShift ?? 0) !== burnInShift ||
+    (cfg.burnInRefreshMins ?? 0) !== burnInRefreshMins ||
+    (cfg.maxStaticSecs ?? 0) !== maxStaticSecs;
 
   const saveConfig = async () => {
     try {
-      const newConfig = { interval: slideInterval, transition, transitionDuration, blur, shuffle, showClock, clockOffsetX, clockOffsetY, showInfo, showLocationInfo, displayMode };
+      // Spread the stored config first so keys this card doesn't edit survive a save.
+      const newConfig = { ...cfg, interval: slideInterval, transition, transitionDuration, transitionEasing, transitions: parseTransitionMix(transitionMix), blur, kenBurns, shuffle, showClock, clockOffsetX, clockOffsetY, clockFormat, clockDateFormat, clockTimezone, showInfo, showLocationInfo, displayMode, pinnedTags: parseTagList(pinnedTags), quietMode, quietHours: compactQuietHours(quietHours), quietExceptions: parseQuietExceptions(quietExceptions), dimming: parseDimming(dimming), latitude: parseCoordinate(latitude), longitude: parseCoordinate(longitude), rotation, burnInShift, burnInRefreshMins, maxStaticSecs };
       await pb.collection("devices").update(device.id, { config: newConfig });
       try {
         await pb.collection("device_inbox").create({ device_id: device.id, type: "config_reload" });
@@ -119,7 +271,7 @@ function DeviceCard({ device, onRefresh, showMessage, showError }: DeviceCardPro
         // non-fatal — viewer will pick up changes on next poll
       }
       setSaveSuccess(true);
-      showMessage("Settings saved — viewer will restart shortly.");
+      showMessage("Settings saved — the viewer will apply them in a few seconds.");
       onRefresh(device.id);
     } catch (err) {
       showError(err, "Failed to save settings");
@@ -244,6 +396,40 @@ function DeviceCard({ device, onRefresh, showMessage, showError }: DeviceCardPro
               <SelectItem value="fade" text="Fade" />
               <SelectItem value="crossfade" text="Crossfade" />
               <SelectItem value="cut" text="Cut" />
+              <SelectItem value="slide-left" text="Slide left" />
+              <SelectItem value="slide-right" text="Slide right" />
+              <SelectItem value="slide-up" text="Slide up" />
+              <SelectItem value="slide-down" text="Slide down" />
+              <SelectItem value="push-left" text="Push left" />
+              <SelectItem value="push-right" text="Push right" />
+              <SelectItem value="push-up" text="Push up" />
+              <SelectItem value="push-down" text="Push down" />
+              <SelectItem value="wipe-left" text="Wipe left" />
+              <SelectItem value="wipe-right" text="Wipe right" />
+              <SelectItem value="wipe-up" text="Wipe up" />
+              <SelectItem value="wipe-down" text="Wipe down" />
+              <SelectItem value="wipe-radial" text="Radial wipe" />
+            </Select>
+            <TextInput
+              id={`transition-mix-${device.id}`}
+              labelText="Transition Mix (optional)"
+              helperText="Picked per slide, never the same twice in a row — e.g. fade:2, slide-left, wipe-radial. Overrides the effect above when set."
+              value={transitionMix}
+              onChange={(e) => { setTransitionMix(e.target.value); setSaveSuccess(false); }}
+              size="sm"
+            />
+            <Select
+              id={`transition-easing-${device.id}`}
+              labelText="Transition Easing"
+              value={transitionEasing}
+              onChange={(e) => { setTransitionEasing(e.target.value); setSaveSuccess(false); }}
+              size="sm"
+            >
+              <SelectItem value="" text="Default for effect" />
+              <SelectItem value="linear" text="Linear" />
+              <SelectItem value="ease-in" text="Ease in" />
+              <SelectItem value="ease-out" text="Ease out" />
+              <SelectItem value="ease-in-out" text="Ease in-out" />
             </Select>
             <NumberInput
               id={`transition-duration-${device.id}`}
@@ -261,6 +447,13 @@ function DeviceCard({ device, onRefresh, showMessage, showError }: DeviceCardPro
               onToggle={(val) => { setBlur(val); setSaveSuccess(false); }}
               size="sm"
             />
+            <Toggle
+              id={`ken-burns-${device.id}`}
+              labelText="Ken Burns Pan & Zoom"
+              toggled={kenBurns}
+              onToggle={(val) => { setKenBurns(val); setSaveSuccess(false); }}
+              size="sm"
+            />
             <Toggle
               id={`shuffle-${device.id}`}
               labelText="Shuffle Playlist"
@@ -295,6 +488,36 @@ function DeviceCard({ device, onRefresh, showMessage, showError }: DeviceCardPro
                 />
               </div>
             )}
+            {showClock && (
+              <Select
+                id={`clock-format-${device.id}`}
+                labelText="Clock Format"
+                value={clockFormat}
+                onChange={(e) => { setClockFormat(e.target.value); setSaveSuccess(false); }}
+                size="sm"
+              >
+                <SelectItem value="12h" text="12-hour" />
+                <SelectItem value="24h" text="24-hour" />
+              </Select>
+            )}
+            {showClock && (
+              <TextInput
+                id={`clock-date-format-${device.id}`}
+                labelText="Clock Date Line (strftime, e.g. %A, %d %B — empty hides it)"
+                value={clockDateFormat}
+                onChange={(e) => { setClockDateFormat(e.target.value); setSaveSuccess(false); }}
+                size="sm"
+              />
+            )}
+            {showClock && (
+              <TextInput
+                id={`clock-timezone-${device.id}`}
+                labelText="Clock Time Zone (e.g. Europe/Bratislava — empty uses the system zone)"
+                value={clockTimezone}
+                onChange={(e) => { setClockTimezone(e.target.value); setSaveSuccess(false); }}
+                size="sm"
+              />
+            )}
             <Toggle
               id={`show-info-${device.id}`}
               labelText="Show All Details"
@@ -320,11 +543,121 @@ function DeviceCard({ device, onRefresh, showMessage, showError }: DeviceCardPro
               <SelectItem value="dynamic" text="Dynamic random layout" />
               <SelectItem value="portrait_pairs" text="Portrait pairs" />
             </Select>
+            <Select
+              id={`rotation-${device.id}`}
+              labelText="Screen Rotation (the viewer restarts to apply)"
+              value={rotation}
+              onChange={(e) => { setRotation(Number(e.target.value)); setSaveSuccess(false); }}
+              size="sm"
+            >
+              <SelectItem value={0} text="None" />
+              <SelectItem value={90} text="90° clockwise (portrait)" />
+              <SelectItem value={180} text="180° (upside down)" />
+              <SelectItem value={270} text="270° clockwise (portrait)" />
+            </Select>
+            <TextInput
+              id={`pinned-tags-${device.id}`}
+              labelText="Pinned Tags (comma-separated — media with these tags is always kept offline)"
+              value={pinnedTags}
+              onChange={(e) => { setPinnedTags(e.target.value); setSaveSuccess(false); }}
+              size="sm"
+            />
+            <Select
+              id={`quiet-mode-${device.id}`}
+              labelText="Quiet Hours"
+              value={quietMode}
+              onChange={(e) => { setQuietMode(e.target.value); setSaveSuccess(false); }}
+              size="sm"
+            >
+              <SelectItem value="off" text="Off — slideshow around the clock" />
+              <SelectItem value="blank" text="Blank screen" />
+              <SelectItem value="clock" text="Dimmed clock only" />
+            </Select>
+            {quietMode !== "off" && (
+              <div style={{ display: "grid", gridTemplateColumns: "repeat(auto-fill, minmax(7rem, 1fr))", gap: "0.5rem" }}>
+                {QUIET_DAYS.map(([day, label]) => (
+                  <TextInput
+                    key={day}
+                    id={`quiet-${day}-${device.id}`}
+                    labelText={label}
+                    placeholder="22:00-07:00"
+                    value={quietHours[day] ?? ""}
+                    onChange={(e) => { setQuietHours((prev) => ({ ...prev, [day]: e.target.value })); setSaveSuccess(false); }}
+                    size="sm"
+                  />
+                ))}
+              </div>
+            )}
+            {quietMode !== "off" && (
+              <TextInput
+                id={`quiet-exceptions-${device.id}`}
+                labelText="Quiet Hours Exceptions (e.g. 2026-12-24 01:00-06:00, 2026-12-31 off)"
+                helperText="Each night's window starts on the day it's listed under and may run past midnight. Exceptions replace a date's window."
+                value={quietExceptions}
+                onChange={(e) => { setQuietExceptions(e.target.value); setSaveSuccess(false); }}
+                size="sm"
+              />
+            )}
+            <TextInput
+              id={`dimming-${device.id}`}
+              labelText="Dimming (time=brightness/warmth %, e.g. sunrise=100, sunset+30=60/40, 22:00=25/80)"
+              helperText="Brightness fades between points over the day. Leave empty for full brightness."
+              value={dimming}
+              onChange={(e) => { setDimming(e.target.value); setSaveSuccess(false); }}
+              size="sm"
+            />
+            <div style={{ display: "grid", gridTemplateColumns: "1fr 1fr", gap: "0.5rem" }}>
+              <TextInput
+                id={`latitude-${device.id}`}
+                labelText="Latitude (for sunrise/sunset)"
+                placeholder="48.15"
+                value={latitude}
+                onChange={(e) => { setLatitude(e.target.value); setSaveSuccess(false); }}
+                size="sm"
+              />
+              <TextInput
+                id={`longitude-${device.id}`}
+                labelText="Longitude"
+                placeholder="17.11"
+                value={longitude}
+                onChange={(e) => { setLongitude(e.target.value); setSaveSuccess(false); }}
+                size="sm"
+              />
+            </div>
+            <div style={{ display: "flex", gap: "0.5rem" }}>
+              <NumberInput
+                id={`burn-in-shift-${device.id}`}
+                label="Burn-in Shift (px, 0 = off)"
+                value={burnInShift}
+                min={0}
+                max={20}
+                onChange={(_e, { value }) => { setBurnInShift(Number(value)); setSaveSuccess(false); }}
+                size="sm"
+              />
+              <NumberInput
+                id={`burn-in-refresh-${device.id}`}
+                label="Quiet-hours Refresh (every N min, 0 = off)"
+                value={burnInRefreshMins}
+                min={0}
+                step={30}
+                onChange={(_e, { value }) => { setBurnInRefreshMins(Number(value)); setSaveSuccess(false); }}
+                size="sm"
+              />
+              <NumberInput
+                id={`max-static-${device.id}`}
+                label="Max Time on One Image (s, 0 = no cap)"
+                value={maxStaticSecs}
+                min={0}
+                step={60}
+                onChange={(_e, { value }) => { setMaxStaticSecs(Number(value)); setSaveSuccess(false); }}
+                size="sm"
+              />
+            </div>
           </Stack>
           {saveSuccess && (
             <InlineNotification
               kind="success"
-              title="Settings saved — viewer will restart shortly."
+              title="Settings saved — the viewer will apply them in a few seconds."
               hideCloseButton
               lowContrast
               style={{ marginTop: "1rem" }}
@@ -20,11 +20,42 @@ import {
   Shuffle,
   Filter,
   FilterRemove,
+  Image as ImageIcon,
+  Screen,
+  ScreenOff,
 } from "@carbon/icons-react";
-import type { DeviceRecord } from "../types/pocketbase";
+import type { CommandAck, DeviceInboxRecord, DeviceRecord } from "../types/pocketbase";
 import { isDeviceOnline } from "../utils";
 
 const PAUSE_SECS = 300; // 5 minutes
+const ACK_POLL_MS = 1_000;
+const ACK_TIMEOUT_MS = 15_000;
+
+interface SentCommand {
+  id: string;
+  type: string;
+  sentAt: number;
+  ack?: CommandAck | null;
+  /** Gave up waiting for a final status. */
+  timedOut?: boolean;
+}
+
+function describeCommand(cmd: SentCommand): string {
+  const ack = cmd.ack;
+  if (!ack) {
+    return cmd.timedOut ? "no response from the viewer" : "sent, waiting for the viewer…";
+  }
+  if (ack.status === "failed") return `failed${ack.reason ? `: ${ack.reason}` : ""}`;
+  if (ack.status === "received") return "received";
+  const parts = ["applied"];
+  if (ack.state?.currentMediaId) parts.push(`showing ${ack.state.currentMediaId}`);
+  if (ack.state?.pausedUntil) {
+    parts.push(`paused until ${new Date(ack.state.pausedUntil).toLocaleTimeString()}`);
+  } else if (ack.state?.paused) {
+    parts.push("paused");
+  }
+  return parts.join(" · ");
+}
 
 function formatCountdown(secs: number): string {
   const m = Math.floor(secs / 60);
@@ -38,11 +69,19 @@ export function ViewerControlPage() {
   const [loading, setLoading] = useState(true);
   const [error, setError] = useState<string | null>(null);
   const [sending, setSending] = useState<string | null>(null);
+  const [lastCommand, setLastCommand] = useState<SentCommand | null>(null);
 
   // Pause countdown
   const [pauseSecs, setPauseSecs] = useState<number | null>(null);
   const countdownRef = useRef<ReturnType<typeof setInterval> | null>(null);
 
+  // Show media
+  const [showMediaId, setShowMediaId] = useState("");
+  const [showHoldMins, setShowHoldMins] = useState("");
+
+  // Brightness override
+  const [brightness, setBrightness] = useState("");
+
   // Tag filter
   const [tagInput, setTagInput] = useState("");
   const [selectedTags, setSelectedTags] = useState<string[]>([]);
@@ -87,15 +126,48 @@ export function ViewerControlPage() {
     // eslint-disable-next-line react-hooks/exhaustive-deps
   }, [filterApplied, selectedId]);
 
+  // Poll the last command's inbox record until the viewer reports a final
+  // status (or stops answering).
+  useEffect(() => {
+    if (
+      !lastCommand ||
+      lastCommand.timedOut ||
+      lastCommand.ack?.status === "applied" ||
+      lastCommand.ack?.status === "failed"
+    ) {
+      return;
+    }
+    const { id, sentAt } = lastCommand;
+    const interval = setInterval(async () => {
+      if (Date.now() - sentAt > ACK_TIMEOUT_MS) {
+        clearInterval(interval);
+        setLastCommand((prev) => (prev && prev.id === id ? { ...prev, timedOut: true } : prev));
+        return;
+      }
+      try {
+        const rec = await pb
+          .collection("device_inbox")
+          .getOne<DeviceInboxRecord>(id, { requestKey: null });
+        if (rec.ack) {
+          setLastCommand((prev) => (prev && prev.id === id ? { ...prev, ack: rec.ack } : prev));
+        }
+      } catch {
+        // Transient; try again on the next tick.
+      }
+    }, ACK_POLL_MS);
+    return () => clearInterval(interval);
+  }, [lastCommand]);
+
   const sendCommand = async (type: string, payload?: Record<string, unknown>) => {
     if (!selectedId) return;
     setSending(type);
     setError(null);
     try {
-      await pb.collection("device_inbox").create(
+      const rec = await pb.collection("device_inbox").create<DeviceInboxRecord>(
         { device_id: selectedId, type, payload },
         { requestKey: null },
       );
+      setLastCommand({ id: rec.id, type, sentAt: Date.now() });
     } catch (e) {
       setError(e instanceof Error ? e.message : "Failed to send command");
     } finally {
@@ -130,6 +202,21 @@ export function ViewerControlPage() {
     setPauseSecs(null);
   };
 
+  const handleShow = async () => {
+    const mediaId = showMediaId.trim();
+    if (!mediaId) return;
+    const mins = Number(showHoldMins);
+    const payload: Record<string, unknown> = { mediaId };
+    if (showHoldMins.trim() && mins > 0) payload.holdSecs = Math.round(mins * 60);
+    await sendCommand("show", payload);
+  };
+
+  const handleBrightness = async () => {
+    const percent = Number(brightness);
+    if (!brightness.trim() || !Number.isFinite(percent)) return;
+    await sendCommand("brightness", { brightness: Math.min(100, Math.max(5, percent)) });
+  };
+
   const addTag = () => {
     const tag = tagInput.trim();
     if (tag && !selectedTags.includes(tag)) {
@@ -185,6 +272,7 @@ export function ViewerControlPage() {
                   setPauseSecs(null);
                   setFilterApplied(false);
                   setSelectedTags([]);
+                  setLastCommand(null);
                 }}
                 disabled={loading}
               >
@@ -268,6 +356,109 @@ export function ViewerControlPage() {
               >
                 {sending === "random" ? "Jumping…" : "Random"}
               </Button>
+              {lastCommand && (
+                <p
+                  className="cds--helper-text-01"
+                  style={{
+                    color:
+                      lastCommand.ack?.status === "failed"
+                        ? "var(--cds-text-error)"
+                        : "var(--cds-text-secondary)",
+                  }}
+                >
+                  Last command ({lastCommand.type}): {describeCommand(lastCommand)}
+                </p>
+              )}
+            </div>
+          </Tile>
+
+          {/* Display power */}
+          <Tile>
+            <Heading style={{ fontSize: "1rem", marginBottom: "1rem" }}>Display</Heading>
+            <div style={{ display: "flex", flexDirection: "column", gap: "0.75rem" }}>
+              <div style={{ display: "grid", gridTemplateColumns: "1fr 1fr", gap: "0.75rem" }}>
+                <Button
+                  kind="tertiary"
+                  renderIcon={Screen}
+                  style={{ minHeight: 64, width: "100%" }}
+                  onClick={() => sendCommand("display-on")}
+                  disabled={!selectedId || sending === "display-on"}
+                >
+                  {sending === "display-on" ? "…" : "Display On"}
+                </Button>
+                <Button
+                  kind="tertiary"
+                  renderIcon={ScreenOff}
+                  style={{ minHeight: 64, width: "100%" }}
+                  onClick={() => sendCommand("display-off")}
+                  disabled={!selectedId || sending === "display-off"}
+                >
+                  {sending === "display-off" ? "…" : "Display Off"}
+                </Button>
+              </div>
+              <p className="cds--helper-text-01" style={{ color: "var(--cds-text-secondary)" }}>
+                Overrides the device&apos;s quiet hours until they next start or end.
+              </p>
+              <TextInput
+                id="brightness-percent"
+                labelText="Brightness (%)"
+                helperText="Overrides the dimming schedule until set back."
+                type="number"
+                min={5}
+                max={100}
+                value={brightness}
+                onChange={(e) => setBrightness(e.target.value)}
+              />
+              <div style={{ display: "grid", gridTemplateColumns: "1fr 1fr", gap: "0.75rem" }}>
+                <Button
+                  kind="tertiary"
+                  size="sm"
+                  onClick={handleBrightness}
+                  disabled={!selectedId || !brightness.trim() || sending === "brightness"}
+                >
+                  Set Brightness
+                </Button>
+                <Button
+                  kind="ghost"
+                  size="sm"
+                  onClick={() => sendCommand("brightness")}
+                  disabled={!selectedId || sending === "brightness"}
+                >
+                  Back to Schedule
+                </Button>
+              </div>
+            </div>
+          </Tile>
+
+          {/* Show media */}
+          <Tile>
+            <Heading style={{ fontSize: "1rem", marginBottom: "1rem" }}>Show Media</Heading>
+            <div style={{ display: "flex", flexDirection: "column", gap: "0.75rem" }}>
+              <TextInput
+                id="show-media-id"
+                labelText="Media ID"
+                placeholder="Record ID from the media library"
+                value={showMediaId}
+                onChange={(e) => setShowMediaId(e.target.value)}
+              />
+              <TextInput
+                id="show-hold-mins"
+                labelText="Hold for (minutes)"
+                helperText="Leave empty to use the normal slide interval."
+                type="number"
+                min={0}
+                value={showHoldMins}
+                onChange={(e) => setShowHoldMins(e.target.value)}
+              />
+              <Button
+                kind="primary"
+                renderIcon={ImageIcon}
+                style={{ minHeight: 64, width: "100%" }}
+                onClick={handleShow}
+                disabled={!selectedId || !showMediaId.trim() || sending === "show"}
+              >
+                {sending === "show" ? "Sending…" : "Show Now"}
+              </Button>
             </div>
           </Tile>
 
@@ -72,27 +72,84 @@ export interface MediaRecord extends BaseRecord {
   processingLog?: string[];
   displayUrl?: string;
   thumbUrl?: string;
+  blurUrl?: string;
   videoUrl?: string;
   posterUrl?: string;
 }
 
 export type ApprovalStatus = "approved" | "rejected";
 
+type TransitionDirection = "left" | "right" | "up" | "down";
+
+/**
+ * Transition names understood by the viewer (see `Transition::from_str`).
+ */
+export type TransitionName =
+  | "fade"
+  | "crossfade"
+  | "cut"
+  | `slide-${TransitionDirection}`
+  | `push-${TransitionDirection}`
+  | `wipe-${TransitionDirection}`
+  | "wipe-radial";
+
+export type WeightedTransition = TransitionName | { name: TransitionName; weight?: number };
+
 /**
  * Device configuration
  */
 export interface DeviceConfig {
   interval?: number;
-  transition?: "fade" | "crossfade" | "cut";
+  transition?: TransitionName;
   transitionDuration?: number;
+  transitionEasing?: "" | "linear" | "ease-in" | "ease-out" | "ease-in-out";
+  /** Weighted set picked per slide; bare names weigh 1. */
+  transitions?: WeightedTransition[];
+  /** Per-layout overrides of `transitions`, keyed by layout (e.g. "quad_landscape"). */
+  layoutTransitions?: Record<string, WeightedTransition[]>;
   blur?: boolean;
+  kenBurns?: boolean;
   shuffle?: boolean;
   showClock?: boolean;
+  clockFormat?: "12h" | "24h";
+  clockDateFormat?: string;
+  clockTimezone?: string;
   showInfo?: boolean;
   showLocationInfo?: boolean;
   displayMode?: "single" | "dynamic" | "portrait_pairs";
+  /** Media carrying any of these tags is pinned in the viewer's offline cache. */
+  pinnedTags?: string[];
+  /** What the screen shows during quiet hours; "off" disables them. */
+  quietMode?: "off" | "blank" | "clock";
+  /** Quiet-hours window ("HH:MM-HH:MM") starting on each weekday, keyed "mon".."sun". */
+  quietHours?: Partial<Record<QuietDay, string>>;
+  /** Dates whose window differs from their weekday's; empty `hours` means none. */
+  quietExceptions?: { date: string; hours: string }[];
+  /** Brightness curve over the day; `at` is "HH:MM", "sunrise[±min]" or "sunset[±min]". */
+  dimming?: DimmingPoint[];
+  /** Location for sunrise/sunset dimming points, in degrees (north/east positive). */
+  latitude?: number | null;
+  longitude?: number | null;
+  /** Degrees the picture is turned clockwise for the panel's mounting; the viewer restarts to apply it. */
+  rotation?: 0 | 90 | 180 | 270;
+  /** Pixels the picture drifts around to spread screen wear; 0 is off. */
+  burnInShift?: number;
+  /** Minutes between refresh patterns during quiet hours; 0 is off. */
+  burnInRefreshMins?: number;
+  /** Longest one image stays up, even paused, in seconds; 0 is no cap. */
+  maxStaticSecs?: number;
+}
+
+export interface DimmingPoint {
+  at: string;
+  /** Percent, 5-100. */
+  brightness: number;
+  /** Percent, 0-100. */
+  warmth?: number;
 }
 
+export type QuietDay = "mon" | "tue" | "wed" | "thu" | "fri" | "sat" | "sun";
+
 /**
  * Self-reported viewer telemetry, updated on each heartbeat (~90s cadence).
  * cpuPercent is a delta since the previous heartbeat, not instantaneous, and
@@ -105,6 +162,19 @@ export interface DeviceTelemetry {
   cpuPercent?: number | null;
   rssBytes?: number;
   memAvailableBytes?: number;
+  cacheBytes?: number;
+  cacheMaxBytes?: number;
+  diskFreeBytes?: number | null;
+  diskTotalBytes?: number | null;
+  /** Free space on the cache's filesystem is below the viewer's floor. */
+  diskPressure?: boolean;
+  /** Background sync: "starting", "syncing", "idle", "full" (cache budget reached) or "waiting" (outside sync hours). */
+  syncPhase?: string;
+  /** Playlist items fully cached, out of syncTotal. */
+  syncCached?: number;
+  syncTotal?: number;
+  /** Items waiting to retry after failing to sync. */
+  syncRetrying?: number;
 }
 
 /**
@@ -121,8 +191,25 @@ export interface DeviceRecord extends BaseRecord {
 /**
  * Device inbox record — ephemeral command signals sent to viewer devices.
  */
+/**
+ * A viewer's acknowledgment of an inbox command, stored on the inbox record.
+ */
+export interface CommandAck {
+  status: "received" | "applied" | "failed";
+  reason?: string | null;
+  /** Playback state after the command was handled. */
+  state?: {
+    currentMediaId?: string | null;
+    paused?: boolean;
+    /** End of a timed pause, Unix milliseconds. */
+    pausedUntil?: number | null;
+  } | null;
+  at: string;
+}
+
 export interface DeviceInboxRecord extends BaseRecord {
   device_id: string;
   type?: string;
   payload?: Record<string, unknown>;
+  ack?: CommandAck | null;
 }
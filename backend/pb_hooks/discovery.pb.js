@@ -137,7 +137,11 @@ routerAdd("POST", "/api/spomienka/device-auth", (e) => {
                 interval: cfg.interval ?? 8000,
                 transition: cfg.transition ?? "fade",
                 transitionDuration: cfg.transitionDuration ?? 1000,
+                transitionEasing: cfg.transitionEasing ?? "",
+                transitions: cfg.transitions ?? [],
+                layoutTransitions: cfg.layoutTransitions ?? {},
                 blur: cfg.blur ?? true,
+                kenBurns: cfg.kenBurns ?? false,
                 shuffle: cfg.shuffle ?? false,
                 showClock: cfg.showClock ?? true,
                 showInfo: cfg.showInfo ?? false,
@@ -145,6 +149,20 @@ routerAdd("POST", "/api/spomienka/device-auth", (e) => {
                 displayMode: cfg.displayMode ?? "single",
                 clockOffsetX: cfg.clockOffsetX ?? 0,
                 clockOffsetY: cfg.clockOffsetY ?? 0,
+                clockFormat: cfg.clockFormat ?? "12h",
+                clockDateFormat: cfg.clockDateFormat ?? "",
+                clockTimezone: cfg.clockTimezone ?? "",
+                pinnedTags: cfg.pinnedTags ?? ["favorite", "pinned"],
+                quietMode: cfg.quietMode ?? "off",
+                quietHours: cfg.quietHours ?? {},
+                quietExceptions: cfg.quietExceptions ?? [],
+                dimming: cfg.dimming ?? [],
+                latitude: cfg.latitude ?? null,
+                longitude: cfg.longitude ?? null,
+                rotation: cfg.rotation ?? null,
+                burnInShift: cfg.burnInShift ?? 0,
+                burnInRefreshMins: cfg.burnInRefreshMins ?? 0,
+                maxStaticSecs: cfg.maxStaticSecs ?? 0,
             },
         });
     } catch (err) {
@@ -203,6 +221,72 @@ routerAdd("POST", "/api/spomienka/device-heartbeat", (e) => {
     }
 });
 
+// POST /api/spomienka/command-ack
+// Body: { device_id, api_key, id, status, reason?, state? }
+// Records a viewer's acknowledgment of a device_inbox command on the inbox
+// record itself: "received" on arrival, then "applied" or "failed" with the
+// resulting playback state. Acks can arrive out of order, so a final status
+// is never downgraded back to "received".
+routerAdd("POST", "/api/spomienka/command-ack", (e) => {
+    try {
+        const body = e.requestInfo().body;
+        const deviceId = (body.device_id || "").trim();
+        const apiKey = (body.api_key || "").trim();
+        const inboxId = (body.id || "").trim();
+        const status = body.status;
+
+        if (!deviceId || !apiKey || !inboxId) {
+            throw new BadRequestError("Missing device_id, api_key or id");
+        }
+        if (!["received", "applied", "failed"].includes(status)) {
+            throw new BadRequestError("Invalid status");
+        }
+
+        let device;
+        try {
+            device = $app.findRecordById("devices", deviceId);
+        } catch (_) {
+            throw new UnauthorizedError("Device not found");
+        }
+
+        const utils = require(__hooks + "/utils.js");
+        if (utils.hashApiKey(apiKey) !== device.getString("apiKey")) {
+            throw new UnauthorizedError("Invalid API key");
+        }
+
+        let command;
+        try {
+            command = $app.findRecordById("device_inbox", inboxId);
+        } catch (_) {
+            throw new NotFoundError("Command not found");
+        }
+        if (command.getString("device_id") !== deviceId) {
+            throw new NotFoundError("Command not found");
+        }
+
+        let previous = null;
+        try { previous = JSON.parse(command.getString("ack") || "null"); } catch (_) {}
+        if (status === "received" && previous && previous.status && previous.status !== "received") {
+            e.json(200, { status: "ok" });
+            return;
+        }
+
+        const state = body.state;
+        command.set("ack", {
+            status: status,
+            reason: typeof body.reason === "string" ? body.reason.slice(0, 500) : null,
+            state: state && typeof state === "object" && !Array.isArray(state) ? state : null,
+            at: new Date().toISOString(),
+        });
+        $app.save(command);
+
+        e.json(200, { status: "ok" });
+    } catch (err) {
+        if (err && err.code) throw err;
+        throw new BadRequestError("Command ack failed: " + String(err));
+    }
+});
+
 // GET /api/spomienka/pending  (admin only)
 // Returns unclaimed viewers that have announced in the last 10 minutes.
 routerAdd("GET", "/api/spomienka/pending", (e) => {
@@ -431,7 +515,7 @@ routerAdd("POST", "/api/spomienka/reprocess", (e) => {
     // Delete old processed derivatives so the hook regenerates them cleanly.
     const collectionId = record.collection().id;
     const storagePath = $app.dataDir() + "/storage/" + collectionId + "/" + id;
-    const derived = ["display_", "thumb_", "poster_"];
+    const derived = ["display_", "thumb_", "blur_", "poster_"];
     try {
         const files = $os.readdir(storagePath);
         for (const file of files) {
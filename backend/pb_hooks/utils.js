@@ -109,10 +109,10 @@ function execCommand(cmd, args) {
 }
 
 function buildFileUrl(collectionId, recordId, fileName) {
-    // Derived files (display_, thumb_, poster_, video_) are placed on disk
+    // Derived files (display_, thumb_, blur_, poster_, video_) are placed on disk
     // by the processing hook and served via a custom route because PocketBase's
     // /api/files/ endpoint only serves files registered in file-type schema fields.
-    const DERIVED_PREFIXES = ["display_", "thumb_", "poster_", "video_"];
+    const DERIVED_PREFIXES = ["display_", "thumb_", "blur_", "poster_", "video_"];
     const isDerived = DERIVED_PREFIXES.some((p) => fileName.startsWith(p));
     if (isDerived) {
         return "/api/spomienka/media/" + collectionId + "/" + recordId + "/" + fileName;
@@ -227,6 +227,11 @@ function generateChecksum(filePath) {
 
 const FFMPEG_DISPLAY_SCALE = "scale='min(1920,iw)':'min(1080,ih)':force_original_aspect_ratio=decrease";
 const FFMPEG_THUMB_SCALE   = "scale=300:-1";
+// Backdrop for letterboxed slides. Deliberately stretched to a fixed 480x270
+// and blurred at sigma 8 — the same recipe the viewer uses when it has to
+// blur on the CPU, so server and local backdrops look identical. The viewer
+// scales it up to the screen on the GPU.
+const FFMPEG_BLUR_FILTER   = "scale=480:270,gblur=sigma=8";
 // Bounding-box scale (not a fixed width) so portrait video isn't blown up:
 // ffmpeg auto-rotates portrait phone footage before this filter runs, which
 // swaps its effective width/height — a fixed "scale=1920:-2" then forces the
@@ -293,6 +298,19 @@ function processImage(record, originalPath, procDir, storagePath, steps) {
         console.error("Thumbnail failed:", err);
         steps.push("Thumbnail generation failed: " + (err.message || err));
     }
+
+    try {
+        const blurPath = procDir + "/blur.png";
+        execCommand(FFMPEG, ["-y", "-i", originalPath, "-map", "0:v:0", "-vf", FFMPEG_BLUR_FILTER, blurPath]);
+        const name = "blur_" + recordId + ".png";
+        $os.rename(blurPath, storagePath + "/" + name);
+        record.set("blurUrl", buildFileUrl(collectionId, recordId, name));
+        steps.push("Blurred backdrop generated");
+    } catch (err) {
+        // Non-fatal: viewers blur locally when blurUrl is missing.
+        console.error("Blurred backdrop failed:", err);
+        steps.push("Blurred backdrop generation failed: " + (err.message || err));
+    }
 }
 
 function processVideo(record, originalPath, procDir, storagePath, steps) {
@@ -362,9 +380,23 @@ function processVideo(record, originalPath, procDir, storagePath, steps) {
         console.error("Video thumbnail failed:", err);
         steps.push("Thumbnail generation failed: " + (err.message || err));
     }
+
+    try {
+        const blurPath = procDir + "/blur.png";
+        execCommand(FFMPEG, ["-y", "-i", originalPath, "-ss", "00:00:01", "-vframes", "1",
+            "-vf", FFMPEG_BLUR_FILTER, blurPath]);
+        const name = "blur_" + recordId + ".png";
+        $os.rename(blurPath, storagePath + "/" + name);
+        record.set("blurUrl", buildFileUrl(collectionId, recordId, name));
+        steps.push("Blurred backdrop generated");
+    } catch (err) {
+        // Non-fatal: viewers blur the poster locally when blurUrl is missing.
+        console.error("Video blurred backdrop failed:", err);
+        steps.push("Blurred backdrop generation failed: " + (err.message || err));
+    }
 }
 
-// Copies derived files (display/thumb/poster/video) from an existing
+// Copies derived files (display/thumb/blur/poster/video) from an existing
 // duplicate record instead of re-running ffmpeg/exiftool. Returns false
 // (and leaves the record untouched) if anything's missing, so the caller
 // falls back to full processing.
@@ -372,17 +404,21 @@ function reuseDuplicateProcessing(record, existing, mediaType, collectionId, sto
     const existingStoragePath = $app.dataDir() + "/storage/" + collectionId + "/" + existing.id;
     const recordId = record.id;
 
+    // The fourth element marks optional files: records processed before
+    // blurred backdrops existed have none, and that shouldn't force a full
+    // reprocess — viewers blur locally without one.
     const fileSpecs = mediaType === "video"
-        ? [["video_", ".mp4", "videoUrl"], ["poster_", ".png", "posterUrl"], ["thumb_", ".png", "thumbUrl"]]
-        : [["display_", ".png", "displayUrl"], ["thumb_", ".png", "thumbUrl"]];
+        ? [["video_", ".mp4", "videoUrl"], ["poster_", ".png", "posterUrl"], ["thumb_", ".png", "thumbUrl"], ["blur_", ".png", "blurUrl", true]]
+        : [["display_", ".png", "displayUrl"], ["thumb_", ".png", "thumbUrl"], ["blur_", ".png", "blurUrl", true]];
 
-    for (const [prefix, ext, field] of fileSpecs) {
+    for (const [prefix, ext, field, optional] of fileSpecs) {
         const srcPath = existingStoragePath + "/" + prefix + existing.id + ext;
         const dstPath = storagePath + "/" + prefix + recordId + ext;
         try {
             execCommand(CP_CMD, [srcPath, dstPath]);
             record.set(field, buildFileUrl(collectionId, recordId, prefix + recordId + ext));
         } catch (err) {
+            if (optional) continue;
             console.error("Failed to copy derived file for duplicate", recordId, ":", err.message || err);
             return false;
         }
@@ -512,7 +548,7 @@ function processMediaRecord(record) {
         if (processingFailed) {
             try {
                 const files = $os.readdir(storagePath);
-                const derived = ["display_", "thumb_", "video_", "poster_"];
+                const derived = ["display_", "thumb_", "blur_", "video_", "poster_"];
                 for (const file of files) {
                     for (const prefix of derived) {
                         if (file.startsWith(prefix)) {
@@ -0,0 +1,17 @@
+/// <reference path="../pb_data/types.d.ts" />
+migrate((app) => {
+  const col = app.findCollectionByNameOrId("device_inbox");
+  col.fields.add(new JSONField({
+    id: "di_ack",
+    name: "ack",
+    required: false,
+    system: false,
+    hidden: false,
+    presentable: false,
+  }));
+  return app.save(col);
+}, (app) => {
+  const col = app.findCollectionByNameOrId("device_inbox");
+  col.fields.removeById("di_ack");
+  return app.save(col);
+});
@@ -2,14 +2,17 @@
 //!
 //! Handles downloading assets from PocketBase and loading them into textures.
 
-use crate::cache::Cache;
-use crate::renderer::{MediaTextures, Renderer};
+use crate::cache::{AssetRef, Cache};
+use crate::decode::{Backdrop, DecodePool, DecodeRequest};
+use crate::renderer::{KenBurns, MediaTextures, Renderer};
+use crate::throttle::Throttle;
 use anyhow::Result;
 use sdl2::render::TextureCreator;
 use sdl2::video::WindowContext;
+use std::collections::HashSet;
 use std::path::{Path, PathBuf};
-use std::sync::Arc;
-use tokio::sync::RwLock;
+use std::sync::{Arc, Mutex};
+use tokio::sync::{Notify, RwLock};
 
 /// Represents a media item from the playlist.
 #[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
@@ -25,6 +28,11 @@ pub struct Media {
     pub display_url: Option<String>,
     pub video_url: Option<String>,
     pub poster_url: Option<String>,
+    /// Server-rendered blurred backdrop (small; stretched to the screen).
+    pub blur_url: Option<String>,
+    pub thumb_url: Option<String>,
+    /// SHA-256 of the original upload (not of the derived files).
+    pub checksum: Option<String>,
     pub duration: Option<f32>,
     pub tags: Option<serde_json::Value>,
     pub device_scopes: Option<serde_json::Value>,
@@ -41,6 +49,8 @@ pub struct Media {
     pub f_number: Option<String>,
     pub exposure_time: Option<String>,
     pub iso: Option<String>,
+    /// PocketBase's last-modified stamp for the record.
+    pub updated: Option<String>,
 }
 
 impl Media {
@@ -62,12 +72,24 @@ impl Media {
         self.media_type == "video"
     }
 
+    /// Whether this item carries any of `tags` (case-insensitive).
+    pub fn has_any_tag(&self, tags: &[String]) -> bool {
+        let Some(own) = self.tags.as_ref().and_then(|v| v.as_array()) else {
+            return false;
+        };
+        own.iter()
+            .filter_map(|v| v.as_str())
+            .any(|t| tags.iter().any(|p| p.eq_ignore_ascii_case(t)))
+    }
+
     /// Return the processed URL for the given asset type, or None if absent/empty.
     pub fn url_for_asset(&self, asset_type: AssetType) -> Option<&str> {
         match asset_type {
             AssetType::Display => self.display_url.as_deref(),
             AssetType::Video => self.video_url.as_deref(),
             AssetType::Poster => self.poster_url.as_deref(),
+            AssetType::Blur => self.blur_url.as_deref(),
+            AssetType::Thumb => self.thumb_url.as_deref(),
         }
         .filter(|s| !s.is_empty())
     }
@@ -79,6 +101,8 @@ pub enum AssetType {
     Display,
     Video,
     Poster,
+    Blur,
+    Thumb,
 }
 
 impl AssetType {
@@ -87,19 +111,36 @@ impl AssetType {
             AssetType::Display => "display",
             AssetType::Video => "video",
             AssetType::Poster => "poster",
+            AssetType::Blur => "blur",
+            AssetType::Thumb => "thumb",
+        }
+    }
+
+    /// Inverse of `as_str`, for cache file names.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "display" => Some(AssetType::Display),
+            "video" => Some(AssetType::Video),
+            "poster" => Some(AssetType::Poster),
+            "blur" => Some(AssetType::Blur),
+            "thumb" => Some(AssetType::Thumb),
+            _ => None,
         }
     }
 
     pub fn extension(&self) -> &'static str {
         match self {
-            AssetType::Display | AssetType::Poster => "png",
+            AssetType::Display | AssetType::Poster | AssetType::Blur | AssetType::Thumb => "png",
             AssetType::Video => "mp4",
         }
     }
 }
 
 fn is_image_asset(asset_type: AssetType) -> bool {
-    matches!(asset_type, AssetType::Display | AssetType::Poster)
+    matches!(
+        asset_type,
+        AssetType::Display | AssetType::Poster | AssetType::Blur | AssetType::Thumb
+    )
 }
 
 fn is_supported_raw_extension(url: &str) -> bool {
@@ -111,8 +152,21 @@ fn is_supported_raw_extension(url: &str) -> bool {
         || lower.ends_with(".gif")
 }
 
+/// Read the first bytes of a file for format sniffing.
+fn read_magic(path: &Path) -> Option<Vec<u8>> {
+    use std::io::Read;
+    // The magic bytes are all we need — don't read whole photos on the
+    // render thread just to sniff them.
+    let mut bytes = vec![0u8; 12];
+    let n = std::fs::File::open(path)
+        .and_then(|mut f| f.read(&mut bytes))
+        .ok()?;
+    bytes.truncate(n);
+    Some(bytes)
+}
+
 fn is_supported_image_file(path: &Path) -> bool {
-    let Ok(bytes) = std::fs::read(path) else {
+    let Some(bytes) = read_magic(path) else {
         return false;
     };
 
@@ -123,16 +177,93 @@ fn is_supported_image_file(path: &Path) -> bool {
         || bytes.starts_with(b"GIF89a")
 }
 
+/// Cheap sanity check that a cached file is what its asset type says it is:
+/// an image we can decode, or an MP4 (`ftyp` box first). Catches HTML error
+/// pages and files cut off before their header.
+pub fn is_valid_asset_file(path: &Path, asset_type: AssetType) -> bool {
+    if is_image_asset(asset_type) {
+        return is_supported_image_file(path);
+    }
+    read_magic(path).is_some_and(|b| b.get(4..8) == Some(b"ftyp"))
+}
+
 /// Manages asset loading and preloading.
 pub struct AssetManager {
     cache: Arc<RwLock<Cache>>,
     pb_url: String,
+    decoder: Arc<DecodePool>,
+    throttle: Arc<Throttle>,
+    /// Assets being downloaded (cache keys), so two tasks never fetch the
+    /// same one into the same `.part` file.
+    in_flight: Mutex<HashSet<String>>,
+    /// Signalled whenever a download leaves `in_flight`.
+    in_flight_done: Notify,
+}
+
+/// Claim on downloading one asset; released on drop.
+struct InFlight<'a> {
+    manager: &'a AssetManager,
+    key: String,
+}
+
+impl Drop for InFlight<'_> {
+    fn drop(&mut self) {
+        self.manager
+            .in_flight
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(&self.key);
+        self.manager.in_flight_done.notify_waiters();
+    }
 }
 
 impl AssetManager {
     /// Create a new asset manager.
-    pub fn new(cache: Arc<RwLock<Cache>>, pb_url: String) -> Self {
-        Self { cache, pb_url }
+    pub fn new(
+        cache: Arc<RwLock<Cache>>,
+        pb_url: String,
+        decoder: Arc<DecodePool>,
+        throttle: Arc<Throttle>,
+    ) -> Self {
+        Self {
+            cache,
+            pb_url,
+            decoder,
+            throttle,
+            in_flight: Mutex::new(HashSet::new()),
+            in_flight_done: Notify::new(),
+        }
+    }
+
+    /// Wait until nobody else is downloading this asset, then claim it.
+    async fn claim(&self, media_id: &str, asset_type: AssetType) -> InFlight<'_> {
+        let key = format!("{}:{}", media_id, asset_type.as_str());
+        loop {
+            // Register for the wakeup before checking, so a release in
+            // between isn't missed.
+            let done = self.in_flight_done.notified();
+            tokio::pin!(done);
+            done.as_mut().enable();
+            if self
+                .in_flight
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .insert(key.clone())
+            {
+                return InFlight { manager: self, key };
+            }
+            done.await;
+        }
+    }
+
+    /// The pool decoding upcoming slides off the render thread.
+    pub fn decoder(&self) -> &DecodePool {
+        &self.decoder
+    }
+
+    /// The limits every download goes through.
+    pub fn throttle(&self) -> &Throttle {
+        &self.throttle
     }
 
     /// Get the full URL for an asset.
@@ -144,6 +275,49 @@ impl AssetManager {
         }
     }
 
+    /// Whether every asset `preload_media` would fetch for this item is
+    /// cached, or failed recently enough not to be retried yet. Only
+    /// consults the cache index, so it's cheap enough to run over the whole
+    /// playlist.
+    pub async fn is_fully_cached(&self, media: &Media) -> bool {
+        let display = media.url_for_asset(AssetType::Display).map(str::to_string);
+        let display = display.or_else(|| {
+            // Mirrors ensure_cached's raw-file fallback for still images.
+            (!media.is_video())
+                .then(|| media.raw_file_url())
+                .flatten()
+                .filter(|u| is_supported_raw_extension(u))
+        });
+        let mut wanted: Vec<(AssetType, String)> = Vec::new();
+        if let Some(url) = display {
+            wanted.push((AssetType::Display, url));
+        }
+        let mut optional = Vec::new();
+        if media.is_video() {
+            optional.push(AssetType::Poster);
+            // Not wanted yet on a metered connection outside off-peak hours.
+            if self.throttle.video_allowed() {
+                optional.push(AssetType::Video);
+            }
+        }
+        optional.push(if media.url_for_asset(AssetType::Blur).is_some() {
+            AssetType::Blur
+        } else {
+            AssetType::Thumb
+        });
+        for asset_type in optional {
+            if let Some(url) = media.url_for_asset(asset_type) {
+                wanted.push((asset_type, url.to_string()));
+            }
+        }
+
+        let cache = self.cache.read().await;
+        wanted.iter().all(|(asset_type, url)| {
+            cache.get_cached_path(&media.id, *asset_type).is_some()
+                || cache.is_backing_off(&self.full_url(url), media.updated.as_deref())
+        })
+    }
+
     /// Ensure an asset is cached, downloading if necessary.
     pub async fn ensure_cached(
         &self,
@@ -194,7 +368,11 @@ impl AssetManager {
                     );
                     return Ok(None);
                 }
-                AssetType::Display | AssetType::Video | AssetType::Poster => {
+                AssetType::Display
+                | AssetType::Video
+                | AssetType::Poster
+                | AssetType::Blur
+                | AssetType::Thumb => {
                     return Ok(None);
                 }
             },
@@ -202,41 +380,73 @@ impl AssetManager {
 
         let full_url = self.full_url(url);
 
-        // Check if already cached or permanently failed
+        // The backend's checksum is of the original upload, so it can only
+        // verify the raw-file fallback. Derived files are checked for length
+        // and format instead (see Cache::download_and_cache).
+        let expected_sha256 = if processed_url.is_none() {
+            media.checksum.as_deref().filter(|s| !s.is_empty())
+        } else {
+            None
+        };
+
+        // Check if already cached or recently failed
         {
             let cache = self.cache.read().await;
-            if cache.is_permanently_failed(&full_url) {
+            if cache.is_backing_off(&full_url, media.updated.as_deref()) {
                 tracing::debug!(
-                    "Skipping permanently-failed {} for {}",
+                    "Skipping recently failed {} for {}",
                     asset_type.as_str(),
                     media.id
                 );
                 return Ok(None);
             }
+            if let Some(path) = cache.get_cached_path(&media.id, asset_type) {
+                if path.exists() && is_valid_asset_file(&path, asset_type) {
+                    return Ok(Some(path));
+                }
+            }
+        }
+
+        if asset_type == AssetType::Video && !self.throttle.video_allowed() {
+            tracing::debug!(
+                "Metered connection: leaving video for {} until off-peak hours",
+                media.id
+            );
+            return Ok(None);
+        }
+
+        // If another task was already fetching this asset, it's cached by
+        // the time we get the claim. Only quarantine a bad file while
+        // holding it, so nobody's fresh download gets thrown away.
+        let _claim = self.claim(&media.id, asset_type).await;
+        {
+            let mut cache = self.cache.write().await;
             if let Some(path) = cache.get_cached_path(&media.id, asset_type) {
                 if path.exists() {
-                    if is_image_asset(asset_type) && !is_supported_image_file(&path) {
-                        tracing::warn!(
-                            "Discarding invalid cached {} image for {}: {:?}",
-                            asset_type.as_str(),
-                            media.id,
-                            path
-                        );
-                        if let Err(e) = std::fs::remove_file(&path) {
-                            tracing::warn!("Failed to remove invalid cached image: {}", e);
-                        }
-                    } else {
+                    if is_valid_asset_file(&path, asset_type) {
                         return Ok(Some(path));
                     }
+                    cache.quarantine(&media.id, asset_type, "failed format check");
                 }
             }
         }
 
         // Download and cache
-        let mut cache = self.cache.write().await;
-        let path = cache
-            .download_and_cache(client, &full_url, &media.id, asset_type, token)
-            .await?;
+        let _slot = self.throttle.slot().await;
+        let path = Cache::download_and_cache(
+            &self.cache,
+            &self.throttle,
+            client,
+            &full_url,
+            AssetRef {
+                media_id: &media.id,
+                asset_type,
+                media_updated: media.updated.as_deref(),
+            },
+            token,
+            expected_sha256,
+        )
+        .await?;
 
         Ok(Some(path))
     }
@@ -273,10 +483,87 @@ impl AssetManager {
             }
         }
 
+        // Server-rendered backdrop, so the Pi doesn't have to blur on the CPU.
+        // Without one, the thumbnail is a much cheaper source for the local
+        // blur than the full display image.
+        let backdrop = if media.url_for_asset(AssetType::Blur).is_some() {
+            AssetType::Blur
+        } else {
+            AssetType::Thumb
+        };
+        if let Err(e) = self.ensure_cached(media, backdrop, client, token).await {
+            tracing::warn!(
+                "Failed to cache {} for {}: {}",
+                backdrop.as_str(),
+                media.id,
+                e
+            );
+        }
+
         Ok(())
     }
 
-    /// Load textures for a media item into SDL2 textures.
+    /// Work out which cached files make up a media item's slide: the display
+    /// image (poster for videos) and the best available backdrop source.
+    /// Invalid cached display images are discarded so they get refetched.
+    fn decode_request(&self, media: &Media, cache: &Cache) -> DecodeRequest {
+        let display_asset = if media.is_video() {
+            AssetType::Poster
+        } else {
+            AssetType::Display
+        };
+
+        let mut display = cache
+            .get_cached_path(&media.id, display_asset)
+            .filter(|p| p.exists());
+        if let Some(ref path) = display {
+            if !is_supported_image_file(path) {
+                tracing::warn!("Discarding invalid cached display image: {:?}", path);
+                if let Err(e) = std::fs::remove_file(path) {
+                    tracing::warn!("Failed to remove invalid cached display image: {}", e);
+                }
+                display = None;
+            }
+        }
+
+        // Prefer the server-rendered backdrop: decoding a small PNG is far
+        // cheaper than blurring on the CPU. Otherwise blur the thumbnail when
+        // we have one, the display image as a last resort.
+        let cached_image = |asset_type: AssetType| {
+            cache
+                .get_cached_path(&media.id, asset_type)
+                .filter(|p| p.exists() && is_supported_image_file(p))
+        };
+        let backdrop = cached_image(AssetType::Blur)
+            .map(Backdrop::Ready)
+            .or_else(|| {
+                cached_image(AssetType::Thumb)
+                    .or_else(|| display.clone())
+                    .map(Backdrop::Generate)
+            });
+
+        DecodeRequest {
+            media_id: media.id.clone(),
+            display,
+            backdrop,
+        }
+    }
+
+    /// Queue a cached media item for background decoding, so its textures
+    /// are ready to upload by the time it comes up.
+    pub async fn queue_decode(&self, media: &Media) {
+        let request = {
+            let cache = self.cache.read().await;
+            self.decode_request(media, &cache)
+        };
+        if request.display.is_some() || request.backdrop.is_some() {
+            self.decoder.request(request);
+        }
+    }
+
+    /// Load textures for a media item into SDL2 textures. Pixels come from
+    /// the decode pool (normally decoded ahead by the preloader); this only
+    /// uploads them.
     pub fn load_textures<'a>(
         &self,
         renderer: &Renderer,
@@ -286,58 +573,29 @@ impl AssetManager {
     ) -> Result<MediaTextures<'a>> {
         let mut textures = MediaTextures::new();
 
-        // Load display texture (or poster for videos)
-        let display_asset = if media.is_video() {
-            AssetType::Poster
-        } else {
-            AssetType::Display
-        };
-
-        let display_path = cache.get_cached_path(&media.id, display_asset);
-
-        if let Some(ref path) = display_path {
-            if path.exists() {
-                if !is_supported_image_file(path) {
-                    tracing::warn!("Discarding invalid cached display image: {:?}", path);
-                    if let Err(e) = std::fs::remove_file(path) {
-                        tracing::warn!("Failed to remove invalid cached display image: {}", e);
-                    }
-                } else {
-                    match renderer.load_texture_from_file(texture_creator, path) {
-                        Ok((tex, width, height)) => {
-                            textures.display = Some(tex);
-                            textures.display_size = Some((width, height));
-                        }
-                        Err(e) => {
-                            let dims = image::image_dimensions(path)
-                                .map(|(w, h)| format!("{}x{}", w, h))
-                                .unwrap_or_else(|_| "unknown".to_string());
-                            tracing::warn!(
-                                "Failed to load display texture ({:?}, {}): {}",
-                                path,
-                                dims,
-                                e
-                            );
-                        }
+        let request = self.decode_request(media, cache);
+        if request.display.is_none() && request.backdrop.is_none() {
+            return Ok(textures);
+        }
+        let decoded = self.decoder.get(&request)?;
+
+        if let Some(ref image) = decoded.display {
+            match renderer.upload_image(texture_creator, image) {
+                Ok(tex) => {
+                    textures.display = Some(tex);
+                    textures.display_size = Some((image.width, image.height));
+                    if !media.is_video() {
+                        textures.ken_burns = Some(KenBurns::random());
                     }
                 }
+                Err(e) => tracing::warn!("Failed to upload display texture: {}", e),
             }
         }
 
-        // Generate blur dynamically from the display image on the CPU.
-        // Runs once per image load (~16ms on RPi4) — imperceptible during a transition.
-        if let Some(ref path) = display_path {
-            if path.exists() {
-                let t = std::time::Instant::now();
-                match renderer.generate_blur_texture(texture_creator, path) {
-                    Ok(tex) => {
-                        tracing::debug!("Generated Gaussian blur in {:?}", t.elapsed());
-                        textures.blur = Some(tex);
-                    }
-                    Err(e) => {
-                        tracing::warn!("Failed to generate blur texture: {}", e);
-                    }
-                }
+        if let Some(ref image) = decoded.blur {
+            match renderer.upload_image(texture_creator, image) {
+                Ok(tex) => textures.blur = Some(tex),
+                Err(e) => tracing::warn!("Failed to upload blur texture: {}", e),
             }
         }
 
@@ -360,7 +618,38 @@ impl Preloader {
         }
     }
 
-    /// Preload the next N items in the playlist.
+    /// Download pinned media that isn't cached yet. Runs ahead of any other
+    /// preloading: pinned media is what has to be there when the network
+    /// isn't. Only the cache index is checked, so this is cheap once
+    /// everything pinned is on disk.
+    pub async fn preload_pinned(&self, playlist: &[Media], token: Option<&str>) {
+        let pinned: Vec<&Media> = {
+            let cache = self.asset_manager.cache.read().await;
+            playlist.iter().filter(|m| cache.is_pinned(&m.id)).collect()
+        };
+        let mut missing = Vec::new();
+        for media in pinned {
+            if !self.asset_manager.is_fully_cached(media).await {
+                missing.push(media);
+            }
+        }
+        if missing.is_empty() {
+            return;
+        }
+
+        tracing::info!("Fetching {} pinned media items...", missing.len());
+        for media in missing {
+            if let Err(e) = self
+                .asset_manager
+                .preload_media(media, &self.client, token)
+                .await
+            {
+                tracing::warn!("Failed to preload pinned {}: {}", media.id, e);
+            }
+        }
+    }
+
+    /// Preload the next N items in the playlist, after any pinned media.
     pub async fn preload_next(
         &self,
         playlist: &[Media],
@@ -374,6 +663,8 @@ impl Preloader {
             return;
         }
 
+        self.preload_pinned(playlist, token).await;
+
         for i in 1..=count {
             let next_index = (current_index + i) % playlist.len();
             if next_index == current_index {
@@ -390,11 +681,13 @@ impl Preloader {
             {
                 tracing::warn!("Failed to preload {}: {}", media.id, e);
             }
+            self.asset_manager.queue_decode(media).await;
         }
     }
 
-    /// Preload all items in the playlist (for initial sync).
+    /// Preload all items in the playlist (for initial sync), pinned first.
     pub async fn preload_all(&self, playlist: &[Media], token: Option<&str>) {
+        self.preload_pinned(playlist, token).await;
         tracing::info!("Preloading {} media items...", playlist.len());
         for (i, media) in playlist.iter().enumerate() {
             tracing::debug!("Preloading {}/{}: {}", i + 1, playlist.len(), media.id);
@@ -0,0 +1,232 @@
+//! Time-of-day brightness and colour temperature.
+//!
+//! A curve of points over the day, each a brightness and a warmth, some of
+//! them tied to sunrise or sunset worked out locally from the configured
+//! latitude/longitude. The renderer multiplies every frame by the shade the
+//! curve gives for the current time.
+
+use crate::schedule::{local_day, parse_hhmm};
+use serde::Deserialize;
+
+/// Lowest brightness the picture is dimmed to, so a dimmed frame never
+/// looks switched off.
+const MIN_BRIGHTNESS: f32 = 0.05;
+
+/// How bright and how warm the picture is drawn.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Shade {
+    /// 1.0 is the picture as is.
+    pub brightness: f32,
+    /// 0.0 is neutral, 1.0 a candle-like warm tint.
+    pub warmth: f32,
+}
+
+impl Default for Shade {
+    fn default() -> Self {
+        Self::FULL
+    }
+}
+
+impl Shade {
+    /// Full brightness, no tint.
+    pub const FULL: Shade = Shade {
+        brightness: 1.0,
+        warmth: 0.0,
+    };
+
+    /// From percentages, clamped to the usable range.
+    pub fn from_percent(brightness: f32, warmth: f32) -> Self {
+        Self {
+            brightness: (brightness / 100.0).clamp(MIN_BRIGHTNESS, 1.0),
+            warmth: (warmth / 100.0).clamp(0.0, 1.0),
+        }
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.brightness >= 1.0 && self.warmth <= 0.0
+    }
+
+    /// The colour every pixel is multiplied by: dimmed evenly, with blue
+    /// and then green taken down for warmth.
+    pub fn modulation(&self) -> (u8, u8, u8) {
+        let channel = |factor: f32| (255.0 * self.brightness * factor).round() as u8;
+        (
+            channel(1.0),
+            channel(1.0 - 0.25 * self.warmth),
+            channel(1.0 - 0.55 * self.warmth),
+        )
+    }
+
+    fn lerp(self, other: Shade, t: f32) -> Shade {
+        Shade {
+            brightness: self.brightness + (other.brightness - self.brightness) * t,
+            warmth: self.warmth + (other.warmth - self.warmth) * t,
+        }
+    }
+}
+
+/// One point of the brightness curve, as configured.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct ShadePoint {
+    /// "HH:MM", or "sunrise"/"sunset" with an optional offset in minutes,
+    /// e.g. "sunset+30".
+    pub at: String,
+    /// Percent, 5-100.
+    pub brightness: f32,
+    /// Percent, 0-100.
+    #[serde(default)]
+    pub warmth: f32,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Anchor {
+    /// Minutes since local midnight.
+    Clock(u32),
+    /// Minutes after (or before, if negative) sunrise.
+    Sunrise(i32),
+    Sunset(i32),
+}
+
+impl Anchor {
+    fn parse(s: &str) -> Option<Self> {
+        let s = s.trim().to_ascii_lowercase();
+        let relative = |rest: &str| -> Option<i32> {
+            let rest = rest.trim();
+            if rest.is_empty() {
+                Some(0)
+            } else {
+                rest.strip_prefix('+').unwrap_or(rest).trim().parse().ok()
+            }
+        };
+        if let Some(rest) = s.strip_prefix("sunrise") {
+            relative(rest).map(Anchor::Sunrise)
+        } else if let Some(rest) = s.strip_prefix("sunset") {
+            relative(rest).map(Anchor::Sunset)
+        } else {
+            parse_hhmm(&s).map(Anchor::Clock)
+        }
+    }
+}
+
+/// Brightness over the day: linear between points, wrapping past midnight.
+#[derive(Debug, Clone)]
+pub struct ShadeCurve {
+    points: Vec<(Anchor, Shade)>,
+    /// (latitude, longitude) in degrees, north and east positive.
+    location: Option<(f64, f64)>,
+}
+
+impl ShadeCurve {
+    /// Build from `dimming` and the location; `None` if there are no usable
+    /// points. Invalid points, and sun-relative ones without a location,
+    /// are logged and skipped.
+    pub fn from_settings(
+        points: &[ShadePoint],
+        latitude: Option<f64>,
+        longitude: Option<f64>,
+    ) -> Option<Self> {
+        let location = latitude.zip(longitude);
+        let points: Vec<_> = points
+            .iter()
+            .filter_map(|point| {
+                let anchor = Anchor::parse(&point.at);
+                match anchor {
+                    None => {
+                        tracing::warn!("Ignoring dimming point at {:?} (want HH:MM, sunrise or sunset)", point.at);
+                        None
+                    }
+                    Some(Anchor::Sunrise(_) | Anchor::Sunset(_)) if location.is_none() => {
+                        tracing::warn!(
+                            "Ignoring dimming point at {:?}: sunrise/sunset need latitude and longitude",
+                            point.at
+                        );
+                        None
+                    }
+                    Some(anchor) => {
+                        Some((anchor, Shade::from_percent(point.brightness, point.warmth)))
+                    }
+                }
+            })
+            .collect();
+        (!points.is_empty()).then_some(Self { points, location })
+    }
+
+    /// The shade for the current local time.
+    pub fn shade_now(&self) -> Shade {
+        let today = local_day(0);
+        let sun = self.location.and_then(|(lat, lon)| {
+            sun_times(
+                lat,
+                lon,
+                today.year_day,
+                today.utc_offset_secs as f64 / 60.0,
+            )
+        });
+        let mut resolved: Vec<(f64, Shade)> = self
+            .points
+            .iter()
+            .filter_map(|&(anchor, shade)| {
+                let minute = match anchor {
+                    Anchor::Clock(m) => m as f64,
+                    // Polar day or night: no sunrise/sunset to follow.
+                    Anchor::Sunrise(offset) => sun?.0 + offset as f64,
+                    Anchor::Sunset(offset) => sun?.1 + offset as f64,
+                };
+                Some((minute.rem_euclid(1440.0), shade))
+            })
+            .collect();
+        resolved.sort_by(|a, b| a.0.total_cmp(&b.0));
+        shade_at(&resolved, today.minute as f64)
+    }
+}
+
+/// Interpolate the shade at `minute` between sorted points, wrapping
+/// around midnight.
+fn shade_at(points: &[(f64, Shade)], minute: f64) -> Shade {
+    let (Some(first), Some(last)) = (points.first(), points.last()) else {
+        return Shade::FULL;
+    };
+    let (before, after) = match points.iter().position(|(m, _)| *m > minute) {
+        Some(0) => ((last.0 - 1440.0, last.1), *first),
+        None => (*last, (first.0 + 1440.0, first.1)),
+        Some(i) => (points[i - 1], points[i]),
+    };
+    let span = after.0 - before.0;
+    if span <= 0.0 {
+        return before.1;
+    }
+    before.1.lerp(after.1, ((minute - before.0) / span) as f32)
+}
+
+/// Local sunrise and sunset for a day of the year, in minutes since local
+/// midnight, or `None` when the sun doesn't rise or set (NOAA's
+/// approximation, good to a minute or two).
+fn sun_times(
+    latitude: f64,
+    longitude: f64,
+    year_day: u32,
+    utc_offset_mins: f64,
+) -> Option<(f64, f64)> {
+    let gamma = 2.0 * std::f64::consts::PI / 365.0 * (year_day as f64 + 0.5);
+    let eq_time = 229.18
+        * (0.000075 + 0.001868 * gamma.cos()
+            - 0.032077 * gamma.sin()
+            - 0.014615 * (2.0 * gamma).cos()
+            - 0.040849 * (2.0 * gamma).sin());
+    let declination = 0.006918 - 0.399912 * gamma.cos() + 0.070257 * gamma.sin()
+        - 0.006758 * (2.0 * gamma).cos()
+        + 0.000907 * (2.0 * gamma).sin()
+        - 0.002697 * (3.0 * gamma).cos()
+        + 0.00148 * (3.0 * gamma).sin();
+    let lat = latitude.to_radians();
+    // 90.833°: the sun's upper limb on the horizon, refraction included.
+    let cos_hour_angle = 90.833f64.to_radians().cos() / (lat.cos() * declination.cos())
+        - lat.tan() * declination.tan();
+    if !(-1.0..=1.0).contains(&cos_hour_angle) {
+        return None;
+    }
+    let hour_angle = cos_hour_angle.acos().to_degrees();
+    let sunrise = 720.0 - 4.0 * (longitude + hour_angle) - eq_time + utc_offset_mins;
+    let sunset = 720.0 - 4.0 * (longitude - hour_angle) - eq_time + utc_offset_mins;
+    Some((sunrise.rem_euclid(1440.0), sunset.rem_euclid(1440.0)))
+}
@@ -2,21 +2,228 @@
 //!
 //! Manages downloading, storing, and evicting cached media assets.
 
-use crate::assets::{AssetType, Media};
+use crate::assets::{is_valid_asset_file, AssetType, Media};
+use crate::throttle::Throttle;
 use anyhow::{Context, Result};
+use futures_util::StreamExt;
 use lru::LruCache;
+use reqwest::header::{CONTENT_RANGE, ETAG, IF_RANGE, LAST_MODIFIED, RANGE};
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::num::NonZeroUsize;
-use std::path::PathBuf;
-use tokio::io::AsyncWriteExt;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::sync::RwLock;
 use walkdir::WalkDir;
 
+/// Directory (inside the cache dir) where files that failed verification are
+/// moved for inspection. Emptied on every start.
+const QUARANTINE_DIR: &str = ".quarantine";
+
+/// Suffix of in-progress downloads; only renamed to the final path once
+/// complete and verified. Kept across restarts so they can be resumed.
+const PART_SUFFIX: &str = "part";
+
+/// Suffix of the file next to a partial download holding the ETag or
+/// Last-Modified it was fetched under, sent as `If-Range` on resume so we
+/// never stitch together two versions of a file.
+const VALIDATOR_SUFFIX: &str = "validator";
+
+/// Partial downloads older than this are abandoned rather than resumed.
+const PART_MAX_AGE: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+/// Persisted cache metadata, next to `playlist.json`.
+const INDEX_FILE: &str = "cache_index.json";
+
+/// Minimum time between index writes. Touches happen every slide; writing
+/// on each would wear the SD card for little gain — a power cut loses at
+/// most this much recency.
+const INDEX_SAVE_INTERVAL: Duration = Duration::from_secs(30);
+
+/// How often a download of unknown length re-checks disk space.
+const SPACE_CHECK_BYTES: u64 = 8 * 1024 * 1024;
+
+/// How long a failed URL is left alone after each consecutive failure, in
+/// seconds; the last step repeats. Short at first, since the commonest
+/// failure is a derived file the backend hasn't finished writing.
+const FAILURE_BACKOFF_SECS: [u64; 6] =
+    [60, 5 * 60, 30 * 60, 2 * 60 * 60, 6 * 60 * 60, 24 * 60 * 60];
+
+/// The asset a download is for.
+#[derive(Debug, Clone, Copy)]
+pub struct AssetRef<'a> {
+    pub media_id: &'a str,
+    pub asset_type: AssetType,
+    /// The media record's `updated` stamp, if known.
+    pub media_updated: Option<&'a str>,
+}
+
+/// Result of streaming a response body to a temp file.
+struct Fetched {
+    size: u64,
+    /// Content-Length the server announced, if any.
+    expected_size: Option<u64>,
+    /// Hex SHA-256 of the whole file.
+    sha256: String,
+    etag: Option<String>,
+}
+
+/// A download in flight, for the debug overlay.
+#[derive(Debug, Clone)]
+pub struct DownloadProgress {
+    pub media_id: String,
+    pub asset: &'static str,
+    /// Bytes on disk so far, including any resumed prefix.
+    pub downloaded: u64,
+    pub total: Option<u64>,
+    /// Bytes kept from an earlier, interrupted attempt.
+    pub resumed_from: u64,
+}
+
+/// Space on the filesystem holding the cache.
+#[derive(Debug, Clone, Copy)]
+pub struct DiskUsage {
+    /// Bytes available to unprivileged writers.
+    pub free: u64,
+    pub total: u64,
+}
+
+/// `statvfs` of the filesystem holding `dir`.
+#[cfg(unix)]
+pub fn disk_usage(dir: &Path) -> Option<DiskUsage> {
+    use std::os::unix::ffi::OsStrExt;
+    let c_path = std::ffi::CString::new(dir.as_os_str().as_bytes()).ok()?;
+    let mut st: libc::statvfs = unsafe { std::mem::zeroed() };
+    if unsafe { libc::statvfs(c_path.as_ptr(), &mut st) } != 0 {
+        return None;
+    }
+    let frsize = st.f_frsize as u64;
+    Some(DiskUsage {
+        free: st.f_bavail as u64 * frsize,
+        total: st.f_blocks as u64 * frsize,
+    })
+}
+
+/// Not available here, so the free-space floor isn't enforced.
+#[cfg(not(unix))]
+pub fn disk_usage(_dir: &Path) -> Option<DiskUsage> {
+    None
+}
+
+/// Cache state the overlay can read without waiting for the cache lock,
+/// which queued downloads and evictions keep busy.
+#[derive(Debug, Clone, Default)]
+pub struct CacheStatus {
+    pub stats: CacheStats,
+    /// Downloads in flight, oldest first, keyed like the cache index.
+    pub downloads: Vec<(String, DownloadProgress)>,
+    /// URLs that failed to download, most recent failure first.
+    pub failures: Vec<FailureRecord>,
+}
+
 /// Metadata for a cached asset.
 #[derive(Debug, Clone)]
 struct CacheEntry {
     path: PathBuf,
     size: u64,
+    /// Unix seconds this asset was last shown (or downloaded).
+    last_used: u64,
+    /// Where it was downloaded from.
+    url: Option<String>,
+    etag: Option<String>,
+    /// Hex SHA-256 of the file as downloaded.
+    checksum: Option<String>,
+    /// Held against the pinned budget: never evicted.
+    pinned: bool,
+}
+
+impl CacheEntry {
+    fn new(path: PathBuf, size: u64) -> Self {
+        Self {
+            path,
+            size,
+            last_used: unix_now(),
+            url: None,
+            etag: None,
+            checksum: None,
+            pinned: false,
+        }
+    }
+}
+
+/// A URL that returned an HTTP error, and when to try it again.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FailureRecord {
+    pub url: String,
+    #[serde(default)]
+    pub media_id: String,
+    #[serde(default)]
+    pub asset: String,
+    /// HTTP status of the last attempt.
+    pub status: u16,
+    /// Consecutive failures.
+    #[serde(default)]
+    pub attempts: u32,
+    /// Unix seconds of the last failure, and of the next retry.
+    #[serde(default)]
+    pub last_failed: u64,
+    #[serde(default)]
+    pub retry_at: u64,
+    /// The media record's `updated` stamp at the last failure. Once the
+    /// record changes (say, processing finished) the URL is tried again
+    /// straight away.
+    #[serde(default)]
+    pub media_updated: Option<String>,
+}
+
+impl FailureRecord {
+    /// Whether to leave the URL alone for now, given the media record's
+    /// current `updated` stamp.
+    fn holds(&self, media_updated: Option<&str>, now: u64) -> bool {
+        self.retry_at > now && self.media_updated.as_deref() == media_updated
+    }
+
+    /// Seconds until the next retry; 0 once it's due.
+    pub fn retry_in_secs(&self) -> u64 {
+        self.retry_at.saturating_sub(unix_now())
+    }
+}
+
+/// On-disk form of the cache metadata.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct IndexFile {
+    /// Least recently used first.
+    #[serde(default)]
+    entries: Vec<IndexEntry>,
+    #[serde(default)]
+    failures: Vec<FailureRecord>,
+    /// Media pinned by an inbox command.
+    #[serde(default)]
+    pinned: Vec<String>,
+    /// Media pinned for carrying a pinned tag, as of the last playlist seen.
+    #[serde(default)]
+    tag_pinned: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct IndexEntry {
+    key: String,
+    size: u64,
+    last_used: u64,
+    #[serde(default)]
+    url: Option<String>,
+    #[serde(default)]
+    etag: Option<String>,
+    #[serde(default)]
+    checksum: Option<String>,
 }
 
 /// LRU cache for media assets.
@@ -25,55 +232,154 @@ pub struct Cache {
     cache_dir: PathBuf,
     /// Maximum cache size in bytes.
     max_size: u64,
-    /// Current cache size in bytes.
+    /// Free space to leave on the filesystem, in bytes. The cache shares the
+    /// SD card with the OS and logs; filling it breaks far more than us.
+    min_free: u64,
+    /// Current cache size in bytes, pinned entries included.
     current_size: u64,
+    /// Space for pinned media in bytes, on top of `max_size`.
+    pinned_budget: u64,
+    /// Bytes held by pinned entries.
+    pinned_size: u64,
+    /// Media pinned by an inbox command.
+    pinned_ids: HashSet<String>,
+    /// Media pinned for carrying one of the pinned tags. Kept for media
+    /// missing from the current playlist (e.g. hidden by a tag filter).
+    tag_pinned_ids: HashSet<String>,
     /// LRU tracking for cache entries (key: media_id:asset_type).
     lru: LruCache<String, CacheEntry>,
     /// Quick lookup by media ID and asset type.
     index: HashMap<String, PathBuf>,
-    /// URLs that returned a permanent error (4xx) — skip until process restart.
-    failed_urls: HashSet<String>,
+    /// URLs that failed to download, by URL — skipped until their retry
+    /// time or until their media record changes.
+    failures: HashMap<String, FailureRecord>,
+    /// Whether the persisted index is behind, and when it was last written.
+    index_dirty: bool,
+    index_saved_at: Instant,
+    /// Published copy of stats and download progress.
+    status: Arc<Mutex<CacheStatus>>,
 }
 
 impl Cache {
-    /// Create a new cache with the given directory and size limit.
-    pub fn new(cache_dir: PathBuf, max_size_gb: u64) -> Result<Self> {
+    /// Create a new cache with the given directory, size limit, budget for
+    /// pinned media and the free space to always leave on its filesystem.
+    pub fn new(
+        cache_dir: PathBuf,
+        max_size_gb: u64,
+        pinned_budget_gb: u64,
+        min_free_mb: u64,
+    ) -> Result<Self> {
         let max_size = max_size_gb * 1024 * 1024 * 1024;
 
         // Create cache directory if it doesn't exist
         fs::create_dir_all(&cache_dir).context("Failed to create cache directory")?;
+        let _ = fs::remove_dir_all(cache_dir.join(QUARANTINE_DIR));
 
         let mut cache = Self {
             cache_dir,
             max_size,
+            min_free: min_free_mb * 1024 * 1024,
             current_size: 0,
+            pinned_budget: pinned_budget_gb * 1024 * 1024 * 1024,
+            pinned_size: 0,
+            pinned_ids: HashSet::new(),
+            tag_pinned_ids: HashSet::new(),
             lru: LruCache::new(NonZeroUsize::new(10000).unwrap()),
             index: HashMap::new(),
-            failed_urls: HashSet::new(),
+            failures: HashMap::new(),
+            index_dirty: false,
+            index_saved_at: Instant::now(),
+            status: Arc::new(Mutex::new(CacheStatus::default())),
         };
 
-        // Scan existing cache directory
+        // Scan existing cache directory, then restore recency and metadata
+        // from the persisted index
         cache.scan_existing()?;
+        cache.apply_pins();
+
+        // Get back under the size limit straight away, in case it shrank
+        // while we weren't running. A disk below the free-space floor is
+        // only reported: it may have been filled by other files, which
+        // emptying the cache wouldn't fix. The floor is enforced before
+        // each download.
+        cache.trim_to_limit();
+        if let Some(disk) = disk_usage(&cache.cache_dir) {
+            if disk.free < cache.min_free {
+                tracing::warn!(
+                    "Disk nearly full: {} MB free, keeping {} MB free",
+                    disk.free / 1024 / 1024,
+                    cache.min_free / 1024 / 1024
+                );
+            }
+        }
+        cache.save_index();
+        cache.publish_stats();
 
         tracing::info!(
             "Cache initialized: {:.2} GB / {:.2} GB used",
             cache.current_size as f64 / 1024.0 / 1024.0 / 1024.0,
             max_size_gb as f64
         );
+        if cache.pinned_size > 0 {
+            tracing::info!(
+                "Pinned media: {:.2} GB / {:.2} GB budget",
+                cache.pinned_size as f64 / 1024.0 / 1024.0 / 1024.0,
+                pinned_budget_gb as f64
+            );
+        }
 
         Ok(cache)
     }
 
     /// Scan existing cache directory and populate the index.
+    ///
+    /// The filesystem is the source of truth for what's cached; the persisted
+    /// index only contributes recency and metadata. Files it doesn't know
+    /// (e.g. written by an older version) fall back to their mtime, and its
+    /// entries without a file are dropped.
+    ///
+    /// Partial downloads (interrupted by a crash or power cut) are left for
+    /// `download_and_cache` to resume, unless they're too old to bother.
+    /// Files that fail the format check are quarantined rather than indexed,
+    /// so they get fetched again.
     fn scan_existing(&mut self) -> Result<()> {
+        let saved = self.load_index();
+        self.failures = saved
+            .failures
+            .into_iter()
+            .map(|f| (f.url.clone(), f))
+            .collect();
+        self.publish_failures();
+        self.pinned_ids = saved.pinned.into_iter().collect();
+        self.tag_pinned_ids = saved.tag_pinned.into_iter().collect();
+        let mut known: HashMap<String, IndexEntry> = saved
+            .entries
+            .into_iter()
+            .map(|e| (e.key.clone(), e))
+            .collect();
+
+        let mut found: Vec<(String, CacheEntry)> = Vec::new();
         for entry in WalkDir::new(&self.cache_dir)
             .min_depth(2)
             .max_depth(2)
             .into_iter()
+            .filter_entry(|e| e.file_name() != QUARANTINE_DIR)
             .filter_map(|e| e.ok())
         {
             if entry.file_type().is_file() {
                 let path = entry.path().to_path_buf();
+                if Self::is_partial_file(&path) {
+                    let stale = fs::metadata(&path)
+                        .and_then(|m| m.modified())
+                        .ok()
+                        .and_then(|t| t.elapsed().ok())
+                        .is_some_and(|age| age > PART_MAX_AGE);
+                    if stale {
+                        tracing::info!("Removing abandoned partial download {:?}", path);
+                        let _ = fs::remove_file(&path);
+                    }
+                    continue;
+                }
                 if let Ok(metadata) = fs::metadata(&path) {
                     let size = metadata.len();
 
@@ -82,25 +388,132 @@ impl Cache {
                         if let Some(media_id) = parent.file_name().and_then(|n| n.to_str()) {
                             // Extract asset type from filename
                             if let Some(filename) = path.file_stem().and_then(|n| n.to_str()) {
+                                if let Some(asset_type) = AssetType::from_name(filename) {
+                                    if !is_valid_asset_file(&path, asset_type) {
+                                        self.quarantine_file(
+                                            &path,
+                                            media_id,
+                                            asset_type,
+                                            "failed format check at startup",
+                                        );
+                                        continue;
+                                    }
+                                }
                                 let key = format!("{}:{}", media_id, filename);
-                                self.lru.put(
-                                    key.clone(),
-                                    CacheEntry {
-                                        path: path.clone(),
-                                        size,
-                                    },
-                                );
-                                self.index.insert(key, path);
-                                self.current_size += size;
+                                let mut cache_entry = CacheEntry::new(path, size);
+                                match known.remove(&key) {
+                                    // A size change means the file isn't what
+                                    // the metadata describes; keep only recency.
+                                    Some(saved) if saved.size == size => {
+                                        cache_entry.last_used = saved.last_used;
+                                        cache_entry.url = saved.url;
+                                        cache_entry.etag = saved.etag;
+                                        cache_entry.checksum = saved.checksum;
+                                    }
+                                    Some(saved) => cache_entry.last_used = saved.last_used,
+                                    None => {
+                                        cache_entry.last_used = metadata
+                                            .modified()
+                                            .ok()
+                                            .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+                                            .map_or(0, |d| d.as_secs());
+                                    }
+                                }
+                                found.push((key, cache_entry));
                             }
                         }
                     }
                 }
             }
         }
+
+        if !known.is_empty() {
+            tracing::info!(
+                "Cache index: dropping {} entries with no file on disk",
+                known.len()
+            );
+        }
+
+        // Insert oldest first so the LRU order matches last use.
+        found.sort_by_key(|(_, e)| e.last_used);
+        for (key, entry) in found {
+            self.index.insert(key.clone(), entry.path.clone());
+            self.current_size += entry.size;
+            self.lru.put(key, entry);
+        }
         Ok(())
     }
 
+    fn index_path(&self) -> PathBuf {
+        self.cache_dir.join(INDEX_FILE)
+    }
+
+    /// Read the persisted index. A missing or unreadable one just means
+    /// starting from the directory walk alone.
+    fn load_index(&self) -> IndexFile {
+        let path = self.index_path();
+        let Ok(json) = fs::read_to_string(&path) else {
+            return IndexFile::default();
+        };
+        serde_json::from_str(&json).unwrap_or_else(|e| {
+            tracing::warn!("Ignoring unreadable cache index {:?}: {}", path, e);
+            IndexFile::default()
+        })
+    }
+
+    /// Write the index now (atomically, via a temp file and rename).
+    fn save_index(&mut self) {
+        // LruCache iterates most recent first.
+        let mut entries: Vec<IndexEntry> = self
+            .lru
+            .iter()
+            .map(|(key, e)| IndexEntry {
+                key: key.clone(),
+                size: e.size,
+                last_used: e.last_used,
+                url: e.url.clone(),
+                etag: e.etag.clone(),
+                checksum: e.checksum.clone(),
+            })
+            .collect();
+        entries.reverse();
+        let file = IndexFile {
+            entries,
+            failures: self.failures.values().cloned().collect(),
+            pinned: self.pinned_ids.iter().cloned().collect(),
+            tag_pinned: self.tag_pinned_ids.iter().cloned().collect(),
+        };
+
+        let path = self.index_path();
+        let tmp = path.with_extension("json.tmp");
+        let written = serde_json::to_vec(&file)
+            .map_err(std::io::Error::other)
+            .and_then(|json| fs::write(&tmp, json))
+            .and_then(|_| fs::rename(&tmp, &path));
+        match written {
+            Ok(()) => {
+                self.index_dirty = false;
+                self.index_saved_at = Instant::now();
+            }
+            Err(e) => tracing::warn!("Failed to save cache index: {}", e),
+        }
+    }
+
+    /// Note an index change, writing it out if the last write is old enough.
+    fn index_changed(&mut self) {
+        self.index_dirty = true;
+        if self.index_saved_at.elapsed() >= INDEX_SAVE_INTERVAL {
+            self.save_index();
+        }
+    }
+
+    /// Write any pending index changes now, e.g. before exiting.
+    pub fn flush_index(&mut self) {
+        if self.index_dirty {
+            self.save_index();
+        }
+    }
+
     /// Generate cache key for a media asset.
     fn cache_key(media_id: &str, asset_type: AssetType) -> String {
         format!("{}:{}", media_id, asset_type.as_str())
@@ -121,46 +534,240 @@ impl Cache {
         self.index.get(&key).cloned()
     }
 
-    /// Returns true if this URL previously returned a 4xx and should not be retried.
-    pub fn is_permanently_failed(&self, url: &str) -> bool {
-        self.failed_urls.contains(url)
+    /// Returns true if this URL failed recently enough that it shouldn't be
+    /// retried yet. `media_updated` is the media record's current `updated`
+    /// stamp: a record that changed since the failure is always retried.
+    pub fn is_backing_off(&self, url: &str, media_updated: Option<&str>) -> bool {
+        self.failures
+            .get(url)
+            .is_some_and(|f| f.holds(media_updated, unix_now()))
+    }
+
+    /// Note a failed download of `url`, scheduling the next attempt.
+    fn record_failure(&mut self, url: &str, asset: AssetRef<'_>, status: u16) {
+        let now = unix_now();
+        // Count on from an earlier failure only if the record is unchanged
+        // since; otherwise this is a new version failing for the first time.
+        let attempts = match self.failures.get(url) {
+            Some(f) if f.media_updated.as_deref() == asset.media_updated => f.attempts + 1,
+            _ => 1,
+        };
+        let step = (attempts as usize - 1).min(FAILURE_BACKOFF_SECS.len() - 1);
+        let delay = FAILURE_BACKOFF_SECS[step];
+        tracing::warn!(
+            "{} for {} failed with HTTP {} ({} in a row), retrying in {} min",
+            asset.asset_type.as_str(),
+            asset.media_id,
+            status,
+            attempts,
+            delay / 60
+        );
+        self.failures.insert(
+            url.to_string(),
+            FailureRecord {
+                url: url.to_string(),
+                media_id: asset.media_id.to_string(),
+                asset: asset.asset_type.as_str().to_string(),
+                status,
+                attempts,
+                last_failed: now,
+                retry_at: now + delay,
+                media_updated: asset.media_updated.map(str::to_string),
+            },
+        );
+        self.publish_failures();
+        self.index_changed();
+    }
+
+    fn publish_failures(&self) {
+        let mut failures: Vec<FailureRecord> = self.failures.values().cloned().collect();
+        failures.sort_by_key(|f| std::cmp::Reverse(f.last_failed));
+        self.status
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .failures = failures;
     }
 
     /// Download and cache an asset.
+    ///
+    /// The body is streamed to a `.part` file next to the final path, which
+    /// is fsynced and verified (length, format, and `expected_sha256` when
+    /// given) before an atomic rename — so a power cut leaves at worst a
+    /// `.part` file, never a truncated asset. An existing `.part` file is
+    /// resumed with a `Range` request. A download that fails verification is
+    /// quarantined and fetched once more before giving up.
+    ///
+    /// The cache lock is only taken around index updates, not while the body
+    /// streams, so downloads can run side by side (up to the throttle's
+    /// cap). Callers must not download the same asset twice at once.
     pub async fn download_and_cache(
-        &mut self,
+        cache: &RwLock<Cache>,
+        throttle: &Throttle,
         client: &reqwest::Client,
         url: &str,
-        media_id: &str,
-        asset_type: AssetType,
+        asset: AssetRef<'_>,
         token: Option<&str>,
+        expected_sha256: Option<&str>,
     ) -> Result<PathBuf> {
+        let AssetRef {
+            media_id,
+            asset_type,
+            ..
+        } = asset;
         let key = Self::cache_key(media_id, asset_type);
-        let path = self.cache_path(media_id, asset_type);
+        let (path, status) = {
+            let cache = cache.read().await;
+            (cache.cache_path(media_id, asset_type), cache.status.clone())
+        };
+        let tmp_path = Self::part_path(&path);
 
         // Create media directory
         if let Some(parent) = path.parent() {
             fs::create_dir_all(parent).context("Failed to create media cache directory")?;
         }
 
-        // Download the file
-        tracing::debug!("Downloading {} to {:?}", url, path);
+        let mut attempt = 1;
+        let fetched = loop {
+            tracing::debug!("Downloading {} to {:?}", url, tmp_path);
+            let fetched =
+                Self::fetch_to_file(cache, throttle, client, url, token, &tmp_path, asset).await;
+            set_download(&status, &key, None);
+            let fetched = fetched?;
+            match Self::verify(&tmp_path, asset_type, &fetched, expected_sha256) {
+                Ok(()) => break fetched,
+                Err(reason) => {
+                    cache
+                        .read()
+                        .await
+                        .quarantine_file(&tmp_path, media_id, asset_type, &reason);
+                    if attempt >= 2 {
+                        anyhow::bail!(
+                            "Download of {} for {} failed verification twice: {}",
+                            asset_type.as_str(),
+                            media_id,
+                            reason
+                        );
+                    }
+                    attempt += 1;
+                }
+            }
+        };
+
+        let mut cache = cache.write().await;
+
+        // Drop any previous entry for this asset — the rename replaces it.
+        cache.forget(&key);
+
+        let size = fetched.size;
+        let pinned = cache.fits_pinned(media_id, size);
+
+        // The bytes are already on disk; only the size limit is left to
+        // check (for downloads that didn't announce their length).
+        if let Err(e) = cache.ensure_room(size, 0, pinned) {
+            let _ = fs::remove_file(&tmp_path);
+            let _ = fs::remove_file(Self::validator_path(&tmp_path));
+            return Err(e);
+        }
+
+        fs::rename(&tmp_path, &path).context("Failed to move download into place")?;
+        let _ = fs::remove_file(Self::validator_path(&tmp_path));
+        if let Some(parent) = path.parent() {
+            // Persist the rename itself, not just the file contents.
+            if let Err(e) = fs::File::open(parent).and_then(|d| d.sync_all()) {
+                tracing::debug!("Failed to fsync {:?}: {}", parent, e);
+            }
+        }
 
-        let mut request = client.get(url);
-        if let Some(token) = token {
-            request = request.bearer_auth(token);
+        // Update cache index
+        cache.lru.put(
+            key.clone(),
+            CacheEntry {
+                url: Some(url.to_string()),
+                etag: fetched.etag,
+                checksum: Some(fetched.sha256),
+                pinned,
+                ..CacheEntry::new(path.clone(), size)
+            },
+        );
+        cache.index.insert(key, path.clone());
+        cache.current_size += size;
+        if pinned {
+            cache.pinned_size += size;
         }
+        if cache.failures.remove(url).is_some() {
+            cache.publish_failures();
+        }
+        cache.publish_stats();
+        cache.index_changed();
+
+        tracing::debug!(
+            "Cached {} ({:.2} KB), total: {:.2} MB",
+            media_id,
+            size as f64 / 1024.0,
+            cache.current_size as f64 / 1024.0 / 1024.0
+        );
+
+        Ok(path)
+    }
+
+    /// Stream `url` into `dest`, resuming a partial file already there, and
+    /// fsync it. On a dropped connection the partial file is kept (and
+    /// synced) for the next attempt.
+    async fn fetch_to_file(
+        cache: &RwLock<Cache>,
+        throttle: &Throttle,
+        client: &reqwest::Client,
+        url: &str,
+        token: Option<&str>,
+        dest: &Path,
+        asset: AssetRef<'_>,
+    ) -> Result<Fetched> {
+        let AssetRef {
+            media_id,
+            asset_type,
+            ..
+        } = asset;
+        let validator_path = Self::validator_path(dest);
+        let mut offset = tokio::fs::metadata(dest)
+            .await
+            .map(|m| m.len())
+            .unwrap_or(0);
+        let validator = tokio::fs::read_to_string(&validator_path).await.ok();
+        if offset > 0 && validator.is_none() {
+            // Without a validator we can't tell whether the file changed.
+            offset = 0;
+        }
+
+        let response = loop {
+            let mut request = client.get(url);
+            if let Some(token) = token {
+                request = request.bearer_auth(token);
+            }
+            if let (true, Some(v)) = (offset > 0, validator.as_deref()) {
+                request = request
+                    .header(RANGE, format!("bytes={}-", offset))
+                    .header(IF_RANGE, v.trim());
+            }
+
+            let response = request.send().await.context("Failed to send request")?;
+            if response.status() == StatusCode::RANGE_NOT_SATISFIABLE && offset > 0 {
+                tracing::debug!("Server rejected resume of {:?}, starting over", dest);
+                offset = 0;
+                continue;
+            }
+            break response;
+        };
 
-        let response = request.send().await.context("Failed to send request")?;
         let status = response.status();
         if !status.is_success() {
             let body = response
                 .text()
                 .await
                 .unwrap_or_else(|_| "<unreadable body>".to_string());
-            if status.is_client_error() {
-                self.failed_urls.insert(url.to_string());
-            }
+            cache
+                .write()
+                .await
+                .record_failure(url, asset, status.as_u16());
             anyhow::bail!(
                 "Server returned {} for {}: {}",
                 status,
@@ -169,50 +776,291 @@ impl Cache {
             );
         }
 
-        let bytes = response.bytes().await.context("Failed to read response")?;
-        let size = bytes.len() as u64;
+        // 206 continues where the partial file ends; a plain 200 means the
+        // server ignored the range (or the file changed), so start over.
+        let (start, total) = if status == StatusCode::PARTIAL_CONTENT {
+            let range = response
+                .headers()
+                .get(CONTENT_RANGE)
+                .and_then(|v| v.to_str().ok())
+                .and_then(parse_content_range);
+            match range {
+                Some((start, total)) if start == offset => (start, total),
+                _ => {
+                    let _ = tokio::fs::remove_file(dest).await;
+                    anyhow::bail!("Unexpected Content-Range resuming {}", url);
+                }
+            }
+        } else {
+            (0, response.content_length())
+        };
 
-        // Check if we need to evict before writing
-        while self.current_size + size > self.max_size {
-            if !self.evict_lru() {
-                tracing::warn!("Cache full and cannot evict, continuing anyway");
-                break;
+        if start == 0 {
+            // Remember what we're fetching so a later resume can check it.
+            // Weak ETags aren't allowed in If-Range.
+            let headers = response.headers();
+            let new_validator = headers
+                .get(ETAG)
+                .and_then(|v| v.to_str().ok())
+                .filter(|v| !v.starts_with("W/"))
+                .or_else(|| headers.get(LAST_MODIFIED).and_then(|v| v.to_str().ok()))
+                .map(str::to_string);
+            match new_validator {
+                Some(v) => {
+                    let _ = tokio::fs::write(&validator_path, v).await;
+                }
+                None => {
+                    let _ = tokio::fs::remove_file(&validator_path).await;
+                }
             }
+        } else {
+            tracing::info!(
+                "Resuming {} for {} at {:.1} MB",
+                asset_type.as_str(),
+                media_id,
+                start as f64 / 1024.0 / 1024.0
+            );
         }
 
-        // Write to file
-        let mut file = tokio::fs::File::create(&path)
-            .await
-            .context("Failed to create cache file")?;
-        file.write_all(&bytes)
-            .await
-            .context("Failed to write cache file")?;
-        file.flush().await.context("Failed to flush cache file")?;
+        // Refuse up front what can't fit, before writing anything.
+        if let Some(total) = total {
+            let mut cache = cache.write().await;
+            let pinned = cache.fits_pinned(media_id, total);
+            cache.ensure_room(total, total.saturating_sub(start), pinned)?;
+        }
 
-        // Update cache index
-        self.lru.put(
-            key.clone(),
-            CacheEntry {
-                path: path.clone(),
-                size,
-            },
-        );
-        self.index.insert(key, path.clone());
-        self.current_size += size;
+        let mut file = if start > 0 {
+            tokio::fs::OpenOptions::new().append(true).open(dest).await
+        } else {
+            tokio::fs::File::create(dest).await
+        }
+        .context("Failed to open cache file")?;
 
-        tracing::debug!(
-            "Cached {} ({:.2} KB), total: {:.2} MB",
+        let etag = response
+            .headers()
+            .get(ETAG)
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string);
+
+        let mut hasher = Sha256::new();
+        if start > 0 {
+            hash_file_prefix(dest, &mut hasher).await?;
+        }
+
+        let key = Self::cache_key(media_id, asset_type);
+        let status = cache.read().await.status.clone();
+        let mut progress = DownloadProgress {
+            media_id: media_id.to_string(),
+            asset: asset_type.as_str(),
+            downloaded: start,
+            total,
+            resumed_from: start,
+        };
+        set_download(&status, &key, Some(&progress));
+
+        let mut stream = response.bytes_stream();
+        let mut space_checked_at = start;
+        let streamed: Result<()> = async {
+            while let Some(chunk) = stream.next().await {
+                let chunk = chunk.context("Download interrupted")?;
+                file.write_all(&chunk)
+                    .await
+                    .context("Failed to write cache file")?;
+                hasher.update(&chunk);
+                progress.downloaded += chunk.len() as u64;
+                set_download(&status, &key, Some(&progress));
+                throttle.consume(chunk.len() as u64).await;
+
+                // Other writers (logs, the OS) share the disk, and a length
+                // may not have been announced: keep checking as we go.
+                if progress.downloaded - space_checked_at >= SPACE_CHECK_BYTES {
+                    space_checked_at = progress.downloaded;
+                    let size = total.unwrap_or(progress.downloaded);
+                    let mut cache = cache.write().await;
+                    let pinned = cache.fits_pinned(media_id, size);
+                    cache.ensure_room(size, 0, pinned)?;
+                }
+            }
+            Ok(())
+        }
+        .await;
+
+        // Sync even after a failure: that's what makes the partial resumable.
+        file.sync_all().await.context("Failed to sync cache file")?;
+        streamed?;
+
+        Ok(Fetched {
+            size: progress.downloaded,
+            expected_size: total,
+            sha256: format!("{:x}", hasher.finalize()),
+            etag,
+        })
+    }
+
+    /// Check a finished download, returning why it's bad if it is.
+    fn verify(
+        path: &Path,
+        asset_type: AssetType,
+        fetched: &Fetched,
+        expected_sha256: Option<&str>,
+    ) -> std::result::Result<(), String> {
+        if let Some(expected) = fetched.expected_size {
+            if expected != fetched.size {
+                return Err(format!(
+                    "got {} of {} bytes announced",
+                    fetched.size, expected
+                ));
+            }
+        }
+        if !is_valid_asset_file(path, asset_type) {
+            return Err("not a valid file of its type".to_string());
+        }
+        if let Some(expected) = expected_sha256 {
+            if !expected.eq_ignore_ascii_case(&fetched.sha256) {
+                return Err(format!(
+                    "checksum {} != expected {}",
+                    fetched.sha256, expected
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    fn part_path(path: &Path) -> PathBuf {
+        let mut name = path.file_name().unwrap_or_default().to_os_string();
+        name.push(".");
+        name.push(PART_SUFFIX);
+        path.with_file_name(name)
+    }
+
+    fn validator_path(part_path: &Path) -> PathBuf {
+        let mut name = part_path.file_name().unwrap_or_default().to_os_string();
+        name.push(".");
+        name.push(VALIDATOR_SUFFIX);
+        part_path.with_file_name(name)
+    }
+
+    /// A `.part` download or its validator file.
+    fn is_partial_file(path: &Path) -> bool {
+        matches!(
+            path.extension().and_then(|e| e.to_str()),
+            Some(PART_SUFFIX) | Some(VALIDATOR_SUFFIX)
+        )
+    }
+
+    /// Handle to the published stats and download progress.
+    pub fn status_handle(&self) -> Arc<Mutex<CacheStatus>> {
+        self.status.clone()
+    }
+
+    fn publish_stats(&self) {
+        let stats = self.stats();
+        self.status.lock().unwrap_or_else(|e| e.into_inner()).stats = stats;
+    }
+
+    /// Move a bad file out of the cache into the quarantine directory
+    /// (replacing any earlier copy for the same asset).
+    fn quarantine_file(&self, path: &Path, media_id: &str, asset_type: AssetType, reason: &str) {
+        let dir = self.cache_dir.join(QUARANTINE_DIR);
+        let dest = dir.join(format!(
+            "{}-{}.{}",
             media_id,
-            size as f64 / 1024.0,
-            self.current_size as f64 / 1024.0 / 1024.0
+            asset_type.as_str(),
+            asset_type.extension()
+        ));
+        tracing::warn!(
+            "Quarantining {} for {} ({}): {:?}",
+            asset_type.as_str(),
+            media_id,
+            reason,
+            path
         );
+        let moved = fs::create_dir_all(&dir).and_then(|_| fs::rename(path, &dest));
+        if let Err(e) = moved {
+            tracing::warn!("Failed to quarantine {:?}: {}", path, e);
+            let _ = fs::remove_file(path);
+        }
+        let _ = fs::remove_file(Self::validator_path(path));
+    }
 
-        Ok(path)
+    /// Quarantine a cached asset that turned out to be bad, so the next
+    /// download fetches it fresh.
+    pub fn quarantine(&mut self, media_id: &str, asset_type: AssetType, reason: &str) {
+        let key = Self::cache_key(media_id, asset_type);
+        let path = self.cache_path(media_id, asset_type);
+        self.forget(&key);
+        self.publish_stats();
+        if path.exists() {
+            self.quarantine_file(&path, media_id, asset_type, reason);
+        }
+    }
+
+    /// Remove an entry from the index without touching its file.
+    fn forget(&mut self, key: &str) {
+        if let Some(entry) = self.lru.pop(key) {
+            self.current_size = self.current_size.saturating_sub(entry.size);
+            if entry.pinned {
+                self.pinned_size = self.pinned_size.saturating_sub(entry.size);
+            }
+        }
+        self.index.remove(key);
+    }
+
+    /// Make room for an asset of `size` bytes, `disk_bytes` of which are
+    /// still to be written, by evicting least recently used assets until
+    /// both the size limit and the free-space floor hold. Fails when
+    /// eviction can't get there: writing anyway is how the SD card fills up.
+    ///
+    /// A `pinned` asset has already been fitted into the pinned budget, so
+    /// only the free-space floor applies to it. Pinned entries are never
+    /// evicted, not even to keep the floor.
+    fn ensure_room(&mut self, size: u64, disk_bytes: u64, pinned: bool) -> Result<()> {
+        loop {
+            let over_limit = !pinned && self.current_size - self.pinned_size + size > self.max_size;
+            let free = disk_usage(&self.cache_dir).map(|d| d.free);
+            let below_floor = free.is_some_and(|f| f < self.min_free + disk_bytes);
+            if !over_limit && !below_floor {
+                return Ok(());
+            }
+            if !self.evict_lru() {
+                let mb = |b: u64| b / 1024 / 1024;
+                if below_floor {
+                    anyhow::bail!(
+                        "Disk nearly full: {} MB free, {} MB needed, keeping {} MB free",
+                        mb(free.unwrap_or(0)),
+                        mb(disk_bytes),
+                        mb(self.min_free)
+                    );
+                }
+                anyhow::bail!(
+                    "Cache full: {} MB needed, {} MB limit",
+                    mb(size),
+                    mb(self.max_size)
+                );
+            }
+        }
     }
 
-    /// Evict the least recently used item.
+    /// Evict least recently used assets until the unpinned ones fit the
+    /// size limit. Unlike `ensure_room`, this leaves the free-space floor
+    /// alone.
+    fn trim_to_limit(&mut self) {
+        while self.current_size - self.pinned_size > self.max_size {
+            if !self.evict_lru() {
+                break;
+            }
+        }
+    }
+
+    /// Evict the least recently used item that isn't pinned.
     fn evict_lru(&mut self) -> bool {
-        if let Some((key, entry)) = self.lru.pop_lru() {
+        let victim = self
+            .lru
+            .iter()
+            .rev()
+            .find(|(_, e)| !e.pinned)
+            .map(|(key, _)| key.clone());
+        if let Some((key, entry)) = victim.and_then(|key| self.lru.pop_entry(&key)) {
             tracing::debug!("Evicting {:?}", entry.path);
 
             // Remove the file
@@ -229,17 +1077,107 @@ impl Cache {
 
             self.index.remove(&key);
             self.current_size = self.current_size.saturating_sub(entry.size);
+            self.publish_stats();
+            self.index_changed();
 
             return true;
         }
         false
     }
 
-    /// Mark an asset as recently used (for LRU tracking).
-    pub fn touch(&mut self, media_id: &str, asset_type: AssetType) {
-        let key = Self::cache_key(media_id, asset_type);
-        // LruCache::get promotes the key to most recently used
-        let _ = self.lru.get(&key);
+    /// Mark all of a media item's assets as just shown (for LRU tracking),
+    /// so its video and backdrop age together with the display image.
+    pub fn touch_media(&mut self, media_id: &str) {
+        let now = unix_now();
+        for asset_type in [
+            AssetType::Display,
+            AssetType::Video,
+            AssetType::Poster,
+            AssetType::Blur,
+            AssetType::Thumb,
+        ] {
+            // LruCache::get_mut promotes the key to most recently used
+            if let Some(entry) = self.lru.get_mut(&Self::cache_key(media_id, asset_type)) {
+                entry.last_used = now;
+            }
+        }
+        self.index_changed();
+    }
+
+    /// Whether a media item is pinned, by tag or by command. Its assets are
+    /// held against the pinned budget while there's room in it.
+    pub fn is_pinned(&self, media_id: &str) -> bool {
+        self.pinned_ids.contains(media_id) || self.tag_pinned_ids.contains(media_id)
+    }
+
+    /// Pin or unpin media by id (from an inbox command).
+    pub fn set_pinned(&mut self, media_ids: &[String], pinned: bool) {
+        let mut changed = false;
+        for id in media_ids {
+            changed |= if pinned {
+                self.pinned_ids.insert(id.clone())
+            } else {
+                self.pinned_ids.remove(id)
+            };
+        }
+        if changed {
+            self.pins_changed();
+        }
+    }
+
+    /// Update tag pins from playlist items: those carrying one of `tags`
+    /// are pinned, the rest unpinned. Media not in `media` keep their state.
+    pub fn apply_tag_pins(&mut self, media: &[Media], tags: &[String]) {
+        let mut changed = false;
+        for m in media {
+            changed |= if m.has_any_tag(tags) {
+                self.tag_pinned_ids.insert(m.id.clone())
+            } else {
+                self.tag_pinned_ids.remove(&m.id)
+            };
+        }
+        if changed {
+            self.pins_changed();
+        }
+    }
+
+    /// Drop every pin on a media item that was deleted from the server.
+    pub fn unpin_deleted(&mut self, media_id: &str) {
+        let explicit = self.pinned_ids.remove(media_id);
+        if self.tag_pinned_ids.remove(media_id) || explicit {
+            self.pins_changed();
+        }
+    }
+
+    fn pins_changed(&mut self) {
+        self.apply_pins();
+        // Unpinned entries count against the size limit again.
+        self.trim_to_limit();
+        self.publish_stats();
+        self.index_changed();
+    }
+
+    /// Whether an asset of `size` bytes for `media_id` would be held as
+    /// pinned if it were cached now.
+    fn fits_pinned(&self, media_id: &str, size: u64) -> bool {
+        self.is_pinned(media_id) && self.pinned_size + size <= self.pinned_budget
+    }
+
+    /// Re-decide which entries are held as pinned: those of pinned media,
+    /// most recently shown first, for as long as the budget lasts. The rest
+    /// age out like any other entry.
+    fn apply_pins(&mut self) {
+        let (explicit, tagged) = (&self.pinned_ids, &self.tag_pinned_ids);
+        let mut pinned_size = 0;
+        for (key, entry) in self.lru.iter_mut() {
+            let media_id = key.split(':').next().unwrap_or(key);
+            entry.pinned = (explicit.contains(media_id) || tagged.contains(media_id))
+                && pinned_size + entry.size <= self.pinned_budget;
+            if entry.pinned {
+                pinned_size += entry.size;
+            }
+        }
+        self.pinned_size = pinned_size;
     }
 
     /// Save the current playlist to cache for offline use.
@@ -268,14 +1206,21 @@ impl Cache {
 
     /// Get cache statistics.
     pub fn stats(&self) -> CacheStats {
+        let disk = disk_usage(&self.cache_dir);
         CacheStats {
             current_size: self.current_size,
             max_size: self.max_size,
             item_count: self.lru.len(),
+            pinned_size: self.pinned_size,
+            pinned_budget: self.pinned_budget,
+            disk,
+            disk_pressure: disk.is_some_and(|d| d.free < self.min_free),
         }
     }
 
     /// Clean up orphaned cache entries (assets not in current playlist).
+    /// Pinned media is kept: the playlist may only be missing it because of
+    /// a tag filter. Deleted media is unpinned first (see `unpin_deleted`).
     pub fn cleanup_orphans(&mut self, playlist: &[Media]) {
         let playlist_ids: std::collections::HashSet<_> =
             playlist.iter().map(|m| m.id.as_str()).collect();
@@ -285,7 +1230,7 @@ impl Cache {
         for (key, entry) in self.lru.iter() {
             // Extract media_id from key (format: media_id:asset_type)
             if let Some(media_id) = key.split(':').next() {
-                if !playlist_ids.contains(media_id) {
+                if !playlist_ids.contains(media_id) && !self.is_pinned(media_id) {
                     to_remove.push((key.clone(), entry.clone()));
                 }
             }
@@ -299,17 +1244,72 @@ impl Cache {
             if let Some(parent) = entry.path.parent() {
                 let _ = fs::remove_dir(parent);
             }
-            self.lru.pop(&key);
-            self.index.remove(&key);
-            self.current_size = self.current_size.saturating_sub(entry.size);
+            self.forget(&key);
         }
+        self.failures
+            .retain(|_, f| playlist_ids.contains(f.media_id.as_str()));
+        self.publish_failures();
+        self.publish_stats();
+        self.index_changed();
     }
 }
 
 /// Cache statistics.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct CacheStats {
     pub current_size: u64,
     pub max_size: u64,
     pub item_count: usize,
+    /// Bytes held by pinned media, and the budget for them.
+    pub pinned_size: u64,
+    pub pinned_budget: u64,
+    pub disk: Option<DiskUsage>,
+    /// Free space is below the configured floor.
+    pub disk_pressure: bool,
+}
+
+/// Publish (or, with `None`, clear) the progress of the download of `key`.
+fn set_download(status: &Mutex<CacheStatus>, key: &str, progress: Option<&DownloadProgress>) {
+    let mut status = status.lock().unwrap_or_else(|e| e.into_inner());
+    let existing = status.downloads.iter().position(|(k, _)| k == key);
+    match (existing, progress) {
+        (Some(i), Some(p)) => status.downloads[i].1 = p.clone(),
+        (None, Some(p)) => status.downloads.push((key.to_string(), p.clone())),
+        (Some(i), None) => {
+            status.downloads.remove(i);
+        }
+        (None, None) => {}
+    }
+}
+
+/// Parse `bytes <start>-<end>/<total>` into the start offset and total size
+/// (`*` total means unknown).
+fn parse_content_range(value: &str) -> Option<(u64, Option<u64>)> {
+    let (range, total) = value.strip_prefix("bytes ")?.split_once('/')?;
+    let start = range.split_once('-')?.0.trim().parse().ok()?;
+    Some((start, total.trim().parse().ok()))
+}
+
+/// Feed the bytes already on disk into `hasher`, in chunks.
+async fn hash_file_prefix(path: &Path, hasher: &mut Sha256) -> Result<()> {
+    let mut file = tokio::fs::File::open(path)
+        .await
+        .context("Failed to reopen partial download")?;
+    let mut buf = vec![0u8; 64 * 1024];
+    loop {
+        let n = file
+            .read(&mut buf)
+            .await
+            .context("Failed to read partial download")?;
+        if n == 0 {
+            return Ok(());
+        }
+        hasher.update(&buf[..n]);
+    }
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
 }
@@ -0,0 +1,128 @@
+//! Acknowledgments for remote commands (`device_inbox` records).
+//!
+//! Each command is acknowledged by its inbox record id: `received` as soon
+//! as it arrives, then `applied` or `failed` once the render loop has acted
+//! on it, along with what the frame is doing now. The backend keeps the
+//! latest acknowledgment on the record, where the admin's Viewer Control
+//! page picks it up.
+
+use reqwest::Client;
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Inbox ids remembered for spotting replayed commands.
+const SEEN_CAPACITY: usize = 256;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AckStatus {
+    Received,
+    Applied,
+    Failed,
+}
+
+impl AckStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AckStatus::Received => "received",
+            AckStatus::Applied => "applied",
+            AckStatus::Failed => "failed",
+        }
+    }
+}
+
+/// What the frame is doing after a command, reported with its ack.
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CommandState {
+    pub current_media_id: Option<String>,
+    pub paused: bool,
+    /// End of a timed pause, in Unix milliseconds.
+    pub paused_until: Option<u64>,
+}
+
+/// Posts command acknowledgments and remembers which commands were seen.
+pub struct CommandAcks {
+    client: Client,
+    pb_url: String,
+    /// device_id and device_api_key; without them nothing is posted.
+    creds: Option<(String, String)>,
+    seen: Mutex<VecDeque<String>>,
+}
+
+impl CommandAcks {
+    pub fn new(pb_url: String, device_id: Option<String>, api_key: Option<String>) -> Self {
+        let client = Client::builder()
+            .timeout(Duration::from_secs(10))
+            .build()
+            .unwrap_or_default();
+        Self {
+            client,
+            pb_url,
+            creds: device_id.zip(api_key),
+            seen: Mutex::new(VecDeque::with_capacity(SEEN_CAPACITY)),
+        }
+    }
+
+    /// Note an inbox id as seen. Returns false if it already was, i.e. the
+    /// command is a replay and shouldn't run again.
+    pub fn first_sight(&self, id: &str) -> bool {
+        let mut seen = self.seen.lock().unwrap_or_else(|e| e.into_inner());
+        if seen.iter().any(|s| s == id) {
+            return false;
+        }
+        if seen.len() == SEEN_CAPACITY {
+            seen.pop_front();
+        }
+        seen.push_back(id.to_string());
+        true
+    }
+
+    /// Post an acknowledgment in the background.
+    pub fn send(
+        self: &Arc<Self>,
+        id: &str,
+        status: AckStatus,
+        reason: Option<String>,
+        state: Option<CommandState>,
+    ) {
+        let acks = self.clone();
+        let id = id.to_string();
+        tokio::spawn(async move {
+            acks.post(&id, status, reason, state).await;
+        });
+    }
+
+    /// Post an acknowledgment and wait for it to go through, e.g. before
+    /// the viewer restarts.
+    pub async fn post(
+        &self,
+        id: &str,
+        status: AckStatus,
+        reason: Option<String>,
+        state: Option<CommandState>,
+    ) {
+        let Some((device_id, api_key)) = &self.creds else {
+            return;
+        };
+        let result = self
+            .client
+            .post(format!("{}/api/spomienka/command-ack", self.pb_url))
+            .json(&serde_json::json!({
+                "device_id": device_id,
+                "api_key": api_key,
+                "id": id,
+                "status": status.as_str(),
+                "reason": reason,
+                "state": state,
+            }))
+            .send()
+            .await
+            .and_then(|r| r.error_for_status());
+        match result {
+            Ok(_) => tracing::debug!("Acknowledged command {} as {}", id, status.as_str()),
+            Err(e) => tracing::warn!("Failed to acknowledge command {}: {}", id, e),
+        }
+    }
+}
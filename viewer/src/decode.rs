@@ -0,0 +1,392 @@
+//! Background image decoding.
+//!
+//! Decoding a phone photo, scaling it down and blurring a backdrop takes tens
+//! to hundreds of milliseconds on a Pi — long enough to visibly stall the
+//! render loop (clock, running video, transitions). The decode pool does that
+//! work on worker threads as soon as the preloader has an item on disk and
+//! keeps the resulting RGBA buffers, bounded by a memory budget, until the
+//! render thread asks for them. All that's left on the render thread is the
+//! texture upload.
+
+use anyhow::{Context, Result};
+use image::imageops::{self, FilterType};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::path::{Path, PathBuf};
+use std::sync::{mpsc, Arc, Condvar, Mutex};
+use std::time::{Duration, Instant};
+
+/// Size locally generated backdrops are blurred at; the GPU stretches them
+/// to the screen. Matches the server-rendered `blur_` assets.
+const BLUR_WIDTH: u32 = 480;
+const BLUR_HEIGHT: u32 = 270;
+const BLUR_SIGMA: f32 = 8.0;
+
+/// How long the render thread waits for a job a worker already has, before
+/// giving up and decoding the item itself.
+const PENDING_WAIT: Duration = Duration::from_secs(3);
+
+/// Tightly packed RGBA pixels (`width * 4` bytes per row), ready for upload.
+pub struct DecodedImage {
+    pub width: u32,
+    pub height: u32,
+    pub pixels: Vec<u8>,
+}
+
+impl DecodedImage {
+    fn from_rgba(img: image::RgbaImage) -> Self {
+        let (width, height) = img.dimensions();
+        Self {
+            width,
+            height,
+            pixels: img.into_raw(),
+        }
+    }
+}
+
+/// Where a media item's backdrop comes from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Backdrop {
+    /// Server-rendered blur — decoded as is.
+    Ready(PathBuf),
+    /// Any other image, blurred locally.
+    Generate(PathBuf),
+}
+
+/// The cached files to decode for one media item.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DecodeRequest {
+    pub media_id: String,
+    /// Display image, or the poster for videos.
+    pub display: Option<PathBuf>,
+    pub backdrop: Option<Backdrop>,
+}
+
+/// Decoded pixels for one media item. A part that failed to decode is `None`
+/// (the failure is logged by whoever decoded it).
+pub struct DecodedMedia {
+    pub display: Option<DecodedImage>,
+    pub blur: Option<DecodedImage>,
+}
+
+impl DecodedMedia {
+    fn bytes(&self) -> usize {
+        self.display.as_ref().map_or(0, |i| i.pixels.len())
+            + self.blur.as_ref().map_or(0, |i| i.pixels.len())
+    }
+}
+
+/// Output sizing, known once the renderer has a window.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DecodeTarget {
+    pub screen_width: u32,
+    pub screen_height: u32,
+    /// Largest texture the GPU accepts.
+    pub max_texture_dim: u32,
+}
+
+struct Entry {
+    request: DecodeRequest,
+    media: Arc<DecodedMedia>,
+}
+
+#[derive(Default)]
+struct Store {
+    target: Option<DecodeTarget>,
+    ready: HashMap<String, Entry>,
+    /// Media ids in `ready`, least recently requested first.
+    order: VecDeque<String>,
+    /// Media ids queued for or being decoded by a worker.
+    pending: HashSet<String>,
+    used_bytes: usize,
+}
+
+impl Store {
+    fn touch(&mut self, media_id: &str) {
+        if let Some(pos) = self.order.iter().position(|id| id == media_id) {
+            let id = self.order.remove(pos).unwrap_or_default();
+            self.order.push_back(id);
+        }
+    }
+
+    fn remove(&mut self, media_id: &str) {
+        if let Some(entry) = self.ready.remove(media_id) {
+            self.used_bytes = self.used_bytes.saturating_sub(entry.media.bytes());
+            self.order.retain(|id| id != media_id);
+        }
+    }
+
+    /// Store a result, then evict least recently requested items until the
+    /// budget holds again. The new item itself is always kept.
+    fn insert(&mut self, request: DecodeRequest, media: Arc<DecodedMedia>, budget: usize) {
+        let id = request.media_id.clone();
+        self.remove(&id);
+        self.used_bytes += media.bytes();
+        self.ready.insert(id.clone(), Entry { request, media });
+        self.order.push_back(id);
+
+        while self.used_bytes > budget && self.order.len() > 1 {
+            let Some(oldest) = self.order.front().cloned() else {
+                break;
+            };
+            tracing::debug!("Decode budget exceeded, dropping decoded {}", oldest);
+            self.remove(&oldest);
+        }
+    }
+}
+
+/// Worker pool that decodes upcoming slides ahead of time.
+pub struct DecodePool {
+    shared: Arc<(Mutex<Store>, Condvar)>,
+    jobs: mpsc::Sender<DecodeRequest>,
+    budget_bytes: usize,
+}
+
+impl DecodePool {
+    /// Start `workers` decode threads holding at most `budget_mb` of decoded
+    /// pixels between them.
+    pub fn new(workers: usize, budget_mb: u64) -> Self {
+        let shared = Arc::new((Mutex::new(Store::default()), Condvar::new()));
+        let budget_bytes = (budget_mb as usize).saturating_mul(1024 * 1024);
+        let (jobs, rx) = mpsc::channel::<DecodeRequest>();
+        let rx = Arc::new(Mutex::new(rx));
+
+        for i in 0..workers.max(1) {
+            let rx = rx.clone();
+            let shared = shared.clone();
+            let spawned = std::thread::Builder::new()
+                .name(format!("decode-{}", i))
+                .spawn(move || worker_loop(rx, shared, budget_bytes));
+            if let Err(e) = spawned {
+                tracing::error!("Failed to spawn decode worker: {}", e);
+            }
+        }
+
+        Self {
+            shared,
+            jobs,
+            budget_bytes,
+        }
+    }
+
+    /// Set the output sizing. Decoded items for a different target are dropped.
+    pub fn set_target(&self, target: DecodeTarget) {
+        let (lock, _) = &*self.shared;
+        let mut store = lock.lock().unwrap_or_else(|e| e.into_inner());
+        if store.target != Some(target) {
+            store.ready.clear();
+            store.order.clear();
+            store.used_bytes = 0;
+            store.target = Some(target);
+        }
+    }
+
+    /// Queue an item for decoding unless it's already decoded or queued.
+    /// Ignored until the renderer has set a target.
+    pub fn request(&self, request: DecodeRequest) {
+        let (lock, _) = &*self.shared;
+        let mut store = lock.lock().unwrap_or_else(|e| e.into_inner());
+        if store.target.is_none() || store.pending.contains(&request.media_id) {
+            return;
+        }
+        if let Some(entry) = store.ready.get(&request.media_id) {
+            if entry.request == request {
+                store.touch(&request.media_id);
+                return;
+            }
+        }
+        store.pending.insert(request.media_id.clone());
+        if self.jobs.send(request.clone()).is_err() {
+            store.pending.remove(&request.media_id);
+        }
+    }
+
+    /// Decoded pixels for an item. Returns at once when a worker already
+    /// finished it, waits when a worker is on it, and otherwise decodes on
+    /// the calling thread.
+    pub fn get(&self, request: &DecodeRequest) -> Result<Arc<DecodedMedia>> {
+        let (lock, cvar) = &*self.shared;
+        let mut store = lock.lock().unwrap_or_else(|e| e.into_inner());
+        let deadline = Instant::now() + PENDING_WAIT;
+
+        loop {
+            if let Some(entry) = store.ready.get(&request.media_id) {
+                if entry.request == *request {
+                    let media = entry.media.clone();
+                    store.touch(&request.media_id);
+                    return Ok(media);
+                }
+            }
+            let now = Instant::now();
+            if !store.pending.contains(&request.media_id) || now >= deadline {
+                break;
+            }
+            store = cvar
+                .wait_timeout(store, deadline - now)
+                .unwrap_or_else(|e| e.into_inner())
+                .0;
+        }
+
+        let target = store
+            .target
+            .context("Decode target not set before first decode")?;
+        drop(store);
+
+        tracing::debug!("Decode miss for {}, decoding inline", request.media_id);
+        let media = Arc::new(decode_media(request, target));
+
+        let mut store = lock.lock().unwrap_or_else(|e| e.into_inner());
+        if store.target == Some(target) {
+            store.insert(request.clone(), media.clone(), self.budget_bytes);
+        }
+        Ok(media)
+    }
+}
+
+fn worker_loop(
+    rx: Arc<Mutex<mpsc::Receiver<DecodeRequest>>>,
+    shared: Arc<(Mutex<Store>, Condvar)>,
+    budget_bytes: usize,
+) {
+    // Decoding is throughput work; let the render thread win any contention
+    // for a core. Linux applies nice values per thread.
+    #[cfg(target_os = "linux")]
+    unsafe {
+        libc::setpriority(libc::PRIO_PROCESS, libc::gettid() as libc::id_t, 10);
+    }
+
+    loop {
+        let request = {
+            let rx = rx.lock().unwrap_or_else(|e| e.into_inner());
+            match rx.recv() {
+                Ok(r) => r,
+                Err(_) => return,
+            }
+        };
+
+        let (lock, cvar) = &*shared;
+        let target = lock.lock().unwrap_or_else(|e| e.into_inner()).target;
+        let decoded = target.map(|t| (t, Arc::new(decode_media(&request, t))));
+
+        let mut store = lock.lock().unwrap_or_else(|e| e.into_inner());
+        store.pending.remove(&request.media_id);
+        if let Some((t, media)) = decoded {
+            if store.target == Some(t) {
+                tracing::debug!(
+                    "Decoded {} ahead ({:.1} MB buffered)",
+                    request.media_id,
+                    (store.used_bytes + media.bytes()) as f64 / 1024.0 / 1024.0
+                );
+                store.insert(request, media, budget_bytes);
+            }
+        }
+        cvar.notify_all();
+    }
+}
+
+/// Decode every part of a request, logging (not returning) per-part failures
+/// so a broken backdrop never costs us the photo itself.
+fn decode_media(request: &DecodeRequest, target: DecodeTarget) -> DecodedMedia {
+    let display = request
+        .display
+        .as_deref()
+        .and_then(|path| match decode_display(path, target) {
+            Ok(img) => Some(img),
+            Err(e) => {
+                let dims = image::image_dimensions(path)
+                    .map(|(w, h)| format!("{}x{}", w, h))
+                    .unwrap_or_else(|_| "unknown".to_string());
+                tracing::warn!(
+                    "Failed to decode display image ({:?}, {}): {}",
+                    path,
+                    dims,
+                    e
+                );
+                None
+            }
+        });
+
+    let blur = request.backdrop.as_ref().and_then(|backdrop| {
+        let result = match backdrop {
+            Backdrop::Ready(path) => decode_plain(path, target),
+            Backdrop::Generate(path) => {
+                let t = Instant::now();
+                let blurred = decode_blurred(path);
+                tracing::debug!("Generated Gaussian blur in {:?}", t.elapsed());
+                blurred
+            }
+        };
+        result
+            .map_err(|e| tracing::warn!("Failed to decode backdrop {:?}: {}", backdrop, e))
+            .ok()
+    });
+
+    DecodedMedia { display, blur }
+}
+
+fn open_image(path: &Path) -> Result<image::DynamicImage> {
+    image::ImageReader::open(path)
+        .context("Failed to open image")?
+        .with_guessed_format()
+        .context("Failed to guess image format")?
+        .decode()
+        .context("Failed to decode image")
+}
+
+/// Decode a display image at no more than screen size. The image is kept
+/// just large enough to cover the screen, so cover-fit panels and Ken Burns
+/// crops never upscale, and never beyond what the GPU can texture — an
+/// oversized texture fails to create on some GPUs (Raspberry Pi 4's
+/// VideoCore VI), leaving only the backdrop visible.
+fn decode_display(path: &Path, target: DecodeTarget) -> Result<DecodedImage> {
+    let img = open_image(path)?;
+    let (w, h) = (img.width().max(1), img.height().max(1));
+
+    let cover = (target.screen_width as f32 / w as f32).max(target.screen_height as f32 / h as f32);
+    let gpu_cap = target.max_texture_dim as f32 / w.max(h) as f32;
+    let scale = cover.min(gpu_cap);
+    if scale >= 1.0 {
+        return Ok(DecodedImage::from_rgba(img.into_rgba8()));
+    }
+
+    let new_w = ((w as f32 * scale).round() as u32).max(1);
+    let new_h = ((h as f32 * scale).round() as u32).max(1);
+    tracing::debug!(
+        "Downscaling {} from {}x{} to {}x{}",
+        path.display(),
+        w,
+        h,
+        new_w,
+        new_h
+    );
+    Ok(DecodedImage::from_rgba(imageops::resize(
+        &img,
+        new_w,
+        new_h,
+        FilterType::Triangle,
+    )))
+}
+
+/// Decode an image as is (server backdrops are already small), only capped
+/// to the GPU's texture limit.
+fn decode_plain(path: &Path, target: DecodeTarget) -> Result<DecodedImage> {
+    let img = open_image(path)?;
+    if img.width().max(img.height()) <= target.max_texture_dim {
+        return Ok(DecodedImage::from_rgba(img.into_rgba8()));
+    }
+    Ok(DecodedImage::from_rgba(imageops::resize(
+        &img,
+        BLUR_WIDTH,
+        BLUR_HEIGHT,
+        FilterType::Triangle,
+    )))
+}
+
+/// Generate a Gaussian-blurred backdrop on the CPU: stretch to 480x270 to
+/// strip fine detail, then a real Gaussian at σ=8 — clean, with no visible
+/// pattern or banding. The blurred image has no detail left, so the GPU's
+/// linear filtering stretches it to the screen without artifacts.
+fn decode_blurred(path: &Path) -> Result<DecodedImage> {
+    let img = open_image(path)?;
+    let small = imageops::resize(&img, BLUR_WIDTH, BLUR_HEIGHT, FilterType::Triangle);
+    Ok(DecodedImage::from_rgba(imageops::blur(&small, BLUR_SIGMA)))
+}
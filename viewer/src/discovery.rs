@@ -5,6 +5,9 @@
 //!   2. Announces itself to the backend every 15 seconds
 //!   3. Polls the backend for registration confirmation every 5 seconds
 //!   4. On claim: writes device_id + api_key to config.toml and exits
+//!
+//! Once registered, the viewer logs in with those credentials through
+//! `device_auth`.
 
 use anyhow::Result;
 use rand::Rng;
@@ -81,6 +84,30 @@ fn read_hostname() -> String {
         .to_string()
 }
 
+/// Response from POST /api/spomienka/device-auth.
+#[derive(Deserialize)]
+pub struct DeviceAuthResponse {
+    pub token: String,
+    pub config: serde_json::Value,
+}
+
+/// Authenticate as a device using device_id + device_api_key.
+/// Updates lastSeen on the backend and returns device config.
+pub async fn device_auth(
+    client: &Client,
+    pb_url: &str,
+    device_id: &str,
+    api_key: &str,
+) -> Result<DeviceAuthResponse> {
+    let res = client
+        .post(format!("{}/api/spomienka/device-auth", pb_url))
+        .json(&serde_json::json!({ "device_id": device_id, "api_key": api_key }))
+        .send()
+        .await?
+        .error_for_status()?;
+    Ok(res.json::<DeviceAuthResponse>().await?)
+}
+
 pub async fn announce(client: &Client, pb_url: &str, state: &DiscoveryState) -> Result<()> {
     let url = format!("{}/api/spomienka/announce", pb_url);
     let res = client
@@ -3,27 +3,42 @@
 //! Displays published media from PocketBase with transitions, caching, and realtime sync.
 
 mod assets;
+mod brightness;
 mod cache;
+mod commands;
+mod decode;
 mod discovery;
+mod power;
 mod realtime;
 mod renderer;
+mod schedule;
+mod sync;
+mod throttle;
 mod video;
 
 use anyhow::{Context, Result};
 use assets::{AssetManager, AssetType, Media, Preloader};
-use cache::Cache;
+use brightness::{Shade, ShadeCurve, ShadePoint};
+use cache::{Cache, CacheStatus};
+use commands::{AckStatus, CommandAcks, CommandState};
 use config::{Config, Environment, File};
-use realtime::{spawn_realtime, RealtimeEvent};
+use decode::DecodePool;
+use power::DisplayPower;
+use realtime::{spawn_realtime, RealtimeEvent, RealtimeStatus};
 use renderer::{
-    MediaInfoOverlay, MediaTextures, OverlayInfo, Renderer, SlideLayout, SlideLayoutKind,
-    Transition, UserAction,
+    ClockStyle, Easing, MediaInfoOverlay, MediaTextures, OverlayInfo, Renderer, Rotation,
+    SlideLayout, SlideLayoutKind, Transition, TransitionSet, UserAction,
 };
 use reqwest::{Client, StatusCode};
+use schedule::{QuietException, QuietMode, QuietSchedule, TimeWindow};
+use serde::de::DeserializeOwned;
 use serde::Deserialize;
-use std::collections::VecDeque;
+use std::collections::{HashMap, VecDeque};
 use std::env;
-use std::sync::Arc;
+use std::sync::{Arc, OnceLock};
 use std::time::{Duration, Instant};
+use sync::{SyncDaemon, SyncProgress, SyncSettings};
+use throttle::{DownloadLimits, Throttle};
 use tokio::sync::RwLock;
 use tracing_subscriber::{fmt, prelude::*, EnvFilter};
 use video::VideoManager;
@@ -32,7 +47,7 @@ use video::VideoManager;
 ///
 /// SECURITY: Auth credentials (auth_email, auth_password) are ONLY loaded from
 /// environment variables, never from config files, to prevent credential leakage.
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Deserialize)]
 struct AppConfig {
     /// PocketBase API URL (env: POCKETBASE_URL or config: pb_url)
     #[serde(default = "default_pb_url")]
@@ -42,10 +57,28 @@ struct AppConfig {
     #[serde(default = "default_interval_ms")]
     interval_ms: u64,
 
-    /// Transition type: fade, crossfade, cut
+    /// Transition type: fade, crossfade, cut, slide-<dir>, push-<dir>,
+    /// wipe-<dir> (dir: left, right, up, down) or wipe-radial
     #[serde(default = "default_transition")]
     transition: String,
 
+    /// Weighted set of transitions to pick from per slide, never the same one
+    /// twice in a row, e.g. `["fade", { name = "slide-left", weight = 3 }]`
+    /// (default: empty — always use `transition`)
+    #[serde(default)]
+    transitions: Vec<WeightedTransition>,
+
+    /// Per-layout overrides of `transitions`, keyed by layout name
+    /// (single, dual_portrait, portrait_dual_landscape, quad_landscape,
+    /// dual_square, square_portrait, dual_landscape, triple_portrait, mosaic)
+    #[serde(default)]
+    layout_transitions: HashMap<String, Vec<WeightedTransition>>,
+
+    /// Transition easing: linear, ease-in, ease-out, ease-in-out
+    /// (default: empty — linear for blends, ease-in-out for moving transitions)
+    #[serde(default)]
+    transition_easing: String,
+
     /// Transition duration in milliseconds
     #[serde(default = "default_transition_duration_ms")]
     transition_duration_ms: u32,
@@ -58,6 +91,25 @@ struct AppConfig {
     #[serde(default = "default_cache_size_limit_gb")]
     cache_size_limit_gb: u64,
 
+    /// Free space to always leave on the cache's filesystem, in MB. The cache
+    /// evicts to stay above it and refuses downloads that would go below.
+    #[serde(default = "default_cache_min_free_mb")]
+    cache_min_free_mb: u64,
+
+    /// Space for pinned media in GB, on top of `cache_size_limit_gb`.
+    /// Pinned media is never evicted while it fits.
+    #[serde(default = "default_pinned_budget_gb")]
+    pinned_budget_gb: u64,
+
+    /// Media carrying any of these tags is pinned in the offline cache
+    /// (default: "favorite", "pinned").
+    #[serde(default = "default_pinned_tags")]
+    pinned_tags: Vec<String>,
+
+    /// Memory for slides decoded ahead of time, in MB (a 1080p image is ~8 MB)
+    #[serde(default = "default_decode_budget_mb")]
+    decode_budget_mb: u64,
+
     /// Optional device ID for device-specific playlisting
     #[serde(default)]
     device_id: Option<String>,
@@ -85,6 +137,12 @@ struct AppConfig {
     #[serde(default = "default_enable_realtime")]
     enable_realtime: bool,
 
+    /// Seconds without any data on the realtime stream, keepalives
+    /// included, before reconnecting (default: 360; PocketBase itself drops
+    /// clients idle for 5 minutes).
+    #[serde(default = "default_realtime_idle_timeout_secs")]
+    realtime_idle_timeout_secs: u64,
+
     /// Shuffle playlist order
     #[serde(default)]
     shuffle: bool,
@@ -93,14 +151,67 @@ struct AppConfig {
     #[serde(default)]
     full_sync: bool,
 
+    /// Minutes between full playlist fetches; fetches in between only ask
+    /// for records changed since the last one (default: 60).
+    #[serde(default = "default_playlist_full_refresh_mins")]
+    playlist_full_refresh_mins: u64,
+
+    /// Keep fetching the rest of the playlist in the background, in play
+    /// order, within the cache budget (default: true).
+    #[serde(default = "default_background_sync")]
+    background_sync: bool,
+
+    /// Seconds between background sync passes; playlist changes start one
+    /// sooner (default: 300).
+    #[serde(default = "default_sync_interval_secs")]
+    sync_interval_secs: u64,
+
+    /// Local time range background sync may download in, "HH:MM-HH:MM"
+    /// (default: empty, any time).
+    #[serde(default)]
+    sync_hours: String,
+
+    /// Downloads allowed in flight at once (default: 2).
+    #[serde(default = "default_download_concurrency")]
+    download_concurrency: usize,
+
+    /// Sustained rate for all downloads together, in KB/s (default: 0,
+    /// unlimited).
+    #[serde(default)]
+    download_rate_kb_per_sec: u64,
+
+    /// KB that may go through at full speed after an idle spell (default:
+    /// 0, one second's worth of `download_rate_kb_per_sec`).
+    #[serde(default)]
+    download_burst_kb: u64,
+
+    /// Metered connection: fetch images as usual but leave video until
+    /// `off_peak_hours` (default: false).
+    #[serde(default)]
+    metered: bool,
+
+    /// Local time range a metered connection is cheap in, "HH:MM-HH:MM"
+    /// (default: empty, video is never fetched while metered).
+    #[serde(default)]
+    off_peak_hours: String,
+
     /// Run in fullscreen mode (default: true). Set to false for windowed dev mode.
     #[serde(default = "default_fullscreen")]
     fullscreen: bool,
 
+    /// Degrees to turn the picture clockwise, for a panel mounted on its
+    /// side or upside down: 0, 90, 180 or 270 (default: 0).
+    #[serde(default)]
+    rotation: u32,
+
     /// Show blurred background behind images (default: true).
     #[serde(default = "default_blur_background")]
     pub blur_background: bool,
 
+    /// Slowly pan and zoom still images while they're shown (default: false).
+    #[serde(default)]
+    pub ken_burns: bool,
+
     /// Show a subtle clock in the bottom-right corner (default: true).
     #[serde(default = "default_show_clock")]
     pub show_clock: bool,
@@ -115,6 +226,20 @@ struct AppConfig {
     #[serde(default)]
     pub clock_offset_y: i32,
 
+    /// Clock hour format: "12h" or "24h" (default: "12h").
+    #[serde(default = "default_clock_format")]
+    pub clock_format: String,
+
+    /// strftime pattern for a date line under the clock, e.g. "%A, %d %B"
+    /// (default: empty, no date line).
+    #[serde(default)]
+    pub clock_date_format: String,
+
+    /// IANA time zone for the clock, e.g. "Europe/Bratislava", overriding
+    /// the system zone (default: empty, system zone).
+    #[serde(default)]
+    pub clock_timezone: String,
+
     /// Start with the full media info overlay visible (default: false).
     #[serde(default)]
     pub show_info: bool,
@@ -126,6 +251,100 @@ struct AppConfig {
     /// Display layout mode: "single", "dynamic", or "portrait_pairs" (default: "single").
     #[serde(default = "default_display_mode")]
     pub display_mode: String,
+
+    /// What quiet hours show: "off", "blank" or "clock" (a dim clock on
+    /// black) (default: "off").
+    #[serde(default = "default_quiet_mode")]
+    pub quiet_mode: String,
+
+    /// Quiet hours starting on each weekday, keyed "mon".."sun", e.g.
+    /// `fri = "23:30-08:00"`; a window may run past midnight (default:
+    /// none).
+    #[serde(default)]
+    pub quiet_hours: HashMap<String, String>,
+
+    /// Dates whose quiet hours differ from their weekday's (default: none).
+    #[serde(default)]
+    pub quiet_exceptions: Vec<QuietException>,
+
+    /// How to switch the display off in blank quiet hours: "none", "noop",
+    /// "vcgencmd", "xset", "wlr-randr" or "command" (default: "none").
+    #[serde(default = "default_display_power")]
+    display_power: String,
+
+    /// Command turning the display on, for `display_power = "command"` or
+    /// in place of a preset's (default: empty).
+    #[serde(default)]
+    display_on_command: String,
+
+    /// Command turning the display off, likewise (default: empty).
+    #[serde(default)]
+    display_off_command: String,
+
+    /// Brightness and warmth over the day: points at "HH:MM",
+    /// "sunrise[±min]" or "sunset[±min]", faded between (default: none,
+    /// always full brightness).
+    #[serde(default)]
+    pub dimming: Vec<ShadePoint>,
+
+    /// Where the frame is, for sunrise/sunset dimming points; degrees, north
+    /// and east positive (default: unset).
+    #[serde(default)]
+    pub latitude: Option<f64>,
+    #[serde(default)]
+    pub longitude: Option<f64>,
+
+    /// Burn-in protection: how many pixels the whole picture (media, clock,
+    /// overlays) slowly drifts around its resting position (default: 0, off).
+    #[serde(default)]
+    pub burn_in_shift: u32,
+
+    /// Minutes between full-screen refresh patterns during quiet hours,
+    /// while the display stays lit (default: 0, off).
+    #[serde(default)]
+    pub burn_in_refresh_mins: u64,
+
+    /// Longest a single image may stay on screen, in seconds, even while
+    /// paused or held by a `show` command (default: 0, no cap).
+    #[serde(default)]
+    pub max_static_secs: u64,
+}
+
+/// One entry of a weighted transition set: a bare name (weight 1) or
+/// `{ name, weight }`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+enum WeightedTransition {
+    Name(String),
+    Weighted {
+        name: String,
+        #[serde(default = "default_transition_weight")]
+        weight: u32,
+    },
+}
+
+fn default_transition_weight() -> u32 {
+    1
+}
+
+/// Build a renderer `TransitionSet`, dropping names the renderer doesn't know.
+fn to_transition_set(list: &[WeightedTransition]) -> TransitionSet {
+    let entries = list
+        .iter()
+        .filter_map(|entry| {
+            let (name, weight) = match entry {
+                WeightedTransition::Name(name) => (name.as_str(), 1),
+                WeightedTransition::Weighted { name, weight } => (name.as_str(), *weight),
+            };
+            let transition = Transition::from_str(name);
+            if transition == Transition::Cut && !name.trim().eq_ignore_ascii_case("cut") {
+                tracing::warn!("Ignoring unknown transition {:?} in transition set", name);
+                return None;
+            }
+            Some((transition, weight))
+        })
+        .collect();
+    TransitionSet::new(entries)
 }
 
 fn default_pb_url() -> String {
@@ -152,6 +371,46 @@ fn default_cache_size_limit_gb() -> u64 {
     10
 }
 
+fn default_playlist_full_refresh_mins() -> u64 {
+    60
+}
+
+fn default_background_sync() -> bool {
+    true
+}
+
+fn default_sync_interval_secs() -> u64 {
+    300
+}
+
+fn default_download_concurrency() -> usize {
+    2
+}
+
+fn default_pinned_budget_gb() -> u64 {
+    2
+}
+
+fn default_pinned_tags() -> Vec<String> {
+    vec!["favorite".to_string(), "pinned".to_string()]
+}
+
+fn default_cache_min_free_mb() -> u64 {
+    1024
+}
+
+fn default_decode_budget_mb() -> u64 {
+    128
+}
+
+/// Decode worker threads. Two keep a few slides ahead on a Pi 4 while
+/// leaving cores for the render loop and video decoding.
+const DECODE_WORKERS: usize = 2;
+
+fn default_realtime_idle_timeout_secs() -> u64 {
+    360
+}
+
 fn default_enable_realtime() -> bool {
     true
 }
@@ -168,10 +427,22 @@ fn default_show_clock() -> bool {
     true
 }
 
+fn default_clock_format() -> String {
+    "12h".to_string()
+}
+
 fn default_display_mode() -> String {
     "single".to_string()
 }
 
+fn default_quiet_mode() -> String {
+    "off".to_string()
+}
+
+fn default_display_power() -> String {
+    "none".to_string()
+}
+
 impl AppConfig {
     /// Load configuration from file and environment variables.
     ///
@@ -220,6 +491,74 @@ impl AppConfig {
         Ok(app_config)
     }
 
+    fn transition_sets(&self) -> (TransitionSet, HashMap<SlideLayoutKind, TransitionSet>) {
+        let per_layout = self
+            .layout_transitions
+            .iter()
+            .filter_map(
+                |(name, list)| match SlideLayoutKind::from_config_name(name) {
+                    Some(kind) => Some((kind, to_transition_set(list))),
+                    None => {
+                        tracing::warn!("Ignoring transitions for unknown layout {:?}", name);
+                        None
+                    }
+                },
+            )
+            .collect();
+        (to_transition_set(&self.transitions), per_layout)
+    }
+
+    fn clock_style(&self) -> ClockStyle {
+        let non_empty = |s: &str| Some(s.trim().to_string()).filter(|s| !s.is_empty());
+        ClockStyle {
+            use_24h: self.clock_format.eq_ignore_ascii_case("24h"),
+            date_format: non_empty(&self.clock_date_format),
+            timezone: non_empty(&self.clock_timezone).and_then(|name| {
+                let tz = name.parse::<chrono_tz::Tz>().ok();
+                if tz.is_none() {
+                    tracing::warn!("Unknown clock timezone {:?}, using the system zone", name);
+                }
+                tz
+            }),
+        }
+    }
+
+    /// Quiet hours, unless `quiet_mode` is off.
+    fn quiet_hours(&self) -> Option<(QuietMode, QuietSchedule)> {
+        let mode = QuietMode::from_setting(&self.quiet_mode)?;
+        Some((
+            mode,
+            QuietSchedule::from_settings(&self.quiet_hours, &self.quiet_exceptions),
+        ))
+    }
+
+    /// The picture's rotation; anything but a quarter-turn multiple is
+    /// ignored.
+    fn rotation(&self) -> Rotation {
+        Rotation::from_degrees(self.rotation).unwrap_or_else(|| {
+            tracing::warn!(
+                "Ignoring rotation = {} (want 0, 90, 180 or 270)",
+                self.rotation
+            );
+            Rotation::None
+        })
+    }
+
+    /// Time between quiet-hours refresh patterns, if they're on.
+    fn burn_in_refresh(&self) -> Option<Duration> {
+        (self.burn_in_refresh_mins > 0).then(|| Duration::from_secs(self.burn_in_refresh_mins * 60))
+    }
+
+    /// Cap on how long one image stays up, if set.
+    fn max_static(&self) -> Option<Duration> {
+        (self.max_static_secs > 0).then(|| Duration::from_secs(self.max_static_secs))
+    }
+
+    /// The dimming curve, if any points are usable.
+    fn shade_curve(&self) -> Option<ShadeCurve> {
+        ShadeCurve::from_settings(&self.dimming, self.latitude, self.longitude)
+    }
+
     fn to_auth_creds(&self) -> AuthCreds {
         AuthCreds {
             token: self.auth_token.clone().filter(|s| !s.is_empty()),
@@ -247,49 +586,204 @@ struct ListResponse<T> {
     items: Vec<T>,
     #[serde(rename = "totalPages")]
     total_pages: u32,
+    #[serde(rename = "totalItems", default)]
+    total_items: u32,
+}
+
+/// A page of a list request, or word that it's unchanged.
+enum ListPage<T> {
+    Fresh(ListResponse<T>, Validators),
+    NotModified,
+}
+
+/// ETag / Last-Modified of a list response, sent back as If-None-Match /
+/// If-Modified-Since the next time the same URL is fetched. PocketBase's
+/// record API doesn't send these itself, but a caching proxy in front of it
+/// may.
+#[derive(Debug, Clone, Default)]
+struct Validators {
+    etag: Option<String>,
+    last_modified: Option<String>,
+}
+
+impl Validators {
+    fn is_empty(&self) -> bool {
+        self.etag.is_none() && self.last_modified.is_none()
+    }
+}
+
+/// Just the id and `updated` stamp of a media record.
+#[derive(Debug, Deserialize)]
+struct RecordStamp {
+    id: String,
+    updated: Option<String>,
+}
+
+/// Where the last playlist fetch left off, so the next one can ask only for
+/// records changed since.
+#[derive(Debug)]
+struct PlaylistCursor {
+    /// Filter the playlist was fetched with; a different one needs a full
+    /// fetch.
+    filter: String,
+    /// Newest `updated` stamp seen.
+    newest_updated: Option<String>,
+    /// When the last full fetch was.
+    full_at: Instant,
+    /// Validators of the last delta queries, by URL.
+    validators: HashMap<String, Validators>,
+}
+
+/// The newest of `current` and `stamps`. PocketBase stamps are fixed-width
+/// UTC ("2006-01-02 15:04:05.000Z"), so they compare as strings.
+fn newest_stamp<'a>(
+    current: Option<&'a str>,
+    stamps: impl Iterator<Item = Option<&'a str>>,
+) -> Option<String> {
+    stamps.flatten().chain(current).max().map(str::to_string)
 }
 
 /// Application state shared across tasks.
 struct AppState {
+    /// The config the viewer started with. Settings that can't change
+    /// without a restart (see `restart_required`) are read from here.
     config: AppConfig,
+    /// The config as last applied, including admin-controlled settings
+    /// changed since startup. Read display settings from here.
+    live_config: RwLock<AppConfig>,
     client: Client,
+    /// For asset downloads: like `client`, but without the overall timeout.
+    download_client: Client,
     auth_token: RwLock<Option<String>>,
     playlist: RwLock<Vec<Media>>,
     current_index: RwLock<usize>,
     cache: Arc<RwLock<Cache>>,
+    /// Cache stats and download progress, readable without the cache lock.
+    cache_status: Arc<std::sync::Mutex<CacheStatus>>,
     asset_manager: Arc<AssetManager>,
     is_offline: RwLock<bool>,
     /// Active tag filter: (tags, mode) where mode is "whitelist" or "blacklist".
     tag_filter: RwLock<Option<(Vec<String>, String)>>,
+    /// Where the last playlist fetch left off (see `fetch_playlist`).
+    playlist_cursor: RwLock<Option<PlaylistCursor>>,
     /// Media ids shown most recently, oldest first. Used by dynamic-layout
     /// reordering to avoid immediately re-showing an image right after a
     /// cycle boundary reshuffle.
     recent_shown: RwLock<VecDeque<String>>,
+    /// Background cache sync (see `run_background_sync`).
+    sync: SyncDaemon,
+    /// Realtime connection state, published by the realtime task.
+    realtime_status: Arc<std::sync::Mutex<RealtimeStatus>>,
+    /// Acknowledgments for remote commands.
+    acks: Arc<CommandAcks>,
+    /// The picture's shape (after rotation), which changes the layouts
+    /// multi-panel modes group images for. Set once the renderer is up.
+    screen_shape: OnceLock<ScreenShape>,
+}
+
+/// The shape of the picture, as far as picking layouts goes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ScreenShape {
+    Landscape,
+    /// Taller than wide.
+    Portrait,
+    /// At least twice as wide as tall, e.g. 21:9 ultrawides.
+    Wide,
+}
+
+impl ScreenShape {
+    fn of(width: u32, height: u32) -> Self {
+        if height > width {
+            ScreenShape::Portrait
+        } else if width >= 2 * height {
+            ScreenShape::Wide
+        } else {
+            ScreenShape::Landscape
+        }
+    }
 }
 
 impl AppState {
+    fn screen_shape(&self) -> ScreenShape {
+        self.screen_shape
+            .get()
+            .copied()
+            .unwrap_or(ScreenShape::Landscape)
+    }
+
     async fn new(config: AppConfig) -> Result<Self> {
         let client = Client::builder()
             .timeout(Duration::from_secs(30))
             .build()
             .context("Failed to create HTTP client")?;
-
-        let cache = Cache::new(config.cache_dir.clone().into(), config.cache_size_limit_gb)?;
+        // Asset downloads can run for many minutes on a slow link, so they
+        // get no overall deadline — only limits on connecting and on the
+        // connection going quiet.
+        let download_client = Client::builder()
+            .connect_timeout(Duration::from_secs(30))
+            .read_timeout(Duration::from_secs(60))
+            .build()
+            .context("Failed to create download client")?;
+
+        let cache = Cache::new(
+            config.cache_dir.clone().into(),
+            config.cache_size_limit_gb,
+            config.pinned_budget_gb,
+            config.cache_min_free_mb,
+        )?;
+        let cache_status = cache.status_handle();
         let cache = Arc::new(RwLock::new(cache));
 
-        let asset_manager = Arc::new(AssetManager::new(cache.clone(), config.pb_url.clone()));
+        let decoder = Arc::new(DecodePool::new(DECODE_WORKERS, config.decode_budget_mb));
+        let throttle = Arc::new(Throttle::new(DownloadLimits {
+            max_concurrent: config.download_concurrency,
+            bytes_per_sec: config.download_rate_kb_per_sec * 1024,
+            burst_bytes: config.download_burst_kb * 1024,
+            metered: config.metered,
+            off_peak: TimeWindow::from_setting("off_peak_hours", &config.off_peak_hours),
+        }));
+        let asset_manager = Arc::new(AssetManager::new(
+            cache.clone(),
+            config.pb_url.clone(),
+            decoder,
+            throttle,
+        ));
+
+        let sync = SyncDaemon::new(
+            asset_manager.clone(),
+            download_client.clone(),
+            SyncSettings {
+                interval: Duration::from_secs(config.sync_interval_secs.max(10)),
+                window: TimeWindow::from_setting("sync_hours", &config.sync_hours),
+            },
+            cache_status.clone(),
+        );
+
+        let acks = Arc::new(CommandAcks::new(
+            config.pb_url.clone(),
+            config.device_id.clone(),
+            config.device_api_key.clone(),
+        ));
 
         Ok(Self {
+            live_config: RwLock::new(config.clone()),
             config,
             client,
+            download_client,
             auth_token: RwLock::new(None),
             playlist: RwLock::new(Vec::new()),
             current_index: RwLock::new(0),
             cache,
+            cache_status,
             asset_manager,
             is_offline: RwLock::new(false),
             tag_filter: RwLock::new(None),
+            playlist_cursor: RwLock::new(None),
             recent_shown: RwLock::new(VecDeque::new()),
+            sync,
+            realtime_status: Arc::default(),
+            acks,
+            screen_shape: OnceLock::new(),
         })
     }
 
@@ -317,7 +811,7 @@ impl AppState {
     async fn preload_media_safe(&self, media: &Media) -> Result<()> {
         let token = self.token().await;
         self.asset_manager
-            .preload_media(media, &self.client, token.as_deref())
+            .preload_media(media, &self.download_client, token.as_deref())
             .await
     }
 
@@ -329,31 +823,23 @@ impl AppState {
 
         // Build filter with device scope and optional tag filter
         let filter = self.build_filter().await;
-        let encoded_filter = urlencoding::encode(&filter);
 
-        let result = async {
-            let mut media = Vec::new();
-            let mut page = 1u32;
-            loop {
-                let url = format!(
-                    "{}/api/collections/media/records?filter={}&perPage=500&sort=-created&page={}",
-                    self.config.pb_url, encoded_filter, page
-                );
-                let mut response = self.fetch_with_retry(&url, &mut token, &creds).await?;
-                let total_pages = response.total_pages;
-                media.append(&mut response.items);
+        // Only ask for what changed since the last fetch, unless the filter
+        // changed or a periodic full fetch is due.
+        let mut cursor = self.playlist_cursor.write().await;
+        let full_every = Duration::from_secs(self.config.playlist_full_refresh_mins.max(1) * 60);
+        let delta_from = cursor.as_ref().filter(|c| {
+            c.filter == filter && c.newest_updated.is_some() && c.full_at.elapsed() < full_every
+        });
 
-                if page >= total_pages {
-                    break;
-                }
-                page += 1;
-            }
-            Ok(media)
-        }
-        .await;
+        let result = match delta_from {
+            Some(prev) => self.fetch_playlist_delta(prev, &mut token, &creds).await,
+            None => self.fetch_playlist_all(&filter, &mut token, &creds).await,
+        };
 
         match result {
-            Ok(media) => {
+            Ok((media, next)) => {
+                *cursor = Some(next);
                 *self.is_offline.write().await = false;
                 Ok(media)
             }
@@ -374,6 +860,217 @@ impl AppState {
         }
     }
 
+    /// Fetch the whole playlist, skipping the delta fetch this time (e.g. on
+    /// a manual refresh).
+    async fn fetch_playlist_full(&self) -> Result<Vec<Media>> {
+        *self.playlist_cursor.write().await = None;
+        self.fetch_playlist().await
+    }
+
+    /// Fetch every record matching `filter`.
+    async fn fetch_playlist_all(
+        &self,
+        filter: &str,
+        token: &mut Option<String>,
+        creds: &AuthCreds,
+    ) -> Result<(Vec<Media>, PlaylistCursor)> {
+        let query = format!("filter={}", urlencoding::encode(filter));
+        let media: Vec<Media> = self
+            .fetch_pages(&query, None, &mut HashMap::new(), token, creds)
+            .await?
+            .unwrap_or_default();
+        tracing::debug!("Fetched full playlist: {} items", media.len());
+        let cursor = PlaylistCursor {
+            filter: filter.to_string(),
+            newest_updated: newest_stamp(None, media.iter().map(|m| m.updated.as_deref())),
+            full_at: Instant::now(),
+            validators: HashMap::new(),
+        };
+        Ok((media, cursor))
+    }
+
+    /// Apply the records changed since `prev` to the current playlist:
+    /// update or add those still matching the filter, and drop those that no
+    /// longer do (rescoped, retagged). Unpublished and deleted records are
+    /// invisible to a device token, so they can't be listed; if the playlist
+    /// ends up longer than what the filter now matches, this falls back to a
+    /// full fetch.
+    async fn fetch_playlist_delta(
+        &self,
+        prev: &PlaylistCursor,
+        token: &mut Option<String>,
+        creds: &AuthCreds,
+    ) -> Result<(Vec<Media>, PlaylistCursor)> {
+        // `>=` rather than `>`: records saved in the same millisecond as the
+        // newest one seen may not all have made it into the last fetch.
+        // Fetching one twice is harmless.
+        let since = prev
+            .newest_updated
+            .as_deref()
+            .unwrap_or_default()
+            .replace('\'', "\\'");
+        let changed_query = format!(
+            "filter={}",
+            urlencoding::encode(&format!("({}) && updated >= '{}'", prev.filter, since))
+        );
+        let removed_query = format!(
+            "filter={}&fields=id,updated",
+            urlencoding::encode(&format!("!({}) && updated >= '{}'", prev.filter, since))
+        );
+
+        let mut validators = HashMap::new();
+        let changed: Vec<Media> = self
+            .fetch_pages(
+                &changed_query,
+                Some(&prev.validators),
+                &mut validators,
+                token,
+                creds,
+            )
+            .await?
+            .unwrap_or_default();
+        let removed: Vec<RecordStamp> = self
+            .fetch_pages(
+                &removed_query,
+                Some(&prev.validators),
+                &mut validators,
+                token,
+                creds,
+            )
+            .await?
+            .unwrap_or_default();
+
+        let mut playlist = self.playlist.read().await.clone();
+        let removed_ids: std::collections::HashSet<&str> =
+            removed.iter().map(|r| r.id.as_str()).collect();
+        let before = playlist.len();
+        playlist.retain(|m| !removed_ids.contains(m.id.as_str()));
+        let dropped = before - playlist.len();
+
+        let newest = newest_stamp(
+            prev.newest_updated.as_deref(),
+            changed
+                .iter()
+                .map(|m| m.updated.as_deref())
+                .chain(removed.iter().map(|r| r.updated.as_deref())),
+        );
+        let updated = changed.len();
+        for media in changed {
+            if let Some(pos) = playlist.iter().position(|m| m.id == media.id) {
+                playlist[pos] = media;
+            } else {
+                playlist.push(media);
+            }
+        }
+        tracing::debug!(
+            "Fetched playlist changes: {} updated, {} removed",
+            updated,
+            dropped
+        );
+
+        let matching = self.count_matching(&prev.filter, token, creds).await?;
+        if matching != playlist.len() {
+            tracing::debug!(
+                "Playlist has {} items but {} match; fetching in full",
+                playlist.len(),
+                matching
+            );
+            return self.fetch_playlist_all(&prev.filter, token, creds).await;
+        }
+
+        let cursor = PlaylistCursor {
+            filter: prev.filter.clone(),
+            newest_updated: newest,
+            full_at: prev.full_at,
+            validators,
+        };
+        Ok((playlist, cursor))
+    }
+
+    /// Fetch every page of a media list query (`filter=...` and any other
+    /// parameters). Returns `None` if the server says the first page hasn't
+    /// changed since the validators in `known` were recorded. The first
+    /// page's validators are added to `seen` either way.
+    async fn fetch_pages<T: DeserializeOwned>(
+        &self,
+        query: &str,
+        known: Option<&HashMap<String, Validators>>,
+        seen: &mut HashMap<String, Validators>,
+        token: &mut Option<String>,
+        creds: &AuthCreds,
+    ) -> Result<Option<Vec<T>>> {
+        let mut items = Vec::new();
+        let mut page = 1u32;
+        loop {
+            let url = format!(
+                "{}/api/collections/media/records?{}&perPage=500&sort=-created&page={}",
+                self.config.pb_url, query, page
+            );
+            let known_page = known.filter(|_| page == 1).and_then(|k| k.get(&url));
+            match self
+                .fetch_with_retry(&url, token, creds, known_page)
+                .await?
+            {
+                ListPage::NotModified => {
+                    if let Some(v) = known_page {
+                        seen.insert(url, v.clone());
+                    }
+                    return Ok(None);
+                }
+                ListPage::Fresh(mut response, validators) => {
+                    if page == 1 && !validators.is_empty() {
+                        seen.insert(url, validators);
+                    }
+                    items.append(&mut response.items);
+                    if page >= response.total_pages {
+                        break;
+                    }
+                    page += 1;
+                }
+            }
+        }
+        Ok(Some(items))
+    }
+
+    /// Count the media records matching `filter`, without fetching them.
+    async fn count_matching(
+        &self,
+        filter: &str,
+        token: &mut Option<String>,
+        creds: &AuthCreds,
+    ) -> Result<usize> {
+        let url = format!(
+            "{}/api/collections/media/records?filter={}&fields=id&perPage=1",
+            self.config.pb_url,
+            urlencoding::encode(filter)
+        );
+        match self
+            .fetch_with_retry::<RecordStamp>(&url, token, creds, None)
+            .await?
+        {
+            ListPage::Fresh(response, _) => Ok(response.total_items as usize),
+            ListPage::NotModified => anyhow::bail!("Unexpected 304 for an unconditional request"),
+        }
+    }
+
+    /// Fetch a single media record by id (published ones only, unless the
+    /// token is an admin's).
+    async fn fetch_media(&self, id: &str) -> Result<Media> {
+        let url = format!(
+            "{}/api/collections/media/records/{}",
+            self.config.pb_url,
+            urlencoding::encode(id)
+        );
+        let token = self.token().await;
+        let (status, res) = self.send_request(&url, token.as_deref(), None).await?;
+        match status {
+            StatusCode::UNAUTHORIZED => anyhow::bail!("Unauthorized"),
+            StatusCode::NOT_FOUND => anyhow::bail!("media not found"),
+            _ => {}
+        }
+        Ok(res.error_for_status()?.json().await?)
+    }
+
     /// Build the filter string for media queries.
     async fn build_filter(&self) -> String {
         let mut filter = "status='published'".to_string();
@@ -407,13 +1104,14 @@ impl AppState {
     }
 
     /// Fetch with automatic token refresh on 401.
-    async fn fetch_with_retry(
+    async fn fetch_with_retry<T: DeserializeOwned>(
         &self,
         url: &str,
         token: &mut Option<String>,
         creds: &AuthCreds,
-    ) -> Result<ListResponse<Media>> {
-        let (status, res) = self.send_request(url, token.as_deref()).await?;
+        validators: Option<&Validators>,
+    ) -> Result<ListPage<T>> {
+        let (status, res) = self.send_request(url, token.as_deref(), validators).await?;
 
         if status != StatusCode::UNAUTHORIZED {
             return self.parse_list(res).await;
@@ -422,7 +1120,7 @@ impl AppState {
         // Try to refresh token
         if let Some(new_token) = self.refresh_token(creds).await? {
             *token = Some(new_token.clone());
-            let (_, res) = self.send_request(url, Some(&new_token)).await?;
+            let (_, res) = self.send_request(url, Some(&new_token), validators).await?;
             return self.parse_list(res).await;
         }
 
@@ -435,11 +1133,20 @@ impl AppState {
         &self,
         url: &str,
         token: Option<&str>,
+        validators: Option<&Validators>,
     ) -> Result<(StatusCode, reqwest::Response)> {
         let mut req = self.client.get(url);
         if let Some(token) = token {
             req = req.bearer_auth(token);
         }
+        if let Some(v) = validators {
+            if let Some(etag) = &v.etag {
+                req = req.header(reqwest::header::IF_NONE_MATCH, etag);
+            }
+            if let Some(modified) = &v.last_modified {
+                req = req.header(reqwest::header::IF_MODIFIED_SINCE, modified);
+            }
+        }
         let res = req.send().await?;
         let status = res.status();
         if status == StatusCode::UNAUTHORIZED {
@@ -449,8 +1156,21 @@ impl AppState {
         Ok((status, res))
     }
 
-    async fn parse_list(&self, res: reqwest::Response) -> Result<ListResponse<Media>> {
-        Ok(res.json().await?)
+    async fn parse_list<T: DeserializeOwned>(&self, res: reqwest::Response) -> Result<ListPage<T>> {
+        if res.status() == StatusCode::NOT_MODIFIED {
+            return Ok(ListPage::NotModified);
+        }
+        let header = |name| {
+            res.headers()
+                .get(name)
+                .and_then(|v| v.to_str().ok())
+                .map(str::to_string)
+        };
+        let validators = Validators {
+            etag: header(reqwest::header::ETAG),
+            last_modified: header(reqwest::header::LAST_MODIFIED),
+        };
+        Ok(ListPage::Fresh(res.json().await?, validators))
     }
 
     async fn refresh_token(&self, creds: &AuthCreds) -> Result<Option<String>> {
@@ -521,13 +1241,6 @@ impl AppState {
     }
 }
 
-/// Response from POST /api/spomienka/device-auth.
-#[derive(Deserialize)]
-struct DeviceAuthResponse {
-    token: String,
-    config: serde_json::Value,
-}
-
 /// Telemetry reported on each heartbeat — process uptime/version, host OS, and
 /// lightweight process/system resource usage. Read directly from /proc rather
 /// than pulling in a dependency like `sysinfo`, since Linux/proc is the only
@@ -539,6 +1252,13 @@ struct Telemetry {
     cpu_percent: Option<f64>,
     rss_bytes: Option<u64>,
     mem_available_bytes: Option<u64>,
+    cache_bytes: u64,
+    cache_max_bytes: u64,
+    disk_free_bytes: Option<u64>,
+    disk_total_bytes: Option<u64>,
+    /// Free space on the cache's filesystem is below `cache_min_free_mb`.
+    disk_pressure: bool,
+    sync: SyncProgress,
 }
 
 /// (utime + stime) in clock ticks from /proc/self/stat, or None on any parse failure.
@@ -588,23 +1308,6 @@ fn read_os_version() -> Option<String> {
         .map(|v| v.trim_matches('"').to_string())
 }
 
-/// Authenticate as a device using device_id + device_api_key.
-/// Updates lastSeen on the backend and returns device config.
-async fn device_auth(
-    client: &reqwest::Client,
-    pb_url: &str,
-    device_id: &str,
-    api_key: &str,
-) -> Result<DeviceAuthResponse> {
-    let res = client
-        .post(format!("{}/api/spomienka/device-auth", pb_url))
-        .json(&serde_json::json!({ "device_id": device_id, "api_key": api_key }))
-        .send()
-        .await?
-        .error_for_status()?;
-    Ok(res.json::<DeviceAuthResponse>().await?)
-}
-
 /// Apply the admin-controlled config fields (from a fresh device-auth response,
 /// or a cached one) onto the running `AppConfig`.
 fn apply_device_config(config: &mut AppConfig, cfg: &serde_json::Value) {
@@ -614,6 +1317,21 @@ fn apply_device_config(config: &mut AppConfig, cfg: &serde_json::Value) {
     if let Some(v) = cfg.get("transition").and_then(|v| v.as_str()) {
         config.transition = v.to_string();
     }
+    if let Some(v) = cfg.get("transitions").filter(|v| !v.is_null()) {
+        match serde_json::from_value(v.clone()) {
+            Ok(list) => config.transitions = list,
+            Err(e) => tracing::warn!("Ignoring invalid transitions set: {}", e),
+        }
+    }
+    if let Some(v) = cfg.get("layoutTransitions").filter(|v| !v.is_null()) {
+        match serde_json::from_value(v.clone()) {
+            Ok(map) => config.layout_transitions = map,
+            Err(e) => tracing::warn!("Ignoring invalid layoutTransitions: {}", e),
+        }
+    }
+    if let Some(v) = cfg.get("transitionEasing").and_then(|v| v.as_str()) {
+        config.transition_easing = v.to_string();
+    }
     if let Some(v) = cfg.get("transitionDuration").and_then(|v| v.as_u64()) {
         config.transition_duration_ms = v as u32;
     }
@@ -623,6 +1341,9 @@ fn apply_device_config(config: &mut AppConfig, cfg: &serde_json::Value) {
     if let Some(v) = cfg.get("blur").and_then(|v| v.as_bool()) {
         config.blur_background = v;
     }
+    if let Some(v) = cfg.get("kenBurns").and_then(|v| v.as_bool()) {
+        config.ken_burns = v;
+    }
     if let Some(v) = cfg.get("showClock").and_then(|v| v.as_bool()) {
         config.show_clock = v;
     }
@@ -632,6 +1353,15 @@ fn apply_device_config(config: &mut AppConfig, cfg: &serde_json::Value) {
     if let Some(v) = cfg.get("clockOffsetY").and_then(|v| v.as_i64()) {
         config.clock_offset_y = v as i32;
     }
+    if let Some(v) = cfg.get("clockFormat").and_then(|v| v.as_str()) {
+        config.clock_format = v.to_string();
+    }
+    if let Some(v) = cfg.get("clockDateFormat").and_then(|v| v.as_str()) {
+        config.clock_date_format = v.to_string();
+    }
+    if let Some(v) = cfg.get("clockTimezone").and_then(|v| v.as_str()) {
+        config.clock_timezone = v.to_string();
+    }
     if let Some(v) = cfg.get("showInfo").and_then(|v| v.as_bool()) {
         config.show_info = v;
     }
@@ -641,6 +1371,177 @@ fn apply_device_config(config: &mut AppConfig, cfg: &serde_json::Value) {
     if let Some(v) = cfg.get("displayMode").and_then(|v| v.as_str()) {
         config.display_mode = v.to_string();
     }
+    if let Some(v) = cfg.get("quietMode").and_then(|v| v.as_str()) {
+        config.quiet_mode = v.to_string();
+    }
+    if let Some(v) = cfg.get("quietHours").filter(|v| !v.is_null()) {
+        match serde_json::from_value(v.clone()) {
+            Ok(map) => config.quiet_hours = map,
+            Err(e) => tracing::warn!("Ignoring invalid quietHours: {}", e),
+        }
+    }
+    if let Some(v) = cfg.get("quietExceptions").filter(|v| !v.is_null()) {
+        match serde_json::from_value(v.clone()) {
+            Ok(list) => config.quiet_exceptions = list,
+            Err(e) => tracing::warn!("Ignoring invalid quietExceptions: {}", e),
+        }
+    }
+    if let Some(v) = cfg.get("dimming").filter(|v| !v.is_null()) {
+        match serde_json::from_value(v.clone()) {
+            Ok(points) => config.dimming = points,
+            Err(e) => tracing::warn!("Ignoring invalid dimming: {}", e),
+        }
+    }
+    if let Some(v) = cfg.get("rotation").and_then(|v| v.as_u64()) {
+        config.rotation = v.min(u32::MAX as u64) as u32;
+    }
+    if let Some(v) = cfg.get("burnInShift").and_then(|v| v.as_u64()) {
+        config.burn_in_shift = v.min(u32::MAX as u64) as u32;
+    }
+    if let Some(v) = cfg.get("burnInRefreshMins").and_then(|v| v.as_u64()) {
+        config.burn_in_refresh_mins = v;
+    }
+    if let Some(v) = cfg.get("maxStaticSecs").and_then(|v| v.as_u64()) {
+        config.max_static_secs = v;
+    }
+    if let Some(v) = cfg.get("latitude").and_then(|v| v.as_f64()) {
+        config.latitude = Some(v);
+    }
+    if let Some(v) = cfg.get("longitude").and_then(|v| v.as_f64()) {
+        config.longitude = Some(v);
+    }
+    if let Some(v) = cfg.get("pinnedTags").and_then(|v| v.as_array()) {
+        config.pinned_tags = v
+            .iter()
+            .filter_map(|t| t.as_str().map(|s| s.to_string()))
+            .collect();
+    }
+}
+
+/// Settings that are only read on startup, by name, that differ between
+/// `old` and `new`. Changing any of these takes a restart; everything
+/// `apply_device_config` sets can be applied to a running viewer.
+fn restart_required(old: &AppConfig, new: &AppConfig) -> Vec<&'static str> {
+    [
+        ("pb_url", old.pb_url != new.pb_url),
+        ("cache_dir", old.cache_dir != new.cache_dir),
+        (
+            "cache_size_limit_gb",
+            old.cache_size_limit_gb != new.cache_size_limit_gb,
+        ),
+        (
+            "cache_min_free_mb",
+            old.cache_min_free_mb != new.cache_min_free_mb,
+        ),
+        (
+            "pinned_budget_gb",
+            old.pinned_budget_gb != new.pinned_budget_gb,
+        ),
+        (
+            "decode_budget_mb",
+            old.decode_budget_mb != new.decode_budget_mb,
+        ),
+        ("device_id", old.device_id != new.device_id),
+        ("device_api_key", old.device_api_key != new.device_api_key),
+        (
+            "enable_realtime",
+            old.enable_realtime != new.enable_realtime,
+        ),
+        (
+            "realtime_idle_timeout_secs",
+            old.realtime_idle_timeout_secs != new.realtime_idle_timeout_secs,
+        ),
+        ("full_sync", old.full_sync != new.full_sync),
+        (
+            "playlist_full_refresh_mins",
+            old.playlist_full_refresh_mins != new.playlist_full_refresh_mins,
+        ),
+        (
+            "background_sync",
+            old.background_sync != new.background_sync,
+        ),
+        (
+            "sync_interval_secs",
+            old.sync_interval_secs != new.sync_interval_secs,
+        ),
+        ("sync_hours", old.sync_hours != new.sync_hours),
+        (
+            "download_concurrency",
+            old.download_concurrency != new.download_concurrency,
+        ),
+        (
+            "download_rate_kb_per_sec",
+            old.download_rate_kb_per_sec != new.download_rate_kb_per_sec,
+        ),
+        (
+            "download_burst_kb",
+            old.download_burst_kb != new.download_burst_kb,
+        ),
+        ("metered", old.metered != new.metered),
+        ("off_peak_hours", old.off_peak_hours != new.off_peak_hours),
+        ("fullscreen", old.fullscreen != new.fullscreen),
+        ("rotation", old.rotation != new.rotation),
+        ("display_power", old.display_power != new.display_power),
+        (
+            "display_on_command",
+            old.display_on_command != new.display_on_command,
+        ),
+        (
+            "display_off_command",
+            old.display_off_command != new.display_off_command,
+        ),
+    ]
+    .into_iter()
+    .filter(|(_, changed)| *changed)
+    .map(|(name, _)| name)
+    .collect()
+}
+
+/// Outcome of re-reading the config (see `reload_config`).
+enum ConfigReload {
+    /// Startup-only settings changed (named); only a restart applies them.
+    Restart(Vec<&'static str>),
+    /// The new config, ready to apply in place.
+    Live(Box<AppConfig>),
+}
+
+/// Re-read the local config file and the device's admin-controlled
+/// settings, after an admin saved them (a `config_reload` command).
+async fn reload_config(state: &AppState) -> Result<ConfigReload> {
+    let mut fresh = AppConfig::load()?;
+    let (Some(id), Some(key)) = (&fresh.device_id, &fresh.device_api_key) else {
+        anyhow::bail!("no device credentials configured");
+    };
+    let resp = discovery::device_auth(&state.client, &fresh.pb_url, id, key).await?;
+    // Compare with the admin's settings applied, as they are to the running
+    // config: some of them (rotation) only take effect on a restart.
+    apply_device_config(&mut fresh, &resp.config);
+    save_cached_device_config(&fresh.cache_dir, &resp.config);
+    let changed = restart_required(&state.config, &fresh);
+    if !changed.is_empty() {
+        return Ok(ConfigReload::Restart(changed));
+    }
+    Ok(ConfigReload::Live(Box::new(fresh)))
+}
+
+/// Push the renderer's share of `config` into a running renderer.
+fn apply_renderer_config(renderer: &mut Renderer<'_, '_>, config: &AppConfig) {
+    renderer.set_transition(
+        Transition::from_str(&config.transition),
+        config.transition_duration_ms,
+    );
+    renderer.set_ken_burns(config.ken_burns, config.interval_ms);
+    renderer.set_transition_easing(Easing::from_str(&config.transition_easing));
+    let (transition_set, layout_transition_sets) = config.transition_sets();
+    renderer.set_transition_sets(transition_set, layout_transition_sets);
+    renderer.blur_background = config.blur_background;
+    renderer.set_clock(
+        config.show_clock,
+        config.clock_offset_x,
+        config.clock_offset_y,
+        config.clock_style(),
+    );
+    renderer.set_orbit(config.burn_in_shift);
 }
 
 fn device_config_cache_path(cache_dir: &str) -> std::path::PathBuf {
@@ -711,11 +1612,40 @@ async fn main() -> Result<()> {
         config.transition_duration_ms
     );
     tracing::info!("  Blur background: {}", config.blur_background);
-    tracing::info!("  Clock: {}", config.show_clock);
+    tracing::info!("  Ken Burns: {}", config.ken_burns);
+    tracing::info!(
+        "  Clock: {} ({}, tz: {})",
+        config.show_clock,
+        config.clock_format,
+        if config.clock_timezone.is_empty() {
+            "system"
+        } else {
+            &config.clock_timezone
+        }
+    );
     tracing::info!(
-        "  Cache: {} ({} GB limit)",
+        "  Cache: {} ({} GB limit + {} GB pinned, keeping {} MB free)",
         config.cache_dir,
-        config.cache_size_limit_gb
+        config.cache_size_limit_gb,
+        config.pinned_budget_gb,
+        config.cache_min_free_mb
+    );
+    if !config.pinned_tags.is_empty() {
+        tracing::info!("  Pinned tags: {}", config.pinned_tags.join(", "));
+    }
+    tracing::info!(
+        "  Downloads: {} at a time, {}{}",
+        config.download_concurrency.max(1),
+        if config.download_rate_kb_per_sec == 0 {
+            "no rate limit".to_string()
+        } else {
+            format!("{} KB/s", config.download_rate_kb_per_sec)
+        },
+        if config.metered {
+            " (metered: video only during off-peak hours)"
+        } else {
+            ""
+        }
     );
     if let Some(ref device_id) = config.device_id {
         tracing::info!("  Device ID: {}", device_id);
@@ -729,10 +1659,10 @@ async fn main() -> Result<()> {
         .build()
         .unwrap_or_default();
 
-    let _device_token: Option<String> = if let (Some(ref id), Some(ref key)) =
+    let device_token: Option<String> = if let (Some(ref id), Some(ref key)) =
         (config.device_id.clone(), config.device_api_key.clone())
     {
-        match device_auth(&pre_client, &config.pb_url, id, key).await {
+        match discovery::device_auth(&pre_client, &config.pb_url, id, key).await {
             Ok(resp) => {
                 apply_device_config(&mut config, &resp.config);
                 save_cached_device_config(&config.cache_dir, &resp.config);
@@ -789,32 +1719,25 @@ async fn main() -> Result<()> {
         tracing::info!("Loaded {} media items", playlist.len());
 
         // Save playlist to cache
-        let cache = state.cache.read().await;
+        let mut cache = state.cache.write().await;
         if let Err(e) = cache.save_playlist(&playlist) {
             tracing::warn!("Failed to save playlist to cache: {}", e);
         }
+        cache.apply_tag_pins(&playlist, &state.config.pinned_tags);
     }
 
     *state.playlist.write().await = playlist.clone();
 
     // Shuffle / reorder the playlist
-    {
-        let mut playlist = state.playlist.write().await;
-        if state.config.display_mode == "dynamic" {
-            // In dynamic mode always reorder into layout-compatible groups so that
-            // multi-image layouts (quad-landscape, portrait+2-landscape, etc.) fire reliably.
-            reorder_for_dynamic_layouts(&mut playlist, &VecDeque::new());
-        } else if state.config.display_mode == "portrait_pairs" {
-            // Likewise, always reorder so portrait pairs land adjacent to each other.
-            reorder_for_portrait_pairs(&mut playlist, &VecDeque::new());
-        } else if state.config.shuffle {
-            use rand::seq::SliceRandom;
-            playlist.shuffle(&mut rand::thread_rng());
-        }
-    }
+    arrange_playlist(
+        &mut *state.playlist.write().await,
+        &state.config,
+        &VecDeque::new(),
+        state.screen_shape(),
+    );
 
     // Start preloader for initial assets
-    let preloader = Preloader::new(state.asset_manager.clone(), state.client.clone());
+    let preloader = Preloader::new(state.asset_manager.clone(), state.download_client.clone());
     let token = state.token().await;
     let playlist_clone = playlist.clone();
 
@@ -824,7 +1747,8 @@ async fn main() -> Result<()> {
             "Full sync mode enabled - preloading all {} media items...",
             playlist.len()
         );
-        let sync_preloader = Preloader::new(state.asset_manager.clone(), state.client.clone());
+        let sync_preloader =
+            Preloader::new(state.asset_manager.clone(), state.download_client.clone());
         let sync_token = state.token().await;
         let sync_playlist = playlist.clone();
 
@@ -842,6 +1766,10 @@ async fn main() -> Result<()> {
         });
     }
 
+    if state.config.background_sync {
+        tokio::spawn(run_background_sync(state.clone()));
+    }
+
     // Periodic heartbeat — keeps lastSeen fresh so the admin UI can show live status.
     // Runs every 90 seconds; a network blip never crashes the viewer, but the
     // outcome is always logged so a stale/rotated key is diagnosable from the
@@ -855,6 +1783,10 @@ async fn main() -> Result<()> {
             .build()
             .unwrap_or_default();
         let pb_url = state.config.pb_url.clone();
+        let cache_status = state.cache_status.clone();
+        let cache_dir = std::path::PathBuf::from(&state.config.cache_dir);
+        let min_free_bytes = state.config.cache_min_free_mb * 1024 * 1024;
+        let sync_progress = state.sync.progress_handle();
         tokio::spawn(async move {
             // CPU% is a delta since the previous heartbeat, not instantaneous — the
             // first heartbeat after startup has no prior sample to diff against, so
@@ -880,6 +1812,13 @@ async fn main() -> Result<()> {
                     percent
                 });
 
+                let cache_stats = cache_status
+                    .lock()
+                    .unwrap_or_else(|e| e.into_inner())
+                    .stats
+                    .clone();
+                let disk = cache::disk_usage(&cache_dir);
+
                 let telemetry = Telemetry {
                     version: env!("CARGO_PKG_VERSION"),
                     uptime_secs: process_start.elapsed().as_secs(),
@@ -887,7 +1826,23 @@ async fn main() -> Result<()> {
                     cpu_percent,
                     rss_bytes: read_process_rss_bytes(),
                     mem_available_bytes: read_mem_available_bytes(),
+                    cache_bytes: cache_stats.current_size,
+                    cache_max_bytes: cache_stats.max_size,
+                    disk_free_bytes: disk.map(|d| d.free),
+                    disk_total_bytes: disk.map(|d| d.total),
+                    disk_pressure: disk.is_some_and(|d| d.free < min_free_bytes),
+                    sync: sync_progress
+                        .lock()
+                        .unwrap_or_else(|e| e.into_inner())
+                        .clone(),
                 };
+                if telemetry.disk_pressure {
+                    tracing::warn!(
+                        "Disk pressure: {} MB free on cache filesystem (floor {} MB)",
+                        telemetry.disk_free_bytes.unwrap_or(0) / 1024 / 1024,
+                        min_free_bytes / 1024 / 1024
+                    );
+                }
 
                 match hb_client
                     .post(format!("{}/api/spomienka/device-heartbeat", pb_url))
@@ -901,6 +1856,15 @@ async fn main() -> Result<()> {
                             "cpuPercent": telemetry.cpu_percent,
                             "rssBytes": telemetry.rss_bytes,
                             "memAvailableBytes": telemetry.mem_available_bytes,
+                            "cacheBytes": telemetry.cache_bytes,
+                            "cacheMaxBytes": telemetry.cache_max_bytes,
+                            "diskFreeBytes": telemetry.disk_free_bytes,
+                            "diskTotalBytes": telemetry.disk_total_bytes,
+                            "diskPressure": telemetry.disk_pressure,
+                            "syncPhase": telemetry.sync.phase.as_str(),
+                            "syncCached": telemetry.sync.cached,
+                            "syncTotal": telemetry.sync.total,
+                            "syncRetrying": telemetry.sync.retrying,
                         },
                     }))
                     .send()
@@ -928,20 +1892,27 @@ async fn main() -> Result<()> {
 
     // Start realtime subscription if enabled
     let mut realtime_rx = if state.config.enable_realtime {
-        let token = state.token().await;
+        // User auth if configured, else the device session token; either
+        // way the realtime task gets a new one from device-auth when it's
+        // rejected.
+        let token = state.token().await.or(device_token);
         Some(spawn_realtime(
             state.config.pb_url.clone(),
             state.config.device_id.clone(),
+            state.config.device_api_key.clone(),
+            Duration::from_secs(state.config.realtime_idle_timeout_secs.max(10)),
             token,
+            state.realtime_status.clone(),
+            state.acks.clone(),
         ))
     } else {
         None
     };
 
     // Run the main render loop
-    run_render_loop(state.clone(), &mut realtime_rx).await?;
-
-    Ok(())
+    let result = run_render_loop(state.clone(), &mut realtime_rx).await;
+    state.cache.write().await.flush_index();
+    result
 }
 
 /// Discovery mode render loop — shown when no device_id is configured, or
@@ -1022,8 +1993,10 @@ async fn run_discovery_mode(config: &AppConfig, repair_device_id: Option<String>
         sdl2::ttf::init().map_err(|e| anyhow::anyhow!("SDL TTF init failed: {}", e))?;
 
     // Show the PIN screen via SDL2
+    let mut overlay_tc = None;
     let mut renderer = renderer::Renderer::new(
         &ttf_context,
+        &mut overlay_tc,
         renderer::Transition::Cut,
         0,
         config.fullscreen,
@@ -1031,7 +2004,9 @@ async fn run_discovery_mode(config: &AppConfig, repair_device_id: Option<String>
         false,
         0,
         0,
+        ClockStyle::default(),
         SlideLayout::Single,
+        config.rotation(),
     )?;
 
     loop {
@@ -1111,8 +2086,10 @@ async fn run_render_loop(
     let ttf_context =
         sdl2::ttf::init().map_err(|e| anyhow::anyhow!("SDL TTF init failed: {}", e))?;
     let transition = Transition::from_str(&state.config.transition);
+    let mut overlay_tc = None;
     let mut renderer = Renderer::new(
         &ttf_context,
+        &mut overlay_tc,
         transition,
         state.config.transition_duration_ms,
         state.config.fullscreen,
@@ -1120,8 +2097,35 @@ async fn run_render_loop(
         state.config.show_clock,
         state.config.clock_offset_x,
         state.config.clock_offset_y,
+        state.config.clock_style(),
         SlideLayout::Single, // initial layout; dynamic mode picks per-slide
+        state.config.rotation(),
     )?;
+    renderer.set_ken_burns(state.config.ken_burns, state.config.interval_ms);
+    renderer.set_orbit(state.config.burn_in_shift);
+    renderer.set_transition_easing(Easing::from_str(&state.config.transition_easing));
+    let (transition_set, layout_transition_sets) = state.config.transition_sets();
+    renderer.set_transition_sets(transition_set, layout_transition_sets);
+    state
+        .asset_manager
+        .decoder()
+        .set_target(renderer.decode_target());
+    let (screen_w, screen_h) = renderer.screen_size();
+    let screen_shape = ScreenShape::of(screen_w, screen_h);
+    let _ = state.screen_shape.set(screen_shape);
+    if screen_shape != ScreenShape::Landscape {
+        tracing::info!("{:?} screen: grouping for its layouts", screen_shape);
+        // The playlist was arranged before the screen's shape was known.
+        if is_multi_panel_mode(&state.config.display_mode) {
+            let mut playlist = state.playlist.write().await;
+            reorder_for_layouts(
+                &mut playlist,
+                &state.config.display_mode,
+                &VecDeque::new(),
+                screen_shape,
+            );
+        }
+    }
 
     // Initialize video manager
     let mut video_manager = VideoManager::new();
@@ -1129,24 +2133,23 @@ async fn run_render_loop(
     // Create texture creator
     let texture_creator = renderer.texture_creator();
 
-    // Panel textures (up to 4 for dynamic layouts)
+    // Panel textures: the first panel (which also plays video), and the rest
+    // of a multi-image slide
     let mut current_textures = MediaTextures::new();
     let mut next_textures: Option<MediaTextures> = None;
-    let mut right_textures = MediaTextures::new();
-    let mut next_right_textures: Option<MediaTextures> = None;
-    let mut panel2_textures = MediaTextures::new();
-    let mut next_panel2_textures: Option<MediaTextures> = None;
-    let mut panel3_textures = MediaTextures::new();
-    let mut next_panel3_textures: Option<MediaTextures> = None;
+    let mut extra_panels = ExtraPanels::new();
 
     // Dynamic layout state
     let session_start = Instant::now();
     let mut layout_history: VecDeque<SlideLayoutKind> = VecDeque::new();
-    let is_dynamic = is_multi_panel_mode(&state.config.display_mode);
+    let mut is_dynamic = is_multi_panel_mode(&state.config.display_mode);
 
     // Timing
     let mut last_advance = Instant::now();
-    let slide_duration = Duration::from_millis(state.config.interval_ms);
+    let mut slide_duration = Duration::from_millis(state.config.interval_ms);
+
+    // Media put up by a `show` command, if it's still on screen
+    let mut show: Option<ShowHold> = None;
 
     // Track if we're showing video
     let mut is_video_playing = false;
@@ -1160,12 +2163,43 @@ async fn run_render_loop(
 
     // Overlay state
     let mut overlay_visible = false;
-    let info_overlay_visible = state.config.show_info;
-    let location_overlay_visible = state.config.show_location_info;
+    let mut info_overlay_visible = state.config.show_info;
+    let mut location_overlay_visible = state.config.show_location_info;
     let mut is_paused = false;
     let mut pause_until: Option<Instant> = None;
     let mut is_realtime_connected = false;
 
+    // Quiet hours: the mode the screen is in while quiet (None while on),
+    // and any display-on/off command overriding the schedule.
+    let mut quiet_hours = state.config.quiet_hours();
+    let display_power = DisplayPower::from_settings(
+        &state.config.display_power,
+        &state.config.display_on_command,
+        &state.config.display_off_command,
+    );
+    let mut quiet: Option<QuietMode> = None;
+    let mut display_override: Option<DisplayOverride> = None;
+    // The panel may still be off from before a restart (quiet hours or a
+    // display-off command); the loop below only switches it on when
+    // leaving a blank spell, so do it here unless one is due.
+    if scheduled_quiet(&quiet_hours) != Some(QuietMode::Blank) {
+        display_power.set(true);
+    }
+
+    // Burn-in protection: the quiet-hours refresh pattern (when the next
+    // one is due, and when the one on screen started), and the cap on how
+    // long one image stays up.
+    let mut burn_in_refresh = state.config.burn_in_refresh();
+    let mut next_refresh: Option<Instant> = None;
+    let mut refresh_started: Option<Instant> = None;
+    let mut max_static = state.config.max_static();
+
+    // Dimming: the day's curve, any brightness command overriding it (with
+    // its end), and when the shade was last worked out.
+    let mut shade_curve = state.config.shade_curve();
+    let mut shade_override: Option<(Shade, Option<Instant>)> = None;
+    let mut shade_checked: Option<Instant> = None;
+
     // Set while an admin-triggered bulk upload is running; suspends the
     // slideshow and video decode, and swaps the render path to a log/progress
     // screen. Auto-cleared if no progress message arrives for a while, in case
@@ -1200,37 +2234,14 @@ async fn run_render_loop(
         if layout_history.len() > 20 {
             layout_history.pop_front();
         }
-        let n = layout.image_count();
-        if n >= 2 {
-            load_panel_item(
-                &state,
-                &mut renderer,
-                &texture_creator,
-                &mut right_textures,
-                1,
-            )
-            .await?;
-        }
-        if n >= 3 {
-            load_panel_item(
-                &state,
-                &mut renderer,
-                &texture_creator,
-                &mut panel2_textures,
-                2,
-            )
-            .await?;
-        }
-        if n >= 4 {
-            load_panel_item(
-                &state,
-                &mut renderer,
-                &texture_creator,
-                &mut panel3_textures,
-                3,
-            )
-            .await?;
-        }
+        extra_panels.shown = load_extra_panels(
+            &state,
+            &mut renderer,
+            &texture_creator,
+            actual_start,
+            layout.image_count(),
+        )
+        .await?;
     }
 
     loop {
@@ -1258,22 +2269,19 @@ async fn run_render_loop(
             }
             UserAction::Next => {
                 tracing::debug!("Skip to next requested");
+                let to = end_show(&state, &mut show).await;
                 advance_to_next(
                     &state,
                     &mut renderer,
                     &texture_creator,
                     &mut current_textures,
                     &mut next_textures,
-                    &mut right_textures,
-                    &mut next_right_textures,
-                    &mut panel2_textures,
-                    &mut next_panel2_textures,
-                    &mut panel3_textures,
-                    &mut next_panel3_textures,
+                    &mut extra_panels,
                     &mut video_manager,
                     &mut is_video_playing,
                     &mut layout_history,
                     session_start,
+                    to,
                 )
                 .await?;
                 last_advance = Instant::now();
@@ -1281,15 +2289,14 @@ async fn run_render_loop(
             }
             UserAction::Previous => {
                 tracing::debug!("Go to previous requested");
+                end_show(&state, &mut show).await;
                 go_to_previous(
                     &state,
                     &mut renderer,
                     &texture_creator,
                     &mut current_textures,
                     &mut next_textures,
-                    &mut right_textures,
-                    &mut panel2_textures,
-                    &mut panel3_textures,
+                    &mut extra_panels,
                     &mut video_manager,
                     &mut is_video_playing,
                 )
@@ -1299,7 +2306,7 @@ async fn run_render_loop(
             }
             UserAction::Refresh => {
                 tracing::info!("Manual playlist refresh requested");
-                match state.fetch_playlist().await {
+                match state.fetch_playlist_full().await {
                     Ok(playlist) => {
                         let cache = state.cache.read().await;
                         if let Err(e) = cache.save_playlist(&playlist) {
@@ -1348,27 +2355,37 @@ async fn run_render_loop(
         // Process realtime events
         if let Some(ref mut rx) = realtime_rx {
             while let Ok(event) = rx.try_recv() {
+                // Inbox commands come with their record id, to acknowledge
+                // them under once handled.
+                let (command_id, event) = match event {
+                    RealtimeEvent::Command { id, command } => (Some(id), *command),
+                    event => (None, event),
+                };
+                if let Some(id) = &command_id {
+                    if matches!(event, RealtimeEvent::RepairRequested) {
+                        // This restarts the viewer; acknowledge first.
+                        state.acks.post(id, AckStatus::Applied, None, None).await;
+                    }
+                }
+                let mut outcome: Result<(), String> = Ok(());
                 match event {
                     RealtimeEvent::Connected => is_realtime_connected = true,
                     RealtimeEvent::Disconnected => is_realtime_connected = false,
                     RealtimeEvent::RemoteNext => {
                         tracing::debug!("Remote: next");
+                        let to = end_show(&state, &mut show).await;
                         advance_to_next(
                             &state,
                             &mut renderer,
                             &texture_creator,
                             &mut current_textures,
                             &mut next_textures,
-                            &mut right_textures,
-                            &mut next_right_textures,
-                            &mut panel2_textures,
-                            &mut next_panel2_textures,
-                            &mut panel3_textures,
-                            &mut next_panel3_textures,
+                            &mut extra_panels,
                             &mut video_manager,
                             &mut is_video_playing,
                             &mut layout_history,
                             session_start,
+                            to,
                         )
                         .await?;
                         last_advance = Instant::now();
@@ -1377,15 +2394,14 @@ async fn run_render_loop(
                     }
                     RealtimeEvent::RemotePrev => {
                         tracing::debug!("Remote: prev");
+                        end_show(&state, &mut show).await;
                         go_to_previous(
                             &state,
                             &mut renderer,
                             &texture_creator,
                             &mut current_textures,
                             &mut next_textures,
-                            &mut right_textures,
-                            &mut panel2_textures,
-                            &mut panel3_textures,
+                            &mut extra_panels,
                             &mut video_manager,
                             &mut is_video_playing,
                         )
@@ -1396,12 +2412,15 @@ async fn run_render_loop(
                     }
                     RealtimeEvent::RemoteRandom => {
                         tracing::debug!("Remote: random");
+                        end_show(&state, &mut show).await;
                         {
                             use rand::Rng;
                             let playlist = state.playlist.read().await;
                             if !playlist.is_empty() {
                                 let idx = rand::thread_rng().gen_range(0..playlist.len());
                                 *state.current_index.write().await = idx;
+                            } else {
+                                outcome = Err("playlist is empty".to_string());
                             }
                         }
                         load_current_item(
@@ -1414,41 +2433,100 @@ async fn run_render_loop(
                         )
                         .await?;
                         if is_dynamic {
-                            let n = renderer.current_layout.image_count();
-                            if n >= 2 {
-                                load_panel_item(
-                                    &state,
-                                    &mut renderer,
-                                    &texture_creator,
-                                    &mut right_textures,
-                                    1,
-                                )
-                                .await?;
+                            let start = *state.current_index.read().await;
+                            let count = renderer.current_layout.image_count();
+                            extra_panels.shown = load_extra_panels(
+                                &state,
+                                &mut renderer,
+                                &texture_creator,
+                                start,
+                                count,
+                            )
+                            .await?;
+                        }
+                        last_advance = Instant::now();
+                        is_paused = false;
+                        pause_until = None;
+                    }
+                    RealtimeEvent::RemoteShow {
+                        media_id,
+                        hold_secs,
+                    } => {
+                        tracing::info!("Remote: show {} (hold {:?}s)", media_id, hold_secs);
+                        // Where the slideshow picks up afterwards; an earlier
+                        // show's resume point wins, so back-to-back shows
+                        // still return to the slideshow proper.
+                        let previous = show.take();
+                        let resume_at = match &previous {
+                            Some(p) => p.resume_at,
+                            None => {
+                                *state.current_index.read().await
+                                    + renderer.current_layout.image_count()
                             }
-                            if n >= 3 {
-                                load_panel_item(
-                                    &state,
-                                    &mut renderer,
-                                    &texture_creator,
-                                    &mut panel2_textures,
-                                    2,
-                                )
-                                .await?;
+                        };
+                        // Media an earlier show added stays only if it's
+                        // being shown again, and is still ours to remove.
+                        let mut kept_insert = false;
+                        if let Some(p) = previous.filter(|p| p.inserted) {
+                            if p.media_id == media_id {
+                                kept_insert = true;
+                            } else {
+                                state.playlist.write().await.retain(|m| m.id != p.media_id);
                             }
-                            if n >= 4 {
-                                load_panel_item(
+                        }
+
+                        let existing = state
+                            .playlist
+                            .read()
+                            .await
+                            .iter()
+                            .position(|m| m.id == media_id);
+                        let target = match existing {
+                            Some(idx) => Ok((idx, kept_insert)),
+                            None if media_id.is_empty() => Err("no mediaId given".to_string()),
+                            None => match state.fetch_media(&media_id).await {
+                                // Added at the end so no other index moves;
+                                // end_show takes it out again.
+                                Ok(media) => {
+                                    let mut playlist = state.playlist.write().await;
+                                    playlist.push(media);
+                                    Ok((playlist.len() - 1, true))
+                                }
+                                Err(e) => {
+                                    tracing::warn!("Remote: can't show {}: {}", media_id, e);
+                                    Err(format!("media {} not available: {}", media_id, e))
+                                }
+                            },
+                        };
+                        match target {
+                            Ok((idx, inserted)) => {
+                                show = Some(ShowHold {
+                                    media_id,
+                                    inserted,
+                                    resume_at,
+                                    hold_until: hold_secs
+                                        .map(|secs| Instant::now() + Duration::from_secs(secs)),
+                                });
+                                advance_to_next(
                                     &state,
                                     &mut renderer,
                                     &texture_creator,
-                                    &mut panel3_textures,
-                                    3,
+                                    &mut current_textures,
+                                    &mut next_textures,
+                                    &mut extra_panels,
+                                    &mut video_manager,
+                                    &mut is_video_playing,
+                                    &mut layout_history,
+                                    session_start,
+                                    Advance::Show(idx),
                                 )
                                 .await?;
+                                last_advance = Instant::now();
+                                is_paused = false;
+                                pause_until = None;
                             }
+                            Err(reason) => outcome = Err(reason),
                         }
-                        last_advance = Instant::now();
-                        is_paused = false;
-                        pause_until = None;
                     }
                     RealtimeEvent::RemotePause { secs } => {
                         tracing::debug!("Remote: pause {}s", secs);
@@ -1462,10 +2540,46 @@ async fn run_render_loop(
                         tracing::debug!("Remote: resume");
                         is_paused = false;
                         pause_until = None;
-                        if is_video_playing {
+                        if is_video_playing && quiet.is_none() {
                             video_manager.resume();
                         }
                     }
+                    RealtimeEvent::RemoteDisplay { on, secs } => {
+                        tracing::info!(
+                            "Remote: display {} ({})",
+                            if on { "on" } else { "off" },
+                            secs.map_or("until the schedule changes".to_string(), |s| format!(
+                                "{}s",
+                                s
+                            ))
+                        );
+                        display_override = Some(DisplayOverride {
+                            on,
+                            until: secs.map(|s| Instant::now() + Duration::from_secs(s)),
+                            scheduled_quiet: scheduled_quiet(&quiet_hours).is_some(),
+                        });
+                    }
+                    RealtimeEvent::RemoteBrightness { shade, secs } => {
+                        match shade {
+                            Some(shade) => {
+                                tracing::info!(
+                                    "Remote: brightness {:.0}%, warmth {:.0}% ({})",
+                                    shade.brightness * 100.0,
+                                    shade.warmth * 100.0,
+                                    secs.map_or("until cleared".to_string(), |s| format!("{}s", s))
+                                );
+                                shade_override = Some((
+                                    shade,
+                                    secs.map(|s| Instant::now() + Duration::from_secs(s)),
+                                ));
+                            }
+                            None => {
+                                tracing::info!("Remote: brightness back to the schedule");
+                                shade_override = None;
+                            }
+                        }
+                        shade_checked = None;
+                    }
                     RealtimeEvent::RemoteTagFilter { tags, mode } => {
                         tracing::info!("Remote: tag filter {:?} ({})", tags, mode);
                         *state.tag_filter.write().await = Some((tags, mode));
@@ -1475,9 +2589,13 @@ async fn run_render_loop(
                                 let _ = cache.save_playlist(&playlist);
                                 drop(cache);
                                 *state.playlist.write().await = playlist;
+                                state.sync.kick();
                                 tracing::info!("Playlist refreshed with tag filter");
                             }
-                            Err(e) => tracing::error!("Failed to refresh playlist: {}", e),
+                            Err(e) => {
+                                tracing::error!("Failed to refresh playlist: {}", e);
+                                outcome = Err(format!("playlist refresh failed: {}", e));
+                            }
                         }
                     }
                     RealtimeEvent::RemoteTagFilterClear => {
@@ -1489,9 +2607,13 @@ async fn run_render_loop(
                                 let _ = cache.save_playlist(&playlist);
                                 drop(cache);
                                 *state.playlist.write().await = playlist;
+                                state.sync.kick();
                                 tracing::info!("Playlist refreshed, tag filter removed");
                             }
-                            Err(e) => tracing::error!("Failed to refresh playlist: {}", e),
+                            Err(e) => {
+                                tracing::error!("Failed to refresh playlist: {}", e);
+                                outcome = Err(format!("playlist refresh failed: {}", e));
+                            }
                         }
                     }
                     RealtimeEvent::BulkUploadStart => {
@@ -1528,11 +2650,185 @@ async fn run_render_loop(
                             video_manager.resume();
                         }
                     }
+                    RealtimeEvent::ConfigChanged => match reload_config(&state).await {
+                        Ok(ConfigReload::Restart(changed)) => {
+                            tracing::info!(
+                                "Config changed ({}) — restarting to apply new settings",
+                                changed.join(", ")
+                            );
+                            if let Some(id) = &command_id {
+                                state.acks.post(id, AckStatus::Applied, None, None).await;
+                            }
+                            state.cache.write().await.flush_index();
+                            reexec_self();
+                        }
+                        Ok(ConfigReload::Live(config)) => {
+                            tracing::info!("Device config changed — applying new settings");
+                            apply_renderer_config(&mut renderer, &config);
+                            slide_duration = Duration::from_millis(config.interval_ms);
+                            quiet_hours = config.quiet_hours();
+                            shade_curve = config.shade_curve();
+                            max_static = config.max_static();
+                            if burn_in_refresh != config.burn_in_refresh() {
+                                burn_in_refresh = config.burn_in_refresh();
+                                next_refresh = burn_in_refresh
+                                    .filter(|_| quiet.is_some())
+                                    .map(|every| Instant::now() + every);
+                            }
+                            shade_checked = None;
+                            info_overlay_visible = config.show_info;
+                            location_overlay_visible = config.show_location_info;
+                            let old = std::mem::replace(
+                                &mut *state.live_config.write().await,
+                                (*config).clone(),
+                            );
+                            if old.pinned_tags != config.pinned_tags {
+                                let playlist = state.playlist.read().await.clone();
+                                state
+                                    .cache
+                                    .write()
+                                    .await
+                                    .apply_tag_pins(&playlist, &config.pinned_tags);
+                                state.sync.kick();
+                            }
+                            if old.display_mode != config.display_mode
+                                || old.shuffle != config.shuffle
+                            {
+                                // Regroup for the new mode and lay the
+                                // current item out again under it.
+                                is_dynamic = is_multi_panel_mode(&config.display_mode);
+                                end_show(&state, &mut show).await;
+                                let resume = rearrange_playlist(&state, &config).await;
+                                layout_history.clear();
+                                advance_to_next(
+                                    &state,
+                                    &mut renderer,
+                                    &texture_creator,
+                                    &mut current_textures,
+                                    &mut next_textures,
+                                    &mut extra_panels,
+                                    &mut video_manager,
+                                    &mut is_video_playing,
+                                    &mut layout_history,
+                                    session_start,
+                                    Advance::ResumeAt(resume),
+                                )
+                                .await?;
+                                last_advance = Instant::now();
+                            }
+                        }
+                        Err(e) => {
+                            tracing::warn!("Failed to reload config: {}", e);
+                            outcome = Err(format!("couldn't reload config: {}", e));
+                        }
+                    },
                     other => {
                         handle_realtime_event(&state, other).await;
                     }
                 }
+                if let Some(id) = command_id {
+                    let (status, reason) = match outcome {
+                        Ok(()) => (AckStatus::Applied, None),
+                        Err(reason) => (AckStatus::Failed, Some(reason)),
+                    };
+                    let command_state = command_state(&state, is_paused, pause_until).await;
+                    state.acks.send(&id, status, reason, Some(command_state));
+                }
+            }
+        }
+
+        // Quiet hours: blank the screen (or dim it to a clock) on schedule,
+        // unless a display-on/off command says otherwise.
+        let scheduled = scheduled_quiet(&quiet_hours);
+        if display_override.as_ref().is_some_and(|o| {
+            o.until.is_some_and(|until| Instant::now() >= until)
+                || o.scheduled_quiet != scheduled.is_some()
+        }) {
+            tracing::info!("Display override ended, back to the schedule");
+            display_override = None;
+        }
+        let want_quiet = match &display_override {
+            Some(o) if o.on => None,
+            Some(_) => Some(QuietMode::Blank),
+            None => scheduled,
+        };
+        if want_quiet != quiet {
+            match want_quiet {
+                Some(mode) => tracing::info!("Quiet hours: {:?} screen", mode),
+                None => tracing::info!("Quiet hours over, resuming the slideshow"),
+            }
+            if quiet == Some(QuietMode::Blank) {
+                display_power.set(true);
+            }
+            if want_quiet == Some(QuietMode::Blank) {
+                display_power.set(false);
+            }
+            // Nothing is watching: hold downloads and video decode.
+            state
+                .asset_manager
+                .throttle()
+                .set_paused(want_quiet.is_some());
+            if is_video_playing && !is_paused {
+                if want_quiet.is_some() {
+                    video_manager.pause();
+                } else {
+                    video_manager.resume();
+                }
             }
+            if want_quiet.is_none() {
+                last_advance = Instant::now();
+            }
+            next_refresh = burn_in_refresh
+                .filter(|_| want_quiet.is_some())
+                .map(|every| Instant::now() + every);
+            refresh_started = None;
+            quiet = want_quiet;
+        }
+
+        // Refresh pattern: every so often during quiet hours, run every
+        // pixel through full colours for a while -- unless the display is
+        // powered off, where there's nothing to refresh, or a display-off
+        // command wants the screen dark even though it stays powered.
+        if let Some(mode) = quiet {
+            let switched_off = display_override.as_ref().is_some_and(|o| !o.on);
+            let lit = !switched_off && (mode == QuietMode::Clock || !display_power.powers_off());
+            if refresh_started.is_some_and(|t| !lit || t.elapsed() >= REFRESH_PATTERN_DURATION) {
+                refresh_started = None;
+                next_refresh = burn_in_refresh.map(|every| Instant::now() + every);
+            } else if lit
+                && refresh_started.is_none()
+                && next_refresh.is_some_and(|t| Instant::now() >= t)
+            {
+                tracing::info!("Running the burn-in refresh pattern");
+                refresh_started = Some(Instant::now());
+            }
+        }
+
+        // Dimming: follow the curve, which moves slowly enough to only be
+        // looked at every so often, unless a brightness command overrides it.
+        if shade_override
+            .as_ref()
+            .is_some_and(|(_, until)| until.is_some_and(|t| Instant::now() >= t))
+        {
+            tracing::info!("Brightness override ended, back to the schedule");
+            shade_override = None;
+            shade_checked = None;
+        }
+        if shade_checked.is_none_or(|t| t.elapsed() >= SHADE_CHECK_INTERVAL) {
+            let shade = match (&shade_override, &shade_curve) {
+                (Some((shade, _)), _) => *shade,
+                (None, Some(curve)) => curve.shade_now(),
+                (None, None) => Shade::FULL,
+            };
+            if shade != renderer.shade() {
+                tracing::debug!(
+                    "Brightness {:.0}%, warmth {:.0}%",
+                    shade.brightness * 100.0,
+                    shade.warmth * 100.0
+                );
+                renderer.set_shade(shade);
+            }
+            shade_checked = Some(Instant::now());
         }
 
         // A video texture is only valid to reuse while its video is still
@@ -1545,7 +2841,7 @@ async fn run_render_loop(
 
         // Update video frame if playing and not paused (video decode is also
         // explicitly paused for the duration of a bulk upload, see above).
-        if is_video_playing && !is_paused && bulk_upload.is_none() {
+        if is_video_playing && !is_paused && bulk_upload.is_none() && quiet.is_none() {
             if let Some(frame) = video_manager.current_frame() {
                 // Reuse the existing texture in place when its dimensions
                 // already match (the common case, every frame after the
@@ -1577,22 +2873,19 @@ async fn run_render_loop(
             if video_manager.is_ended() {
                 tracing::debug!("Video ended, advancing to next");
                 is_video_playing = false;
+                let to = end_show(&state, &mut show).await;
                 advance_to_next(
                     &state,
                     &mut renderer,
                     &texture_creator,
                     &mut current_textures,
                     &mut next_textures,
-                    &mut right_textures,
-                    &mut next_right_textures,
-                    &mut panel2_textures,
-                    &mut next_panel2_textures,
-                    &mut panel3_textures,
-                    &mut next_panel3_textures,
+                    &mut extra_panels,
                     &mut video_manager,
                     &mut is_video_playing,
                     &mut layout_history,
                     session_start,
+                    to,
                 )
                 .await?;
                 last_advance = Instant::now();
@@ -1610,15 +2903,7 @@ async fn run_render_loop(
                 // dimensions happen to coincidentally match.
                 video_texture_dims = None;
             }
-            if let Some(next) = next_right_textures.take() {
-                right_textures = next;
-            }
-            if let Some(next) = next_panel2_textures.take() {
-                panel2_textures = next;
-            }
-            if let Some(next) = next_panel3_textures.take() {
-                panel3_textures = next;
-            }
+            extra_panels.swap_in();
 
             // Video playback for an animated-transition slide is deferred
             // until the swap actually lands here (see advance_to_next) so
@@ -1643,35 +2928,54 @@ async fn run_render_loop(
         // Video advances via its own EOS check above instead, not this timer
         // -- so gate this out entirely while a video is playing.
         // Don't auto-advance if paused, or while the bulk-upload screen is showing.
-        let should_advance = !is_paused
+        // A `show` command's hold replaces the interval for its slide.
+        // `max_static_secs` overrides both, so no image sits still long
+        // enough to burn in.
+        let slide_due = match show.as_ref().and_then(|s| s.hold_until) {
+            Some(until) => Instant::now() >= until,
+            None => last_advance.elapsed() >= slide_duration,
+        };
+        let static_too_long = max_static.is_some_and(|cap| last_advance.elapsed() >= cap);
+        let should_advance = (slide_due && !is_paused || static_too_long)
             && bulk_upload.is_none()
+            && quiet.is_none()
             && !renderer.is_transitioning()
-            && !is_video_playing
-            && last_advance.elapsed() >= slide_duration;
+            && !is_video_playing;
 
         if should_advance {
+            if static_too_long && (is_paused || show.is_some()) {
+                tracing::info!(
+                    "Image up for {}s, moving on (max_static_secs)",
+                    last_advance.elapsed().as_secs()
+                );
+                is_paused = false;
+                pause_until = None;
+            }
+            let to = end_show(&state, &mut show).await;
             advance_to_next(
                 &state,
                 &mut renderer,
                 &texture_creator,
                 &mut current_textures,
                 &mut next_textures,
-                &mut right_textures,
-                &mut next_right_textures,
-                &mut panel2_textures,
-                &mut next_panel2_textures,
-                &mut panel3_textures,
-                &mut next_panel3_textures,
+                &mut extra_panels,
                 &mut video_manager,
                 &mut is_video_playing,
                 &mut layout_history,
                 session_start,
+                to,
             )
             .await?;
             last_advance = Instant::now();
         }
 
-        if let Some(ref bu) = bulk_upload {
+        if let Some(started) = refresh_started {
+            renderer.render_refresh_pattern(started.elapsed());
+        } else if let Some(mode) = quiet {
+            if let Err(e) = renderer.render_quiet_screen(mode == QuietMode::Clock) {
+                tracing::warn!("Failed to render quiet screen: {}", e);
+            }
+        } else if let Some(ref bu) = bulk_upload {
             // A bulk upload is in progress -- show its log/progress screen
             // instead of the slideshow. Stays inside the main loop (rather
             // than a separate mode like discovery) so realtime events keep
@@ -1695,35 +2999,10 @@ async fn run_render_loop(
             if outgoing_layout.is_multi() || incoming_layout.is_multi() {
                 let old_n = outgoing_layout.image_count();
                 let mut p: Vec<&mut MediaTextures> = vec![&mut current_textures];
-                if old_n >= 2 {
-                    p.push(&mut right_textures);
-                }
-                if old_n >= 3 {
-                    p.push(&mut panel2_textures);
-                }
-                if old_n >= 4 {
-                    p.push(&mut panel3_textures);
-                }
+                p.extend(extra_panels.shown.iter_mut().take(old_n - 1));
                 let new_n = incoming_layout.image_count();
-                let mut np: Vec<&mut MediaTextures> = Vec::new();
-                if let Some(ref mut t) = next_textures {
-                    np.push(t);
-                }
-                if new_n >= 2 {
-                    if let Some(ref mut t) = next_right_textures {
-                        np.push(t);
-                    }
-                }
-                if new_n >= 3 {
-                    if let Some(ref mut t) = next_panel2_textures {
-                        np.push(t);
-                    }
-                }
-                if new_n >= 4 {
-                    if let Some(ref mut t) = next_panel3_textures {
-                        np.push(t);
-                    }
-                }
+                let mut np: Vec<&mut MediaTextures> = next_textures.iter_mut().collect();
+                np.extend(extra_panels.incoming.iter_mut().take(new_n - 1));
                 let has_next = !np.is_empty();
                 if has_next {
                     renderer.render_layout(&texture_creator, &mut p, Some(&mut np))?;
@@ -1747,6 +3026,8 @@ async fn run_render_loop(
                     is_paused,
                     pause_until,
                     is_realtime_connected,
+                    renderer.shade(),
+                    shade_override.is_some(),
                 )
                 .await;
                 if let Err(e) = renderer.render_overlay(&overlay_info) {
@@ -1795,6 +3076,12 @@ async fn run_render_loop(
         // until the display's next refresh -- an additional fixed sleep here
         // stacked on top and roughly halved the achievable frame rate.
         renderer.present();
+
+        // Quiet screens change once a minute at most, and with the display
+        // powered off vsync may no longer pace the loop.
+        if quiet.is_some() {
+            tokio::time::sleep(QUIET_FRAME_INTERVAL).await;
+        }
     }
 
     // Cleanup
@@ -1803,7 +3090,59 @@ async fn run_render_loop(
     Ok(())
 }
 
+/// Frame interval while the quiet-hours screen is up.
+const QUIET_FRAME_INTERVAL: Duration = Duration::from_millis(250);
+
+/// How long the quiet-hours burn-in refresh pattern runs.
+const REFRESH_PATTERN_DURATION: Duration = Duration::from_secs(30);
+
+/// How often the dimming curve is re-read.
+const SHADE_CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+/// A `display-on`/`display-off` command overriding quiet hours.
+struct DisplayOverride {
+    on: bool,
+    /// When the override ends, if it was given a duration.
+    until: Option<Instant>,
+    /// Whether the schedule had it quiet at the time; the override also
+    /// ends once the schedule changes.
+    scheduled_quiet: bool,
+}
+
+/// The quiet-hours mode the schedule calls for now, if any.
+fn scheduled_quiet(quiet_hours: &Option<(QuietMode, QuietSchedule)>) -> Option<QuietMode> {
+    quiet_hours
+        .as_ref()
+        .filter(|(_, schedule)| schedule.is_quiet_now())
+        .map(|(mode, _)| *mode)
+}
+
+/// What the frame is doing, to report with a command's acknowledgment.
+async fn command_state(
+    state: &AppState,
+    is_paused: bool,
+    pause_until: Option<Instant>,
+) -> CommandState {
+    let current_media_id = {
+        let playlist = state.playlist.read().await;
+        let idx = *state.current_index.read().await;
+        playlist.get(idx).map(|m| m.id.clone())
+    };
+    let paused_until = pause_until.map(|until| {
+        let remaining = until.saturating_duration_since(Instant::now());
+        (std::time::SystemTime::now() + remaining)
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |d| d.as_millis() as u64)
+    });
+    CommandState {
+        current_media_id,
+        paused: is_paused,
+        paused_until,
+    }
+}
+
 /// Build overlay info from current state.
+#[allow(clippy::too_many_arguments)]
 async fn build_overlay_info(
     state: &AppState,
     video_manager: &VideoManager,
@@ -1811,6 +3150,8 @@ async fn build_overlay_info(
     is_paused: bool,
     pause_until: Option<Instant>,
     is_realtime_connected: bool,
+    shade: Shade,
+    shade_overridden: bool,
 ) -> OverlayInfo {
     let playlist = state.playlist.read().await;
     let current_index = *state.current_index.read().await;
@@ -1826,8 +3167,14 @@ async fn build_overlay_info(
         .map(|m| m.is_video())
         .unwrap_or(false);
 
-    let cache = state.cache.read().await;
-    let cache_stats = cache.stats();
+    // Not the cache lock: the overlay is drawn every frame and mustn't wait
+    // behind downloads committing or evicting.
+    let cache_status = state
+        .cache_status
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone();
+    let cache_stats = cache_status.stats;
 
     let pause_secs_remaining = pause_until.and_then(|until| {
         let now = Instant::now();
@@ -1847,6 +3194,18 @@ async fn build_overlay_info(
         cache_used: cache_stats.current_size,
         cache_max: cache_stats.max_size,
         cache_items: cache_stats.item_count,
+        pinned_used: cache_stats.pinned_size,
+        pinned_budget: cache_stats.pinned_budget,
+        disk_free: cache_stats.disk.map(|d| d.free),
+        disk_pressure: cache_stats.disk_pressure,
+        downloads: cache_status.downloads.into_iter().map(|(_, p)| p).collect(),
+        failures: cache_status.failures,
+        realtime: state
+            .realtime_status
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone(),
+        sync: state.sync.progress(),
         is_video,
         is_paused,
         pause_secs_remaining,
@@ -1860,6 +3219,8 @@ async fn build_overlay_info(
         } else {
             None
         },
+        shade,
+        shade_overridden,
     }
 }
 
@@ -1924,7 +3285,7 @@ async fn build_location_info_overlay(state: &AppState, offset: usize) -> MediaIn
 /// Load the current item into textures.
 async fn load_current_item<'a>(
     state: &AppState,
-    renderer: &mut Renderer<'_>,
+    renderer: &mut Renderer<'_, '_>,
     texture_creator: &'a sdl2::render::TextureCreator<sdl2::video::WindowContext>,
     textures: &mut MediaTextures<'a>,
     video_manager: &mut VideoManager,
@@ -1952,7 +3313,7 @@ async fn load_current_item<'a>(
     // Touch cache entries for LRU
     drop(cache);
     let mut cache = state.cache.write().await;
-    cache.touch(&media.id, AssetType::Display);
+    cache.touch_media(&media.id);
 
     // Start video if applicable
     start_video_if_applicable(media, &cache, video_manager, is_video_playing);
@@ -1960,35 +3321,64 @@ async fn load_current_item<'a>(
     Ok(())
 }
 
-/// Load a panel item at `current_index + offset` (wrapping) into textures.
-/// Used to load the right panel in dual-portrait mode.
-async fn load_panel_item<'a>(
-    state: &AppState,
-    renderer: &mut Renderer<'_>,
-    texture_creator: &'a sdl2::render::TextureCreator<sdl2::video::WindowContext>,
-    textures: &mut MediaTextures<'a>,
-    offset: usize,
-) -> Result<()> {
-    let playlist = state.playlist.read().await;
-    if playlist.is_empty() {
-        return Ok(());
+/// Textures for the panels after the first in a multi-image slide: those
+/// on screen, and those coming in with the next slide. The first panel
+/// lives in `current_textures`/`next_textures`, which also take video frames.
+struct ExtraPanels<'a> {
+    shown: Vec<MediaTextures<'a>>,
+    incoming: Vec<MediaTextures<'a>>,
+}
+
+impl<'a> ExtraPanels<'a> {
+    fn new() -> Self {
+        Self {
+            shown: Vec::new(),
+            incoming: Vec::new(),
+        }
     }
-    let current_index = *state.current_index.read().await;
-    let panel_index = (current_index + offset) % playlist.len();
-    let media = &playlist[panel_index];
 
-    state.preload_media_safe(media).await?;
+    /// Put the incoming panels on screen, if the next slide brought any.
+    fn swap_in(&mut self) {
+        if !self.incoming.is_empty() {
+            self.shown = std::mem::take(&mut self.incoming);
+        }
+    }
+}
 
-    let cache = state.cache.read().await;
-    *textures = state
-        .asset_manager
-        .load_textures(renderer, texture_creator, media, &cache)?;
-    drop(cache);
+/// Load textures for panels 1..`count` of the slide starting at playlist
+/// index `start` (panel 0 is loaded on its own, see `load_current_item`).
+async fn load_extra_panels<'a>(
+    state: &AppState,
+    renderer: &mut Renderer<'_, '_>,
+    texture_creator: &'a sdl2::render::TextureCreator<sdl2::video::WindowContext>,
+    start: usize,
+    count: usize,
+) -> Result<Vec<MediaTextures<'a>>> {
+    let mut panels = Vec::with_capacity(count.saturating_sub(1));
+    for offset in 1..count {
+        let media = {
+            let playlist = state.playlist.read().await;
+            if playlist.is_empty() {
+                break;
+            }
+            playlist[(start + offset) % playlist.len()].clone()
+        };
 
-    let mut cache = state.cache.write().await;
-    cache.touch(&media.id, AssetType::Display);
+        state.preload_media_safe(&media).await?;
 
-    Ok(())
+        let cache = state.cache.read().await;
+        panels.push(state.asset_manager.load_textures(
+            renderer,
+            texture_creator,
+            &media,
+            &cache,
+        )?);
+        drop(cache);
+
+        let mut cache = state.cache.write().await;
+        cache.touch_media(&media.id);
+    }
+    Ok(panels)
 }
 
 /// Image orientation bucket.
@@ -2060,7 +3450,13 @@ fn is_multi_panel_mode(mode: &str) -> bool {
 /// `recent_shown` lists media ids shown just before this reorder (oldest first); if an
 /// orientation bucket's shuffle happens to land one of them in the leading slot, it's
 /// swapped further back so the new cycle doesn't immediately repeat what's still on screen.
-fn reorder_for_dynamic_layouts(images: &mut Vec<assets::Media>, recent_shown: &VecDeque<String>) {
+///
+/// Portrait triples are only grouped on `wide_screen`s, where three fit side by side.
+fn reorder_for_dynamic_layouts(
+    images: &mut Vec<assets::Media>,
+    recent_shown: &VecDeque<String>,
+    wide_screen: bool,
+) {
     use rand::seq::SliceRandom;
     use rand::Rng;
     let mut rng = rand::thread_rng();
@@ -2110,8 +3506,19 @@ fn reorder_for_dynamic_layouts(images: &mut Vec<assets::Media>, recent_shown: &V
 
         // Build weighted option list based on what's available
         // 0=QuadLandscape(4L), 1=PortraitDualLandscape(1P+2L), 2=DualPortrait(2P),
-        // 3=SingleLandscape(1L), 4=DualSquare(2S), 5=SquarePortrait(1S+1P)
-        let opts: &[(u8, f32)] = &[(0, 4.0), (1, 3.0), (2, 2.0), (3, 1.5), (4, 2.0), (5, 2.0)];
+        // 3=SingleLandscape(1L), 4=DualSquare(2S), 5=SquarePortrait(1S+1P),
+        // 6=TriplePortrait(3P), 7=Mosaic(6L), 8=Mosaic(9L)
+        let opts: &[(u8, f32)] = &[
+            (0, 4.0),
+            (1, 3.0),
+            (2, 2.0),
+            (3, 1.5),
+            (4, 2.0),
+            (5, 2.0),
+            (6, 3.0),
+            (7, 1.0),
+            (8, 1.0),
+        ];
         let available: Vec<(u8, f32)> = opts
             .iter()
             .filter_map(|&(id, w)| {
@@ -2122,6 +3529,9 @@ fn reorder_for_dynamic_layouts(images: &mut Vec<assets::Media>, recent_shown: &V
                     3 => lc >= 1,
                     4 => sc >= 2,
                     5 => sc >= 1 && pc >= 1,
+                    6 => wide_screen && pc >= 3,
+                    7 => lc >= 6,
+                    8 => lc >= 9,
                     _ => false,
                 };
                 if ok {
@@ -2178,19 +3588,52 @@ fn reorder_for_dynamic_layouts(images: &mut Vec<assets::Media>, recent_shown: &V
                 images.push(s.pop_front().unwrap());
                 images.push(p.pop_front().unwrap());
             }
+            6 => {
+                for _ in 0..3 {
+                    images.push(p.pop_front().unwrap());
+                }
+            }
+            7 | 8 => {
+                let tiles = if choice == 7 { 6 } else { 9 };
+                for _ in 0..tiles {
+                    images.push(l.pop_front().unwrap());
+                }
+            }
             _ => unreachable!(),
         }
     }
 }
 
-/// Reorder a playlist for "portrait pairs" mode: landscape and square images are
-/// shown solo (to make maximal use of screen space), while portrait images are
-/// grouped two at a time so they reliably land side by side instead of each
-/// eating a full slide alone.
+/// Group a playlist for a multi-panel display mode and the screen's shape.
+/// Portrait screens only pair landscapes (stacked), in either mode.
+fn reorder_for_layouts(
+    images: &mut Vec<assets::Media>,
+    mode: &str,
+    recent_shown: &VecDeque<String>,
+    screen_shape: ScreenShape,
+) {
+    if screen_shape == ScreenShape::Portrait {
+        reorder_in_pairs(images, recent_shown, ImageOrientation::Landscape);
+    } else if mode == "portrait_pairs" {
+        reorder_in_pairs(images, recent_shown, ImageOrientation::Portrait);
+    } else {
+        reorder_for_dynamic_layouts(images, recent_shown, screen_shape == ScreenShape::Wide);
+    }
+}
+
+/// Reorder a playlist so images of the `paired` orientation are grouped two at
+/// a time, and everything else is shown solo (to make maximal use of screen
+/// space). "Portrait pairs" mode pairs portraits so they reliably land side by
+/// side instead of each eating a full slide alone; portrait screens pair
+/// landscapes, stacked.
 ///
-/// Mirrors [`reorder_for_dynamic_layouts`] but only ever produces the two groups
-/// this mode uses: a single solo image, or a pair of portraits.
-fn reorder_for_portrait_pairs(images: &mut Vec<assets::Media>, recent_shown: &VecDeque<String>) {
+/// Mirrors [`reorder_for_dynamic_layouts`] but only ever produces two groups: a
+/// single solo image, or a pair.
+fn reorder_in_pairs(
+    images: &mut Vec<assets::Media>,
+    recent_shown: &VecDeque<String>,
+    paired: ImageOrientation,
+) {
     use rand::seq::SliceRandom;
     use rand::Rng;
     let mut rng = rand::thread_rng();
@@ -2204,17 +3647,18 @@ fn reorder_for_portrait_pairs(images: &mut Vec<assets::Media>, recent_shown: &Ve
         }
     }
 
-    // Landscape and square images both display solo in this mode; only portraits pair up.
+    // Squares and the other orientation display solo; only `paired` pairs up.
     let mut solo: std::collections::VecDeque<assets::Media> = Default::default();
-    let mut portraits: std::collections::VecDeque<assets::Media> = Default::default();
+    let mut pairable: std::collections::VecDeque<assets::Media> = Default::default();
     for img in images.drain(..) {
-        match media_visual_orientation(&img) {
-            ImageOrientation::Portrait => portraits.push_back(img),
-            ImageOrientation::Landscape | ImageOrientation::Square => solo.push_back(img),
+        if media_visual_orientation(&img) == paired {
+            pairable.push_back(img);
+        } else {
+            solo.push_back(img);
         }
     }
     let (mut sv, mut pv): (Vec<_>, Vec<_>) =
-        (solo.into_iter().collect(), portraits.into_iter().collect());
+        (solo.into_iter().collect(), pairable.into_iter().collect());
     sv.shuffle(&mut rng);
     pv.shuffle(&mut rng);
     avoid_leading_repeat(&mut sv, recent_shown);
@@ -2230,7 +3674,7 @@ fn reorder_for_portrait_pairs(images: &mut Vec<assets::Media>, recent_shown: &Ve
         let pair_ok = pc >= 2;
         let solo_ok = sc >= 1;
         // Randomly interleave solo and paired groups when both are available;
-        // otherwise take whichever is possible. A single leftover portrait with
+        // otherwise take whichever is possible. A single leftover image with
         // no partner falls back to being shown solo (mirrors the analogous
         // fallback in `pick_dynamic_layout`).
         if pair_ok && (!solo_ok || rng.gen_bool(0.5)) {
@@ -2254,12 +3698,17 @@ fn reorder_for_portrait_pairs(images: &mut Vec<assets::Media>, recent_shown: &Ve
 /// Rules:
 /// - Only landscape images may be shown alone.
 /// - Portrait images are NEVER shown alone; we scan forward until a valid layout exists.
-/// - The 3-image layout is strictly [portrait, landscape, landscape].
-/// - The 4-image layout requires 4 consecutive landscapes.
+/// - The 3-image layouts are strictly [portrait, landscape, landscape], or three
+///   portraits on a wide screen.
+/// - The 4-image layout requires 4 consecutive landscapes; the mosaics 6 or 9.
 ///
 /// When `mode` is `"portrait_pairs"`, only `Single` (landscape or square) and
-/// `DualPortrait` are ever offered — the 3- and 4-image layouts and the
+/// `DualPortrait` are ever offered — the 3-image and larger layouts and the
 /// square-pairing layouts are reserved for full `"dynamic"` mode.
+///
+/// On a portrait screen the side-by-side layouts don't fit, in either mode:
+/// portraits and squares are shown alone, and landscapes stacked in pairs
+/// (`DualLandscape`), or alone when no landscape follows.
 async fn pick_dynamic_layout(
     state: &AppState,
     mode: &str,
@@ -2300,48 +3749,46 @@ async fn pick_dynamic_layout(
     };
 
     let restrict_to_pairs = mode == "portrait_pairs";
+    let screen_shape = state.screen_shape();
+
+    // Whether the images from `start` on have these orientations, none of
+    // them a video and no media id repeated.
+    let fits = |start: usize, orientations: &[ImageOrientation]| -> bool {
+        let idxs: Vec<usize> = (start..start + orientations.len()).collect();
+        idxs.iter()
+            .zip(orientations)
+            .all(|(&i, &o)| orient_of(i) == o && !is_video_at(i))
+            && ids_distinct(&idxs)
+    };
 
     // Helper: build valid layout candidates for a given start index.
     let candidates_at =
         |start: usize, rng: &mut rand::rngs::ThreadRng| -> Vec<(SlideLayout, f32)> {
-            let o0 = orient_of(start);
-            let o1 = orient_of(start + 1);
-            let o2 = orient_of(start + 2);
-            let o3 = orient_of(start + 3);
-            let (v0, v1, v2, v3) = (
-                is_video_at(start),
-                is_video_at(start + 1),
-                is_video_at(start + 2),
-                is_video_at(start + 3),
-            );
+            use ImageOrientation::{Landscape, Portrait, Square};
             let mut c: Vec<(SlideLayout, f32)> = Vec::new();
+            if screen_shape == ScreenShape::Portrait {
+                if fits(start, &[Landscape, Landscape]) {
+                    c.push((SlideLayout::DualLandscape { flipped: rng.gen() }, 3.0));
+                } else {
+                    c.push((SlideLayout::Single, 3.0));
+                }
+                return c;
+            }
             // Landscape photos may be shown alone; so may any video (regardless of
             // its orientation), since Single is the only layout a video can appear in.
             // In portrait-pairs mode, square photos have no pairing layout of their
             // own either, so they're shown alone too.
-            if o0 == ImageOrientation::Landscape
-                || v0
-                || (restrict_to_pairs && o0 == ImageOrientation::Square)
-            {
+            let o0 = orient_of(start);
+            if o0 == Landscape || is_video_at(start) || (restrict_to_pairs && o0 == Square) {
                 c.push((SlideLayout::Single, 3.0));
             }
-            if o0 == ImageOrientation::Portrait
-                && o1 == ImageOrientation::Portrait
-                && !v0
-                && !v1
-                && ids_distinct(&[start, start + 1])
-            {
+            if fits(start, &[Portrait, Portrait]) {
                 c.push((SlideLayout::DualPortrait { flipped: rng.gen() }, 2.0));
             }
-            if !restrict_to_pairs
-                && o0 == ImageOrientation::Portrait
-                && o1 == ImageOrientation::Landscape
-                && o2 == ImageOrientation::Landscape
-                && !v0
-                && !v1
-                && !v2
-                && ids_distinct(&[start, start + 1, start + 2])
-            {
+            if restrict_to_pairs {
+                return c;
+            }
+            if fits(start, &[Portrait, Landscape, Landscape]) {
                 c.push((
                     SlideLayout::PortraitDualLandscape {
                         portrait_right: rng.gen(),
@@ -2349,35 +3796,34 @@ async fn pick_dynamic_layout(
                     3.0,
                 ));
             }
-            if !restrict_to_pairs
-                && o0 == ImageOrientation::Landscape
-                && o1 == ImageOrientation::Landscape
-                && o2 == ImageOrientation::Landscape
-                && o3 == ImageOrientation::Landscape
-                && !v0
-                && !v1
-                && !v2
-                && !v3
-                && ids_distinct(&[start, start + 1, start + 2, start + 3])
-            {
+            if screen_shape == ScreenShape::Wide && fits(start, &[Portrait; 3]) {
+                c.push((SlideLayout::TriplePortrait, 3.0));
+            }
+            if fits(start, &[Landscape; 4]) {
                 c.push((SlideLayout::QuadLandscape { flipped: rng.gen() }, 4.0));
             }
-            if !restrict_to_pairs
-                && o0 == ImageOrientation::Square
-                && o1 == ImageOrientation::Square
-                && !v0
-                && !v1
-                && ids_distinct(&[start, start + 1])
-            {
+            if fits(start, &[Landscape; 6]) {
+                c.push((
+                    SlideLayout::Mosaic {
+                        columns: 3,
+                        rows: 2,
+                    },
+                    1.0,
+                ));
+            }
+            if fits(start, &[Landscape; 9]) {
+                c.push((
+                    SlideLayout::Mosaic {
+                        columns: 3,
+                        rows: 3,
+                    },
+                    1.0,
+                ));
+            }
+            if fits(start, &[Square, Square]) {
                 c.push((SlideLayout::DualSquare { flipped: rng.gen() }, 2.0));
             }
-            if !restrict_to_pairs
-                && o0 == ImageOrientation::Square
-                && o1 == ImageOrientation::Portrait
-                && !v0
-                && !v1
-                && ids_distinct(&[start, start + 1])
-            {
+            if fits(start, &[Square, Portrait]) {
                 c.push((
                     SlideLayout::SquarePortrait {
                         square_right: rng.gen(),
@@ -2481,24 +3927,59 @@ async fn pick_dynamic_layout(
     )
 }
 
+/// Where `advance_to_next` goes.
+#[derive(Debug, Clone, Copy)]
+enum Advance {
+    /// The slide after the current one.
+    Next,
+    /// Pick the slideshow up again at this playlist index.
+    ResumeAt(usize),
+    /// This playlist index, on its own (`show` command).
+    Show(usize),
+}
+
+/// A `show` command's media on screen, and how to get back to the slideshow.
+struct ShowHold {
+    media_id: String,
+    /// Fetched for the command rather than taken from the playlist; dropped
+    /// from it again afterwards.
+    inserted: bool,
+    /// Playlist index the slideshow resumes at.
+    resume_at: usize,
+    /// Keep it up until then instead of for the usual interval.
+    hold_until: Option<Instant>,
+}
+
+/// End a `show` command's hold, if one is up, and say where to go next:
+/// back to where the slideshow was, or simply on.
+async fn end_show(state: &AppState, show: &mut Option<ShowHold>) -> Advance {
+    let Some(show) = show.take() else {
+        return Advance::Next;
+    };
+    if show.inserted {
+        state
+            .playlist
+            .write()
+            .await
+            .retain(|m| m.id != show.media_id);
+    }
+    Advance::ResumeAt(show.resume_at)
+}
+
 /// Advance to the next item in the playlist.
 #[allow(clippy::too_many_arguments)]
 async fn advance_to_next<'a>(
     state: &AppState,
-    renderer: &mut Renderer<'_>,
+    renderer: &mut Renderer<'_, '_>,
     texture_creator: &'a sdl2::render::TextureCreator<sdl2::video::WindowContext>,
     current_textures: &mut MediaTextures<'a>,
     next_textures: &mut Option<MediaTextures<'a>>,
-    right_textures: &mut MediaTextures<'a>,
-    next_right_textures: &mut Option<MediaTextures<'a>>,
-    panel2_textures: &mut MediaTextures<'a>,
-    next_panel2_textures: &mut Option<MediaTextures<'a>>,
-    panel3_textures: &mut MediaTextures<'a>,
-    next_panel3_textures: &mut Option<MediaTextures<'a>>,
+    extra_panels: &mut ExtraPanels<'a>,
     video_manager: &mut VideoManager,
     is_video_playing: &mut bool,
     layout_history: &mut VecDeque<SlideLayoutKind>,
     session_start: Instant,
+    to: Advance,
 ) -> Result<()> {
     // Stop current video
     video_manager.stop();
@@ -2511,7 +3992,8 @@ async fn advance_to_next<'a>(
         }
     }
 
-    let is_dynamic = is_multi_panel_mode(&state.config.display_mode);
+    let display_mode = state.live_config.read().await.display_mode.clone();
+    let is_dynamic = is_multi_panel_mode(&display_mode);
     let warmup = session_start.elapsed().as_secs() < 120;
 
     // Compute where the next slide starts (after current slide's images).
@@ -2523,21 +4005,17 @@ async fn advance_to_next<'a>(
     // In dynamic / portrait-pairs mode, regroup the playlist whenever we complete a
     // full cycle so that each new pass shows images in a freshly randomised
     // layout-compatible order.
-    if is_dynamic && next_start_raw >= n {
+    if is_dynamic && next_start_raw >= n && matches!(to, Advance::Next) {
         let recent = state.recent_shown.read().await.clone();
         let mut playlist = state.playlist.write().await;
-        if state.config.display_mode == "portrait_pairs" {
-            reorder_for_portrait_pairs(&mut playlist, &recent);
-        } else {
-            reorder_for_dynamic_layouts(&mut playlist, &recent);
-        }
-        tracing::debug!(
-            "{}: reordered playlist for new cycle",
-            state.config.display_mode
-        );
+        reorder_for_layouts(&mut playlist, &display_mode, &recent, state.screen_shape());
+        tracing::debug!("{}: reordered playlist for new cycle", display_mode);
     }
 
-    let next_start = next_start_raw % n;
+    let next_start = match to {
+        Advance::Next => next_start_raw,
+        Advance::ResumeAt(i) | Advance::Show(i) => i,
+    } % n;
 
     // Pick layout for the next slide, peeking from next_start. This is kept in a
     // local variable rather than written to renderer.current_layout right away --
@@ -2547,22 +4025,20 @@ async fn advance_to_next<'a>(
     // the incoming layout instead; update_transition() flips current_layout to it
     // at the exact frame the texture swap occurs.
     // pick_dynamic_layout acquires the playlist lock internally — don't hold it here.
-    let (next_index, mut next_layout) = if is_dynamic {
-        let (layout, actual_start) = pick_dynamic_layout(
-            state,
-            &state.config.display_mode,
-            layout_history,
-            warmup,
-            next_start,
-        )
-        .await;
+    let (next_index, mut next_layout) = if let (Advance::Show(_), true) = (to, is_dynamic) {
+        // A shown item goes up on its own, not grouped with whatever
+        // happens to follow it.
+        (next_start, SlideLayout::Single)
+    } else if is_dynamic {
+        let (layout, actual_start) =
+            pick_dynamic_layout(state, &display_mode, layout_history, warmup, next_start).await;
         layout_history.push_back(layout.kind());
         if layout_history.len() > 20 {
             layout_history.pop_front();
         }
         (actual_start, layout)
     } else {
-        (next_start, renderer.current_layout)
+        (next_start, SlideLayout::Single)
     };
 
     let step = next_layout.image_count();
@@ -2591,7 +4067,7 @@ async fn advance_to_next<'a>(
     tracing::debug!("Advancing to: {} ({})", media.id, media.media_type);
 
     // Preload ahead in background
-    let preloader = Preloader::new(state.asset_manager.clone(), state.client.clone());
+    let preloader = Preloader::new(state.asset_manager.clone(), state.download_client.clone());
     let token = state.token().await;
     let playlist_clone = playlist.clone();
     let preload_ahead = (step * 2).max(4);
@@ -2613,49 +4089,11 @@ async fn advance_to_next<'a>(
 
     *next_textures = Some(new_textures);
 
+    drop(playlist);
+
     // Load extra panels for multi-image layouts
-    if step >= 2 {
-        let idx = (next_index + 1) % playlist.len();
-        let m = playlist[idx].clone();
-        state.preload_media_safe(&m).await?;
-        let cache = state.cache.read().await;
-        let t = state
-            .asset_manager
-            .load_textures(renderer, texture_creator, &m, &cache)?;
-        drop(cache);
-        *next_right_textures = Some(t);
-        let mut cache = state.cache.write().await;
-        cache.touch(&m.id, AssetType::Display);
-    }
-    if step >= 3 {
-        let idx = (next_index + 2) % playlist.len();
-        let m = playlist[idx].clone();
-        state.preload_media_safe(&m).await?;
-        let cache = state.cache.read().await;
-        let t = state
-            .asset_manager
-            .load_textures(renderer, texture_creator, &m, &cache)?;
-        drop(cache);
-        *next_panel2_textures = Some(t);
-        let mut cache = state.cache.write().await;
-        cache.touch(&m.id, AssetType::Display);
-    }
-    if step >= 4 {
-        let idx = (next_index + 3) % playlist.len();
-        let m = playlist[idx].clone();
-        drop(playlist);
-        state.preload_media_safe(&m).await?;
-        let cache = state.cache.read().await;
-        let t = state
-            .asset_manager
-            .load_textures(renderer, texture_creator, &m, &cache)?;
-        drop(cache);
-        *next_panel3_textures = Some(t);
-        let mut cache = state.cache.write().await;
-        cache.touch(&m.id, AssetType::Display);
-    } else {
-        drop(playlist);
-    }
+    extra_panels.incoming =
+        load_extra_panels(state, renderer, texture_creator, next_index, step).await?;
 
     // Minimum-size guard: after loading actual textures, verify that every image in the
     // chosen layout renders at least 20% of the screen's width and height. If any image
@@ -2666,12 +4104,11 @@ async fn advance_to_next<'a>(
         let (sw, sh) = renderer.screen_size();
         let min_w = sw / 5;
         let min_h = sh / 5;
-        let sizes: [Option<(u32, u32)>; 4] = [
-            next_textures.as_ref().and_then(|t| t.display_size),
-            next_right_textures.as_ref().and_then(|t| t.display_size),
-            next_panel2_textures.as_ref().and_then(|t| t.display_size),
-            next_panel3_textures.as_ref().and_then(|t| t.display_size),
-        ];
+        let sizes: Vec<Option<(u32, u32)>> = next_textures
+            .iter()
+            .chain(&extra_panels.incoming)
+            .map(|t| t.display_size)
+            .collect();
         let rects = Renderer::compute_panel_rects(next_layout, sw, sh, &sizes);
         let too_small = rects
             .iter()
@@ -2683,28 +4120,18 @@ async fn advance_to_next<'a>(
             );
             next_layout = SlideLayout::Single;
             // Clear the extra-panel textures; they will be shown on subsequent slides.
-            *next_right_textures = None;
-            *next_panel2_textures = None;
-            *next_panel3_textures = None;
+            extra_panels.incoming.clear();
         }
     }
 
-    let transition = Transition::from_str(&state.config.transition);
+    let transition = renderer.choose_transition(next_layout);
     match transition {
         Transition::Cut => {
             renderer.current_layout = next_layout;
             if let Some(next) = next_textures.take() {
                 *current_textures = next;
             }
-            if let Some(next) = next_right_textures.take() {
-                *right_textures = next;
-            }
-            if let Some(next) = next_panel2_textures.take() {
-                *panel2_textures = next;
-            }
-            if let Some(next) = next_panel3_textures.take() {
-                *panel3_textures = next;
-            }
+            extra_panels.swap_in();
         }
         _ => {
             renderer.start_transition(next_layout);
@@ -2715,7 +4142,7 @@ async fn advance_to_next<'a>(
     let playlist = state.playlist.read().await;
     let media = &playlist[next_index % playlist.len()];
     let mut cache = state.cache.write().await;
-    cache.touch(&media.id, AssetType::Display);
+    cache.touch_media(&media.id);
 
     // For Cut, current_textures already holds the new item, so it's safe to
     // start decoding now. For animated transitions the swap above hasn't
@@ -2735,13 +4162,11 @@ async fn advance_to_next<'a>(
 #[allow(clippy::too_many_arguments)]
 async fn go_to_previous<'a>(
     state: &AppState,
-    renderer: &mut Renderer<'_>,
+    renderer: &mut Renderer<'_, '_>,
     texture_creator: &'a sdl2::render::TextureCreator<sdl2::video::WindowContext>,
     current_textures: &mut MediaTextures<'a>,
     next_textures: &mut Option<MediaTextures<'a>>,
-    right_textures: &mut MediaTextures<'a>,
-    panel2_textures: &mut MediaTextures<'a>,
-    panel3_textures: &mut MediaTextures<'a>,
+    extra_panels: &mut ExtraPanels<'a>,
     video_manager: &mut VideoManager,
     is_video_playing: &mut bool,
 ) -> Result<()> {
@@ -2780,49 +4205,18 @@ async fn go_to_previous<'a>(
     *next_textures = None;
     *current_textures = new_textures;
 
+    drop(playlist);
+
     // Load extra panels for multi-image layouts (cut transition for previous)
-    if step >= 2 {
-        let idx = (prev_index + 1) % playlist.len();
-        let m = playlist[idx].clone();
-        state.preload_media_safe(&m).await?;
-        let cache = state.cache.read().await;
-        *right_textures =
-            state
-                .asset_manager
-                .load_textures(renderer, texture_creator, &m, &cache)?;
-        drop(cache);
-    }
-    if step >= 3 {
-        let idx = (prev_index + 2) % playlist.len();
-        let m = playlist[idx].clone();
-        state.preload_media_safe(&m).await?;
-        let cache = state.cache.read().await;
-        *panel2_textures =
-            state
-                .asset_manager
-                .load_textures(renderer, texture_creator, &m, &cache)?;
-        drop(cache);
-    }
-    if step >= 4 {
-        let idx = (prev_index + 3) % playlist.len();
-        let m = playlist[idx].clone();
-        drop(playlist);
-        state.preload_media_safe(&m).await?;
-        let cache = state.cache.read().await;
-        *panel3_textures =
-            state
-                .asset_manager
-                .load_textures(renderer, texture_creator, &m, &cache)?;
-        drop(cache);
-    } else {
-        drop(playlist);
-    }
+    extra_panels.incoming.clear();
+    extra_panels.shown =
+        load_extra_panels(state, renderer, texture_creator, prev_index, step).await?;
 
     // Touch cache for left panel
     let playlist = state.playlist.read().await;
     let media = &playlist[prev_index % playlist.len()];
     let mut cache = state.cache.write().await;
-    cache.touch(&media.id, AssetType::Display);
+    cache.touch_media(&media.id);
 
     // Start video if applicable
     start_video_if_applicable(media, &cache, video_manager, is_video_playing);
@@ -2846,6 +4240,75 @@ fn reexec_self() -> ! {
     std::process::exit(0);
 }
 
+/// Put a freshly fetched playlist in the order the display mode calls for:
+/// grouped for multi-panel layouts, else shuffled if enabled.
+fn arrange_playlist(
+    playlist: &mut Vec<Media>,
+    config: &AppConfig,
+    recent: &VecDeque<String>,
+    screen_shape: ScreenShape,
+) {
+    if is_multi_panel_mode(&config.display_mode) {
+        // Always reorder into layout-compatible groups so that multi-image
+        // layouts (quad-landscape, portrait pairs, etc.) fire reliably.
+        reorder_for_layouts(playlist, &config.display_mode, recent, screen_shape);
+    } else if config.shuffle {
+        use rand::seq::SliceRandom;
+        playlist.shuffle(&mut rand::thread_rng());
+    }
+}
+
+/// Re-fetch the playlist in server order and arrange it for a changed
+/// display mode or shuffle setting. Returns the new index of the item on
+/// screen, to pick up from.
+async fn rearrange_playlist(state: &AppState, config: &AppConfig) -> usize {
+    let current_id = {
+        let playlist = state.playlist.read().await;
+        let idx = *state.current_index.read().await;
+        playlist.get(idx).map(|m| m.id.clone())
+    };
+    let mut playlist = match state.fetch_playlist_full().await {
+        Ok(playlist) => playlist,
+        Err(e) => {
+            tracing::warn!(
+                "Failed to re-fetch playlist, rearranging the current one: {}",
+                e
+            );
+            state.playlist.read().await.clone()
+        }
+    };
+    let recent = state.recent_shown.read().await.clone();
+    arrange_playlist(&mut playlist, config, &recent, state.screen_shape());
+    let index = current_id
+        .and_then(|id| playlist.iter().position(|m| m.id == id))
+        .unwrap_or(0);
+    if let Err(e) = state.cache.read().await.save_playlist(&playlist) {
+        tracing::warn!("Failed to save playlist: {}", e);
+    }
+    *state.playlist.write().await = playlist;
+    *state.current_index.write().await = index;
+    index
+}
+
+/// Keep the cache reconciled with the playlist for as long as we run.
+async fn run_background_sync(state: Arc<AppState>) {
+    loop {
+        let (playlist, current_index) = {
+            let playlist = state.playlist.read().await.clone();
+            let index = *state.current_index.read().await;
+            (playlist, index)
+        };
+        if !playlist.is_empty() {
+            let token = state.token().await;
+            state
+                .sync
+                .reconcile(&playlist, current_index, token.as_deref())
+                .await;
+        }
+        state.sync.wait_turn().await;
+    }
+}
+
 /// Handle a realtime event.
 async fn handle_realtime_event(state: &AppState, event: RealtimeEvent) {
     match event {
@@ -2857,6 +4320,7 @@ async fn handle_realtime_event(state: &AppState, event: RealtimeEvent) {
         }
         RealtimeEvent::RefreshNeeded => {
             tracing::info!("Refreshing playlist...");
+            let config = state.live_config.read().await.clone();
             match state.fetch_playlist().await {
                 Ok(mut playlist) => {
                     // Save playlist to cache
@@ -2867,9 +4331,11 @@ async fn handle_realtime_event(state: &AppState, event: RealtimeEvent) {
                         }
                     }
 
-                    // Clean up orphaned cache entries
+                    // Clean up orphaned cache entries (after re-reading tag
+                    // pins, which protect their media from the cleanup)
                     {
                         let mut cache = state.cache.write().await;
+                        cache.apply_tag_pins(&playlist, &config.pinned_tags);
                         cache.cleanup_orphans(&playlist);
                         let stats = cache.stats();
                         tracing::debug!(
@@ -2879,7 +4345,7 @@ async fn handle_realtime_event(state: &AppState, event: RealtimeEvent) {
                         );
                     }
 
-                    if is_multi_panel_mode(&state.config.display_mode) {
+                    if is_multi_panel_mode(&config.display_mode) {
                         // This refresh fires often (e.g. every realtime reconnect) --
                         // without reordering here, dynamic mode's grouping only ever
                         // survives until the next refresh, which replaces the playlist
@@ -2892,11 +4358,12 @@ async fn handle_realtime_event(state: &AppState, event: RealtimeEvent) {
                             old_playlist.get(idx).map(|m| m.id.clone())
                         };
                         let recent = state.recent_shown.read().await.clone();
-                        if state.config.display_mode == "portrait_pairs" {
-                            reorder_for_portrait_pairs(&mut playlist, &recent);
-                        } else {
-                            reorder_for_dynamic_layouts(&mut playlist, &recent);
-                        }
+                        reorder_for_layouts(
+                            &mut playlist,
+                            &config.display_mode,
+                            &recent,
+                            state.screen_shape(),
+                        );
                         if let Some(id) = current_id {
                             if let Some(new_idx) = playlist.iter().position(|m| m.id == id) {
                                 *state.current_index.write().await = new_idx;
@@ -2905,6 +4372,7 @@ async fn handle_realtime_event(state: &AppState, event: RealtimeEvent) {
                     }
 
                     *state.playlist.write().await = playlist;
+                    state.sync.kick();
                 }
                 Err(e) => {
                     tracing::error!("Failed to refresh playlist: {}", e);
@@ -2913,7 +4381,10 @@ async fn handle_realtime_event(state: &AppState, event: RealtimeEvent) {
         }
         RealtimeEvent::MediaCreated(media) => {
             tracing::info!("Media created: {}", media.id);
+            let pinned_tags = state.live_config.read().await.pinned_tags.clone();
             let mut playlist = state.playlist.write().await;
+            let mut cache = state.cache.write().await;
+            cache.apply_tag_pins(std::slice::from_ref(&media), &pinned_tags);
             // Guard against duplicate entries: a MediaCreated event can race with a
             // RefreshNeeded full re-fetch that already picked up the same record,
             // which previously left the same media id in the playlist twice and let
@@ -2924,20 +4395,23 @@ async fn handle_realtime_event(state: &AppState, event: RealtimeEvent) {
                 playlist.push(media);
             }
 
-            let cache = state.cache.read().await;
             let _ = cache.save_playlist(&playlist);
+            state.sync.kick();
         }
         RealtimeEvent::MediaUpdated(media) => {
             tracing::info!("Media updated: {}", media.id);
+            let pinned_tags = state.live_config.read().await.pinned_tags.clone();
             let mut playlist = state.playlist.write().await;
+            let mut cache = state.cache.write().await;
+            cache.apply_tag_pins(std::slice::from_ref(&media), &pinned_tags);
             if let Some(pos) = playlist.iter().position(|m| m.id == media.id) {
                 playlist[pos] = media;
             } else {
                 playlist.push(media);
             }
 
-            let cache = state.cache.read().await;
             let _ = cache.save_playlist(&playlist);
+            state.sync.kick();
         }
         RealtimeEvent::MediaDeleted(id) => {
             tracing::info!("Media deleted: {}", id);
@@ -2948,11 +4422,26 @@ async fn handle_realtime_event(state: &AppState, event: RealtimeEvent) {
             let _ = cache.save_playlist(&playlist);
             // Remove this item's cached files immediately rather than
             // waiting for the next periodic RefreshNeeded cleanup pass.
+            cache.unpin_deleted(&id);
             cache.cleanup_orphans(&playlist);
         }
-        RealtimeEvent::ConfigChanged => {
-            tracing::info!("Device config changed — restarting to apply new settings");
-            reexec_self();
+        RealtimeEvent::RemotePin { media_ids, pinned } => {
+            tracing::info!(
+                "Remote: {} {:?}",
+                if pinned { "pin" } else { "unpin" },
+                media_ids
+            );
+            state.cache.write().await.set_pinned(&media_ids, pinned);
+            if pinned {
+                // Fetch newly pinned media now rather than on the next advance.
+                let preloader =
+                    Preloader::new(state.asset_manager.clone(), state.download_client.clone());
+                let playlist = state.playlist.read().await.clone();
+                let token = state.token().await;
+                tokio::spawn(async move {
+                    preloader.preload_pinned(&playlist, token.as_deref()).await;
+                });
+            }
         }
         RealtimeEvent::RepairRequested => {
             tracing::info!(
@@ -2961,19 +4450,25 @@ async fn handle_realtime_event(state: &AppState, event: RealtimeEvent) {
             if let Err(e) = discovery::clear_device_api_key() {
                 tracing::error!("Failed to clear device_api_key for repair: {}", e);
             }
+            state.cache.write().await.flush_index();
             reexec_self();
         }
         // Remote control events are handled inline in the render loop, not here.
-        RealtimeEvent::RemoteNext
+        RealtimeEvent::ConfigChanged
+        | RealtimeEvent::RemoteNext
         | RealtimeEvent::RemotePrev
         | RealtimeEvent::RemoteRandom
         | RealtimeEvent::RemotePause { .. }
         | RealtimeEvent::RemoteResume
+        | RealtimeEvent::RemoteShow { .. }
+        | RealtimeEvent::RemoteDisplay { .. }
+        | RealtimeEvent::RemoteBrightness { .. }
         | RealtimeEvent::RemoteTagFilter { .. }
         | RealtimeEvent::RemoteTagFilterClear
         | RealtimeEvent::BulkUploadStart
         | RealtimeEvent::BulkUploadProgress { .. }
-        | RealtimeEvent::BulkUploadEnd => {}
+        | RealtimeEvent::BulkUploadEnd
+        | RealtimeEvent::Command { .. } => {}
     }
 }
 
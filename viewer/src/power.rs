@@ -0,0 +1,99 @@
+//! Switching the display itself off and on around quiet hours.
+//!
+//! The screen goes black during quiet hours either way; a power backend
+//! additionally turns the panel off (saving power and the backlight) by
+//! running whatever command the platform offers.
+
+use std::process::Command;
+
+/// How the display's power is switched, from `display_power`.
+#[derive(Debug, Clone)]
+pub enum DisplayPower {
+    /// Leave the display alone; quiet hours only blank the picture.
+    None,
+    /// Log each switch without touching the display, for trying out a
+    /// schedule.
+    NoOp,
+    /// Run a command (program and arguments, no shell) for each switch.
+    Command { on: Vec<String>, off: Vec<String> },
+}
+
+impl DisplayPower {
+    /// Pick the backend: "none", "noop", a preset ("vcgencmd", "xset" for
+    /// X11 DPMS, "wlr-randr" for the first HDMI output under Wayland), or
+    /// "command" for `on_command`/`off_command`. Those two also override a
+    /// preset's commands when set.
+    pub fn from_settings(backend: &str, on_command: &str, off_command: &str) -> Self {
+        let (on, off) = match backend.trim().to_ascii_lowercase().as_str() {
+            "" | "none" => return Self::None,
+            "noop" => return Self::NoOp,
+            "vcgencmd" => ("vcgencmd display_power 1", "vcgencmd display_power 0"),
+            "xset" | "dpms" => ("xset dpms force on", "xset dpms force off"),
+            "wlr-randr" => (
+                "wlr-randr --output HDMI-A-1 --on",
+                "wlr-randr --output HDMI-A-1 --off",
+            ),
+            "command" => ("", ""),
+            other => {
+                tracing::warn!(
+                    "Unknown display_power {:?}, leaving the display alone",
+                    other
+                );
+                return Self::None;
+            }
+        };
+        let pick = |custom: &str, preset: &str| -> Vec<String> {
+            let command = if custom.trim().is_empty() {
+                preset
+            } else {
+                custom
+            };
+            command.split_whitespace().map(str::to_string).collect()
+        };
+        let (on, off) = (pick(on_command, on), pick(off_command, off));
+        if on.is_empty() || off.is_empty() {
+            tracing::warn!(
+                "display_power = \"command\" needs both display_on_command and display_off_command; leaving the display alone"
+            );
+            return Self::None;
+        }
+        Self::Command { on, off }
+    }
+
+    /// Whether switching off really powers the display down.
+    pub fn powers_off(&self) -> bool {
+        matches!(self, Self::Command { .. })
+    }
+
+    /// Switch the display on or off in the background; failures are logged.
+    pub fn set(&self, on: bool) {
+        let state = if on { "on" } else { "off" };
+        match self {
+            Self::None => {}
+            Self::NoOp => tracing::info!("Display power {} (no-op backend)", state),
+            Self::Command {
+                on: on_command,
+                off: off_command,
+            } => {
+                let argv = if on { on_command } else { off_command }.clone();
+                tokio::task::spawn_blocking(move || {
+                    match Command::new(&argv[0]).args(&argv[1..]).status() {
+                        Ok(status) if status.success() => {
+                            tracing::info!("Display power {} ({})", state, argv.join(" "));
+                        }
+                        Ok(status) => tracing::warn!(
+                            "Display power command {:?} exited with {}",
+                            argv.join(" "),
+                            status
+                        ),
+                        Err(e) => tracing::warn!(
+                            "Failed to run display power command {:?}: {}",
+                            argv.join(" "),
+                            e
+                        ),
+                    }
+                });
+            }
+        }
+    }
+}
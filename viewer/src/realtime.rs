@@ -6,10 +6,16 @@
 //!   3. Read events from the open SSE stream
 
 use crate::assets::Media;
+use crate::brightness::Shade;
+use crate::commands::{AckStatus, CommandAcks};
+use crate::discovery;
 use anyhow::{Context, Result};
 use futures_util::StreamExt;
-use reqwest::Client;
+use rand::Rng;
+use reqwest::{Client, StatusCode};
 use serde::Deserialize;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
 use tokio::sync::mpsc;
 use tokio::time::{sleep, Duration};
 
@@ -31,11 +37,34 @@ pub enum RealtimeEvent {
         secs: u64,
     },
     RemoteResume,
+    /// Put a particular media item up now, for `hold_secs` if given rather
+    /// than the usual interval, then carry on with the slideshow.
+    RemoteShow {
+        media_id: String,
+        hold_secs: Option<u64>,
+    },
+    /// Turn the display on or off over quiet hours, for `secs` if given,
+    /// else until the schedule next changes.
+    RemoteDisplay {
+        on: bool,
+        secs: Option<u64>,
+    },
+    /// Override the brightness schedule, for `secs` if given, else until
+    /// the next one; `shade: None` goes back to the schedule.
+    RemoteBrightness {
+        shade: Option<Shade>,
+        secs: Option<u64>,
+    },
     RemoteTagFilter {
         tags: Vec<String>,
         mode: String,
     },
     RemoteTagFilterClear,
+    /// Pin (or unpin) media in the offline cache.
+    RemotePin {
+        media_ids: Vec<String>,
+        pinned: bool,
+    },
     BulkUploadStart,
     BulkUploadProgress {
         done: u32,
@@ -44,6 +73,95 @@ pub enum RealtimeEvent {
         lines: Vec<String>,
     },
     BulkUploadEnd,
+    /// A command from the device inbox, with the inbox record id to
+    /// acknowledge it under.
+    Command {
+        id: String,
+        command: Box<RealtimeEvent>,
+    },
+}
+
+/// First delay before reconnecting, unless the server set one with
+/// `retry:`. Doubles with each failed attempt, up to `RECONNECT_MAX`.
+const RECONNECT_DELAY: Duration = Duration::from_secs(5);
+const RECONNECT_MAX: Duration = Duration::from_secs(5 * 60);
+
+/// Longest hold a `show` command may ask for.
+const MAX_SHOW_HOLD_SECS: u64 = 24 * 60 * 60;
+
+/// Longest a `display-on`/`display-off` command may override quiet hours.
+const MAX_DISPLAY_OVERRIDE_SECS: u64 = 24 * 60 * 60;
+
+/// Longest a `brightness` command may override the schedule.
+const MAX_BRIGHTNESS_OVERRIDE_SECS: u64 = 24 * 60 * 60;
+
+/// What went wrong with the last connection attempt.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorKind {
+    /// The token was rejected (401/403).
+    Auth,
+    /// No response, a dropped or idle connection.
+    Network,
+    /// An error status or a response we couldn't make sense of.
+    Server,
+}
+
+impl ErrorKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ErrorKind::Auth => "auth",
+            ErrorKind::Network => "network",
+            ErrorKind::Server => "server",
+        }
+    }
+
+    fn of(e: &anyhow::Error) -> Self {
+        if e.chain().any(|c| c.is::<tokio::time::error::Elapsed>()) {
+            return ErrorKind::Network;
+        }
+        match e.chain().find_map(|c| c.downcast_ref::<reqwest::Error>()) {
+            Some(re) => match re.status() {
+                Some(StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN) => ErrorKind::Auth,
+                Some(_) => ErrorKind::Server,
+                None if re.is_decode() => ErrorKind::Server,
+                None => ErrorKind::Network,
+            },
+            None => ErrorKind::Server,
+        }
+    }
+}
+
+/// Connection state of the realtime subscription.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ConnectionState {
+    #[default]
+    Connecting,
+    Connected,
+    /// Waiting out the backoff before the next attempt.
+    Waiting,
+}
+
+/// Realtime connection status, for the debug overlay.
+#[derive(Debug, Clone, Default)]
+pub struct RealtimeStatus {
+    pub state: ConnectionState,
+    /// Failed attempts since the last successful connection.
+    pub attempts: u32,
+    /// How the last attempt failed.
+    pub last_error: Option<(ErrorKind, String)>,
+    /// When the next attempt is due, while `Waiting`.
+    pub next_retry: Option<Instant>,
+}
+
+/// Delay before reconnect attempt number `attempts` (from 1), with jitter
+/// over the upper half so frames that lost the server together don't all
+/// come back at the same moment.
+fn reconnect_delay(base: Duration, attempts: u32) -> Duration {
+    let delay = base
+        .saturating_mul(1 << attempts.saturating_sub(1).min(16))
+        .min(RECONNECT_MAX);
+    let ms = delay.as_millis() as u64;
+    Duration::from_millis(rand::thread_rng().gen_range(ms / 2..=ms))
 }
 
 #[derive(Debug, Default)]
@@ -52,16 +170,117 @@ struct SseEvent {
     data: String,
 }
 
-fn parse_sse_block(block: &str) -> SseEvent {
-    let mut ev = SseEvent::default();
-    for line in block.lines() {
-        if let Some(v) = line.strip_prefix("event:") {
-            ev.event_type = v.trim().to_string();
-        } else if let Some(v) = line.strip_prefix("data:") {
-            ev.data = v.trim().to_string();
+/// Incremental `text/event-stream` parser, following the WHATWG spec: lines
+/// end in `\n`, `\r\n` or `\r`; `data:` lines are joined with `\n`; `id:`
+/// and `retry:` are remembered across reconnects. Bytes are buffered until a
+/// whole line is in, so a UTF-8 character split across chunks survives.
+#[derive(Debug, Default)]
+struct SseParser {
+    buf: Vec<u8>,
+    /// The last chunk ended in `\r`; a `\n` starting the next one belongs
+    /// to the same line break.
+    skip_lf: bool,
+    /// At the start of the stream, where a byte-order mark may be.
+    started: bool,
+    event_type: String,
+    data: String,
+    has_data: bool,
+    /// Sent back as `Last-Event-ID` when reconnecting.
+    last_event_id: String,
+    /// Reconnection delay requested by the server.
+    retry: Option<Duration>,
+}
+
+impl SseParser {
+    /// Prepare for a new connection: drop anything half-received, but keep
+    /// the last event id and retry delay.
+    fn restart(&mut self) {
+        self.buf.clear();
+        self.skip_lf = false;
+        self.started = false;
+        self.event_type.clear();
+        self.data.clear();
+        self.has_data = false;
+    }
+
+    /// Add bytes from the stream; returns the events they complete.
+    fn feed(&mut self, chunk: &[u8]) -> Vec<SseEvent> {
+        self.buf.extend_from_slice(chunk);
+        let mut events = Vec::new();
+        let mut start = 0;
+        for i in 0..self.buf.len() {
+            let b = self.buf[i];
+            if std::mem::take(&mut self.skip_lf) && b == b'\n' {
+                start = i + 1;
+                continue;
+            }
+            if b != b'\n' && b != b'\r' {
+                continue;
+            }
+            self.skip_lf = b == b'\r';
+            let mut line = String::from_utf8_lossy(&self.buf[start..i]).into_owned();
+            start = i + 1;
+            if !std::mem::replace(&mut self.started, true) {
+                if let Some(rest) = line.strip_prefix('\u{feff}') {
+                    line = rest.to_string();
+                }
+            }
+            if let Some(ev) = self.process_line(&line) {
+                events.push(ev);
+            }
         }
+        self.buf.drain(..start);
+        events
+    }
+
+    fn process_line(&mut self, line: &str) -> Option<SseEvent> {
+        if line.is_empty() {
+            return self.dispatch();
+        }
+        if line.starts_with(':') {
+            // Comment, used as a keepalive.
+            return None;
+        }
+        let (field, value) = match line.split_once(':') {
+            Some((field, value)) => (field, value.strip_prefix(' ').unwrap_or(value)),
+            None => (line, ""),
+        };
+        match field {
+            "event" => self.event_type = value.to_string(),
+            "data" => {
+                if self.has_data {
+                    self.data.push('\n');
+                }
+                self.data.push_str(value);
+                self.has_data = true;
+            }
+            "id" if !value.contains('\0') => self.last_event_id = value.to_string(),
+            "retry" => {
+                if let Ok(ms) = value.parse::<u64>() {
+                    self.retry = Some(Duration::from_millis(ms));
+                }
+            }
+            _ => {}
+        }
+        None
+    }
+
+    /// End of an event (a blank line). An event without data lines is
+    /// dropped, as the spec says.
+    fn dispatch(&mut self) -> Option<SseEvent> {
+        let event_type = std::mem::take(&mut self.event_type);
+        if !std::mem::take(&mut self.has_data) {
+            return None;
+        }
+        Some(SseEvent {
+            event_type: if event_type.is_empty() {
+                "message".to_string()
+            } else {
+                event_type
+            },
+            data: std::mem::take(&mut self.data),
+        })
     }
-    ev
 }
 
 #[derive(Debug, Deserialize)]
@@ -76,18 +295,61 @@ pub struct RealtimeManager {
     pb_url: String,
     event_tx: mpsc::Sender<RealtimeEvent>,
     device_id: Option<String>,
+    /// With `device_id`, lets a rejected token be replaced through
+    /// device-auth.
+    device_api_key: Option<String>,
+    /// Reconnect when nothing at all (not even a keepalive) arrives for this
+    /// long, in case the connection went half-open.
+    idle_timeout: Duration,
+    status: Arc<Mutex<RealtimeStatus>>,
+    acks: Arc<CommandAcks>,
 }
 
 impl RealtimeManager {
     pub fn new(
         pb_url: String,
         device_id: Option<String>,
+        device_api_key: Option<String>,
+        idle_timeout: Duration,
+        status: Arc<Mutex<RealtimeStatus>>,
+        acks: Arc<CommandAcks>,
         event_tx: mpsc::Sender<RealtimeEvent>,
     ) -> Self {
         Self {
             pb_url,
             event_tx,
             device_id,
+            device_api_key,
+            idle_timeout,
+            status,
+            acks,
+        }
+    }
+
+    fn update_status(&self, f: impl FnOnce(&mut RealtimeStatus)) {
+        f(&mut self.status.lock().unwrap_or_else(|e| e.into_inner()));
+    }
+
+    /// A token to replace one the server rejected: a fresh one from
+    /// device-auth if this device has credentials, otherwise none — media
+    /// and the device inbox can be subscribed to without auth.
+    async fn refresh_token(&self, client: &Client) -> Option<String> {
+        let (Some(id), Some(key)) = (&self.device_id, &self.device_api_key) else {
+            tracing::warn!("Realtime token rejected, reconnecting without one");
+            return None;
+        };
+        match discovery::device_auth(client, &self.pb_url, id, key).await {
+            Ok(resp) => {
+                tracing::info!("Realtime token refreshed via device-auth");
+                Some(resp.token)
+            }
+            Err(e) => {
+                tracing::error!(
+                    "Device auth failed while refreshing the realtime token: {} — reconnecting without one",
+                    e
+                );
+                None
+            }
         }
     }
 
@@ -109,62 +371,112 @@ impl RealtimeManager {
         subs
     }
 
-    pub async fn run(&self, token: Option<String>) {
+    pub async fn run(&self, mut token: Option<String>) {
+        // No overall request timeout: the stream is meant to stay open. The
+        // idle watchdog in connect_and_subscribe catches dead connections.
         let client = Client::builder()
-            .timeout(Duration::from_secs(60))
+            .connect_timeout(Duration::from_secs(15))
+            .tcp_keepalive(Duration::from_secs(60))
+            .build()
+            .unwrap_or_default();
+        let auth_client = Client::builder()
+            .timeout(Duration::from_secs(10))
             .build()
             .unwrap_or_default();
+        let mut parser = SseParser::default();
+        let mut attempts = 0u32;
 
         loop {
             tracing::info!("Connecting to PocketBase realtime...");
+            self.update_status(|s| {
+                s.state = ConnectionState::Connecting;
+                s.next_retry = None;
+            });
+
+            parser.restart();
+            let result = self
+                .connect_and_subscribe(&client, token.as_deref(), &mut parser)
+                .await;
+
+            // A connection that got as far as subscribing starts the
+            // backoff over.
+            if self.status.lock().unwrap_or_else(|e| e.into_inner()).state
+                == ConnectionState::Connected
+            {
+                attempts = 0;
+            }
+            attempts += 1;
+            let (kind, message) = match &result {
+                Ok(()) => (ErrorKind::Network, "stream closed".to_string()),
+                Err(e) => (ErrorKind::of(e), format!("{:#}", e)),
+            };
+            if kind == ErrorKind::Auth {
+                token = self.refresh_token(&auth_client).await;
+            }
 
-            match self.connect_and_subscribe(&client, token.as_deref()).await {
-                Ok(()) => tracing::warn!("Realtime SSE stream closed, reconnecting in 5s..."),
-                Err(e) => {
-                    tracing::error!("Realtime connection error: {}, reconnecting in 5s...", e)
-                }
+            let delay = reconnect_delay(parser.retry.unwrap_or(RECONNECT_DELAY), attempts);
+            match result {
+                Ok(()) => tracing::warn!(
+                    "Realtime SSE stream closed, reconnecting in {:.1?} (attempt {})...",
+                    delay,
+                    attempts
+                ),
+                Err(_) => tracing::error!(
+                    "Realtime connection error ({}): {}, reconnecting in {:.1?} (attempt {})...",
+                    kind.as_str(),
+                    message,
+                    delay,
+                    attempts
+                ),
             }
+            self.update_status(|s| {
+                s.state = ConnectionState::Waiting;
+                s.attempts = attempts;
+                s.last_error = Some((kind, message));
+                s.next_retry = Some(Instant::now() + delay);
+            });
 
             let _ = self.event_tx.send(RealtimeEvent::Disconnected).await;
-            sleep(Duration::from_secs(5)).await;
+            sleep(delay).await;
         }
     }
 
-    async fn connect_and_subscribe(&self, client: &Client, token: Option<&str>) -> Result<()> {
+    async fn connect_and_subscribe(
+        &self,
+        client: &Client,
+        token: Option<&str>,
+        parser: &mut SseParser,
+    ) -> Result<()> {
         let url = format!("{}/api/realtime", self.pb_url);
 
         let mut req = client.get(&url).header("Accept", "text/event-stream");
         if let Some(t) = token {
             req = req.bearer_auth(t);
         }
+        if !parser.last_event_id.is_empty() {
+            req = req.header("Last-Event-ID", &parser.last_event_id);
+        }
 
         let response = req
             .send()
             .await
-            .context("Failed to connect to SSE endpoint")?;
-        if !response.status().is_success() {
-            anyhow::bail!("SSE connection failed: {}", response.status());
-        }
+            .context("Failed to connect to SSE endpoint")?
+            .error_for_status()
+            .context("SSE connection failed")?;
 
         let mut stream = response.bytes_stream();
-        let mut buffer = String::new();
         let mut subscribed = false;
 
-        while let Some(chunk) = stream.next().await {
+        loop {
+            let next = tokio::time::timeout(self.idle_timeout, stream.next())
+                .await
+                .with_context(|| {
+                    format!("No data on the SSE stream for {:?}", self.idle_timeout)
+                })?;
+            let Some(chunk) = next else { break };
             let chunk = chunk.context("SSE stream read error")?;
-            buffer.push_str(&String::from_utf8_lossy(&chunk));
-
-            // SSE events are separated by blank lines (\n\n)
-            while let Some(pos) = buffer.find("\n\n") {
-                let block = buffer[..pos].to_string();
-                buffer = buffer[pos + 2..].to_string();
-
-                if block.trim().is_empty() {
-                    continue;
-                }
-
-                let ev = parse_sse_block(&block);
 
+            for ev in parser.feed(&chunk) {
                 if ev.event_type == "PB_CONNECT" {
                     let json: serde_json::Value = serde_json::from_str(&ev.data)
                         .context("Failed to parse PB_CONNECT payload")?;
@@ -179,22 +491,27 @@ impl RealtimeManager {
                     // POST the subscription
                     let sub_url = format!("{}/api/realtime", self.pb_url);
                     let subscriptions = self.build_subscriptions();
-                    let mut sub_req = client.post(&sub_url).json(&serde_json::json!({
-                        "clientId": client_id,
-                        "subscriptions": subscriptions,
-                    }));
+                    let mut sub_req = client.post(&sub_url).timeout(Duration::from_secs(15)).json(
+                        &serde_json::json!({
+                            "clientId": client_id,
+                            "subscriptions": subscriptions,
+                        }),
+                    );
                     if let Some(t) = token {
                         sub_req = sub_req.bearer_auth(t);
                     }
-                    let sub_res = sub_req
+                    sub_req
                         .send()
                         .await
-                        .context("Failed to POST subscription")?;
-                    if !sub_res.status().is_success() {
-                        anyhow::bail!("Subscription POST failed: {}", sub_res.status());
-                    }
+                        .context("Failed to POST subscription")?
+                        .error_for_status()
+                        .context("Subscription POST failed")?;
 
                     subscribed = true;
+                    self.update_status(|s| {
+                        s.state = ConnectionState::Connected;
+                        s.attempts = 0;
+                    });
                     let _ = self.event_tx.send(RealtimeEvent::Connected).await;
                     let _ = self.event_tx.send(RealtimeEvent::RefreshNeeded).await;
                     tracing::info!("Realtime connected and subscribed to: {:?}", subscriptions);
@@ -207,9 +524,42 @@ impl RealtimeManager {
         Ok(())
     }
 
-    fn parse_inbox_event(&self, data: &str) -> RealtimeEvent {
+    /// Handle a `device_inbox` message: a new record is a command.
+    async fn handle_inbox_event(&self, data: &str) {
         let msg: serde_json::Value = serde_json::from_str(data).unwrap_or_default();
+        // The backend updates a command's record to store its ack, and the
+        // cleanup cron deletes it later; neither is a new command.
+        if msg.get("action").and_then(|v| v.as_str()) != Some("create") {
+            return;
+        }
         let record = msg.get("record").cloned().unwrap_or_default();
+        let event = self.parse_inbox_event(&record);
+
+        // Bulk upload progress is chatty, and nothing waits on it; keep it
+        // out of the seen-commands set too.
+        if matches!(event, RealtimeEvent::BulkUploadProgress { .. }) {
+            let _ = self.event_tx.send(event).await;
+            return;
+        }
+        let Some(id) = record.get("id").and_then(|v| v.as_str()) else {
+            let _ = self.event_tx.send(event).await;
+            return;
+        };
+        if !self.acks.first_sight(id) {
+            tracing::debug!("Ignoring replayed command {}", id);
+            return;
+        }
+        self.acks.send(id, AckStatus::Received, None, None);
+        let _ = self
+            .event_tx
+            .send(RealtimeEvent::Command {
+                id: id.to_string(),
+                command: Box::new(event),
+            })
+            .await;
+    }
+
+    fn parse_inbox_event(&self, record: &serde_json::Value) -> RealtimeEvent {
         let cmd_type = record.get("type").and_then(|v| v.as_str()).unwrap_or("");
         let payload = record.get("payload").cloned().unwrap_or_default();
 
@@ -225,6 +575,40 @@ impl RealtimeManager {
                     .min(300),
             },
             "resume" => RealtimeEvent::RemoteResume,
+            "show" => RealtimeEvent::RemoteShow {
+                media_id: payload
+                    .get("mediaId")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or_default()
+                    .to_string(),
+                hold_secs: payload
+                    .get("holdSecs")
+                    .and_then(|v| v.as_u64())
+                    .filter(|&s| s > 0)
+                    .map(|s| s.min(MAX_SHOW_HOLD_SECS)),
+            },
+            "display-on" | "display-off" => RealtimeEvent::RemoteDisplay {
+                on: cmd_type == "display-on",
+                secs: payload
+                    .get("secs")
+                    .and_then(|v| v.as_u64())
+                    .filter(|&s| s > 0)
+                    .map(|s| s.min(MAX_DISPLAY_OVERRIDE_SECS)),
+            },
+            "brightness" => RealtimeEvent::RemoteBrightness {
+                shade: payload
+                    .get("brightness")
+                    .and_then(|v| v.as_f64())
+                    .map(|brightness| {
+                        let warmth = payload.get("warmth").and_then(|v| v.as_f64());
+                        Shade::from_percent(brightness as f32, warmth.unwrap_or(0.0) as f32)
+                    }),
+                secs: payload
+                    .get("secs")
+                    .and_then(|v| v.as_u64())
+                    .filter(|&s| s > 0)
+                    .map(|s| s.min(MAX_BRIGHTNESS_OVERRIDE_SECS)),
+            },
             "tag-filter" => {
                 let tags = payload
                     .get("tags")
@@ -243,6 +627,24 @@ impl RealtimeManager {
                 RealtimeEvent::RemoteTagFilter { tags, mode }
             }
             "tag-filter-clear" => RealtimeEvent::RemoteTagFilterClear,
+            "pin" | "unpin" => {
+                let mut media_ids: Vec<String> = payload
+                    .get("mediaIds")
+                    .and_then(|v| v.as_array())
+                    .map(|arr| {
+                        arr.iter()
+                            .filter_map(|v| v.as_str().map(|s| s.to_string()))
+                            .collect()
+                    })
+                    .unwrap_or_default();
+                if let Some(id) = payload.get("mediaId").and_then(|v| v.as_str()) {
+                    media_ids.push(id.to_string());
+                }
+                RealtimeEvent::RemotePin {
+                    media_ids,
+                    pinned: cmd_type == "pin",
+                }
+            }
             "bulk-upload-start" => RealtimeEvent::BulkUploadStart,
             "bulk-upload-progress" => {
                 let done = payload.get("done").and_then(|v| v.as_u64()).unwrap_or(0) as u32;
@@ -276,8 +678,7 @@ impl RealtimeManager {
         }
 
         if ev.event_type.starts_with("device_inbox") {
-            let event = self.parse_inbox_event(&ev.data);
-            let _ = self.event_tx.send(event).await;
+            self.handle_inbox_event(&ev.data).await;
             return;
         }
 
@@ -343,15 +744,165 @@ impl RealtimeManager {
     }
 }
 
+/// Start the realtime task. With `device_id` and `device_api_key`, a
+/// rejected token is replaced through device-auth; connection state is
+/// published to `status`, and inbox commands are acknowledged through
+/// `acks`.
 pub fn spawn_realtime(
     pb_url: String,
     device_id: Option<String>,
+    device_api_key: Option<String>,
+    idle_timeout: Duration,
     token: Option<String>,
+    status: Arc<Mutex<RealtimeStatus>>,
+    acks: Arc<CommandAcks>,
 ) -> mpsc::Receiver<RealtimeEvent> {
     let (tx, rx) = mpsc::channel(100);
-    let manager = RealtimeManager::new(pb_url, device_id, tx);
+    let manager = RealtimeManager::new(
+        pb_url,
+        device_id,
+        device_api_key,
+        idle_timeout,
+        status,
+        acks,
+        tx,
+    );
     tokio::spawn(async move {
         manager.run(token).await;
     });
     rx
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Feed `chunks` to `parser` in order; returns (event type, data) for
+    /// each event they complete.
+    fn feed_all(parser: &mut SseParser, chunks: &[&[u8]]) -> Vec<(String, String)> {
+        chunks
+            .iter()
+            .flat_map(|chunk| parser.feed(chunk))
+            .map(|ev| (ev.event_type, ev.data))
+            .collect()
+    }
+
+    fn event(event_type: &str, data: &str) -> (String, String) {
+        (event_type.to_string(), data.to_string())
+    }
+
+    #[test]
+    fn line_endings() {
+        for stream in [
+            &b"event: a\ndata: 1\n\n"[..],
+            b"event: a\r\ndata: 1\r\n\r\n",
+            b"event: a\rdata: 1\r\r",
+        ] {
+            let mut parser = SseParser::default();
+            assert_eq!(feed_all(&mut parser, &[stream]), [event("a", "1")]);
+        }
+    }
+
+    #[test]
+    fn crlf_split_across_chunks() {
+        let mut parser = SseParser::default();
+        let events = feed_all(&mut parser, &[b"data: 1\r", b"\ndata: 2\r", b"\n\r", b"\n"]);
+        assert_eq!(events, [event("message", "1\n2")]);
+    }
+
+    #[test]
+    fn multi_line_data() {
+        let mut parser = SseParser::default();
+        let events = feed_all(
+            &mut parser,
+            &[b"data: one\ndata:two\ndata\ndata:  four\n\n"],
+        );
+        assert_eq!(events, [event("message", "one\ntwo\n\n four")]);
+    }
+
+    #[test]
+    fn utf8_split_across_chunks() {
+        let stream = "data: {\"title\":\"Ľadový štít 🏔\"}\n\n".as_bytes();
+        // Split inside the two-byte "Ľ" and again inside the four-byte emoji.
+        let first = stream.iter().position(|&b| b >= 0x80).unwrap() + 1;
+        let second = stream.len() - 5;
+        let mut parser = SseParser::default();
+        let events = feed_all(
+            &mut parser,
+            &[&stream[..first], &stream[first..second], &stream[second..]],
+        );
+        assert_eq!(events, [event("message", "{\"title\":\"Ľadový štít 🏔\"}")]);
+    }
+
+    #[test]
+    fn id_and_retry() {
+        let mut parser = SseParser::default();
+        let events = feed_all(
+            &mut parser,
+            &[b"id: 42\nretry: 2500\nretry: soon\ndata: x\n\nid: bad\0id\ndata: y\n\n"],
+        );
+        assert_eq!(events, [event("message", "x"), event("message", "y")]);
+        assert_eq!(parser.last_event_id, "42");
+        assert_eq!(parser.retry, Some(Duration::from_millis(2500)));
+
+        // Both survive a reconnect.
+        parser.restart();
+        assert_eq!(parser.last_event_id, "42");
+        assert_eq!(parser.retry, Some(Duration::from_millis(2500)));
+
+        // An empty id resets it.
+        feed_all(&mut parser, &[b"id\n\n"]);
+        assert_eq!(parser.last_event_id, "");
+    }
+
+    #[test]
+    fn comments_and_keepalives() {
+        let mut parser = SseParser::default();
+        let events = feed_all(
+            &mut parser,
+            &[
+                b": keepalive\n\n",
+                b":\n",
+                b"data: x\n: inside an event\n\n",
+            ],
+        );
+        assert_eq!(events, [event("message", "x")]);
+    }
+
+    #[test]
+    fn event_without_data_is_dropped() {
+        let mut parser = SseParser::default();
+        let events = feed_all(&mut parser, &[b"event: PB_CONNECT\n\ndata: x\n\n"]);
+        // The event type doesn't carry over to the next event either.
+        assert_eq!(events, [event("message", "x")]);
+    }
+
+    #[test]
+    fn leading_bom() {
+        let mut parser = SseParser::default();
+        let events = feed_all(&mut parser, &[b"\xef\xbb", b"\xbfevent: a\ndata: 1\n\n"]);
+        assert_eq!(events, [event("a", "1")]);
+
+        // Only at the very start of the stream.
+        let events = feed_all(&mut parser, &[b"\xef\xbb\xbfdata: 2\n\n"]);
+        assert!(events.is_empty());
+
+        // A reconnect starts a new stream.
+        parser.restart();
+        let events = feed_all(&mut parser, &[b"\xef\xbb\xbfdata: 3\n\n"]);
+        assert_eq!(events, [event("message", "3")]);
+    }
+
+    #[test]
+    fn unterminated_final_event_is_discarded() {
+        let mut parser = SseParser::default();
+        let events = feed_all(&mut parser, &[b"data: 1\n\ndata: 2\n", b"data: 3"]);
+        assert_eq!(events, [event("message", "1")]);
+
+        // The stream ends here; reconnecting must not resurrect the partial
+        // event.
+        parser.restart();
+        let events = feed_all(&mut parser, &[b"data: 4\n\n"]);
+        assert_eq!(events, [event("message", "4")]);
+    }
+}
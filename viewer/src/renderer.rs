@@ -2,16 +2,20 @@
 //!
 //! Handles window creation, texture management, and rendering with transitions.
 
+use crate::brightness::Shade;
+use crate::cache::{DownloadProgress, FailureRecord};
+use crate::decode::{DecodeTarget, DecodedImage};
+use crate::realtime::{ConnectionState, RealtimeStatus};
+use crate::sync::SyncProgress;
 use anyhow::{Context, Result};
 use sdl2::event::Event;
 use sdl2::keyboard::Keycode;
 use sdl2::pixels::{Color, PixelFormatEnum};
-use sdl2::rect::Rect;
+use sdl2::rect::{FRect, Rect};
 use sdl2::render::{Canvas, Texture, TextureCreator};
 use sdl2::ttf::Sdl2TtfContext;
 use sdl2::video::{Window, WindowContext};
-use std::collections::VecDeque;
-use std::path::Path;
+use std::collections::{HashMap, VecDeque};
 use std::time::{Duration, Instant};
 
 /// Transition types supported by the renderer.
@@ -23,20 +27,187 @@ pub enum Transition {
     Fade,
     /// Crossfade between current and next image.
     Crossfade,
+    /// Incoming image slides in over the outgoing one, moving in `Direction`.
+    Slide(Direction),
+    /// Outgoing image is pushed out as the incoming one moves in behind it.
+    Push(Direction),
+    /// A straight edge sweeps across in `Direction`, revealing the incoming image.
+    Wipe(Direction),
+    /// A circle grows from the centre, revealing the incoming image.
+    RadialWipe,
+}
+
+/// Direction of travel for slide, push and wipe transitions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    Left,
+    Right,
+    Up,
+    Down,
+}
+
+impl Direction {
+    fn from_str(s: &str) -> Option<Self> {
+        match s {
+            "left" => Some(Direction::Left),
+            "right" => Some(Direction::Right),
+            "up" => Some(Direction::Up),
+            "down" => Some(Direction::Down),
+            _ => None,
+        }
+    }
+
+    /// Unit vector of travel in screen coordinates.
+    fn unit(self) -> (f32, f32) {
+        match self {
+            Direction::Left => (-1.0, 0.0),
+            Direction::Right => (1.0, 0.0),
+            Direction::Up => (0.0, -1.0),
+            Direction::Down => (0.0, 1.0),
+        }
+    }
 }
 
 impl Transition {
+    /// Parse an admin transition string: `cut`, `fade`, `crossfade`,
+    /// `slide-<dir>`, `push-<dir>`, `wipe-<dir>` or `wipe-radial`, where
+    /// `<dir>` is `left`, `right`, `up` or `down`. Unknown values cut.
     pub fn from_str(s: &str) -> Self {
-        match s.to_lowercase().as_str() {
-            "fade" => Transition::Fade,
-            "crossfade" => Transition::Crossfade,
+        let s = s.trim().to_lowercase().replace('_', "-");
+        match s.as_str() {
+            "fade" => return Transition::Fade,
+            "crossfade" => return Transition::Crossfade,
+            "wipe-radial" | "radial-wipe" | "radial" => return Transition::RadialWipe,
+            _ => {}
+        }
+        let (kind, dir) = s.split_once('-').unwrap_or((s.as_str(), ""));
+        match (kind, Direction::from_str(dir)) {
+            ("slide", Some(d)) => Transition::Slide(d),
+            ("push", Some(d)) => Transition::Push(d),
+            ("wipe", Some(d)) => Transition::Wipe(d),
             _ => Transition::Cut,
         }
     }
+
+    /// Whether outgoing and incoming are on screen together for the whole
+    /// duration, swapping at the end. Only Fade goes out and back in through
+    /// black in two halves.
+    fn is_single_phase(self) -> bool {
+        !matches!(self, Transition::Cut | Transition::Fade)
+    }
+
+    /// Whether the transition is drawn by compositing two offscreen layers
+    /// (anything that moves or masks rather than blends).
+    fn is_layered(self) -> bool {
+        matches!(
+            self,
+            Transition::Slide(_)
+                | Transition::Push(_)
+                | Transition::Wipe(_)
+                | Transition::RadialWipe
+        )
+    }
+
+    /// Easing used when none is configured: blends stay linear, as they
+    /// always have been; anything that moves starts and settles gently.
+    pub fn default_easing(self) -> Easing {
+        if self.is_layered() {
+            Easing::EaseInOut
+        } else {
+            Easing::Linear
+        }
+    }
 }
 
-/// Layout kind discriminant — used for history tracking (no positional info).
+/// A weighted set of transitions, one picked per slide.
+#[derive(Debug, Clone, Default)]
+pub struct TransitionSet {
+    entries: Vec<(Transition, u32)>,
+}
+
+impl TransitionSet {
+    /// Build a set from `(transition, weight)` pairs; zero weights are dropped.
+    pub fn new(entries: Vec<(Transition, u32)>) -> Self {
+        Self {
+            entries: entries.into_iter().filter(|&(_, w)| w > 0).collect(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Weighted random pick. `last` is excluded whenever anything else is
+    /// available, so the same effect never plays twice in a row.
+    fn pick(&self, last: Option<Transition>) -> Option<Transition> {
+        use rand::Rng;
+        let candidates: Vec<(Transition, u32)> = match last {
+            Some(last) if self.entries.iter().any(|&(t, _)| t != last) => self
+                .entries
+                .iter()
+                .copied()
+                .filter(|&(t, _)| t != last)
+                .collect(),
+            _ => self.entries.clone(),
+        };
+        let total: u32 = candidates.iter().map(|&(_, w)| w).sum();
+        if total == 0 {
+            return None;
+        }
+        let mut roll = rand::thread_rng().gen_range(0..total);
+        for (t, w) in candidates {
+            if roll < w {
+                return Some(t);
+            }
+            roll -= w;
+        }
+        None
+    }
+}
+
+/// Easing curve applied to transition progress.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Easing {
+    Linear,
+    EaseIn,
+    EaseOut,
+    EaseInOut,
+}
+
+impl Easing {
+    /// Parse `linear`, `ease-in`, `ease-out` or `ease-in-out`; `None` for
+    /// anything else (including empty), meaning "the transition's default".
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s.trim().to_lowercase().replace('_', "-").as_str() {
+            "linear" => Some(Easing::Linear),
+            "ease-in" => Some(Easing::EaseIn),
+            "ease-out" => Some(Easing::EaseOut),
+            "ease-in-out" => Some(Easing::EaseInOut),
+            _ => None,
+        }
+    }
+
+    /// Map linear progress `t` (0–1) onto the curve. Cubic, so 0 and 1 map
+    /// exactly onto themselves.
+    pub fn apply(self, t: f32) -> f32 {
+        let t = t.clamp(0.0, 1.0);
+        match self {
+            Easing::Linear => t,
+            Easing::EaseIn => t * t * t,
+            Easing::EaseOut => 1.0 - (1.0 - t).powi(3),
+            Easing::EaseInOut => {
+                if t < 0.5 {
+                    4.0 * t * t * t
+                } else {
+                    1.0 - (-2.0 * t + 2.0).powi(3) / 2.0
+                }
+            }
+        }
+    }
+}
+
+/// Layout kind discriminant — used for history tracking (no positional info).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum SlideLayoutKind {
     Single,
     DualPortrait,
@@ -44,6 +215,27 @@ pub enum SlideLayoutKind {
     QuadLandscape,
     DualSquare,
     SquarePortrait,
+    DualLandscape,
+    TriplePortrait,
+    Mosaic,
+}
+
+impl SlideLayoutKind {
+    /// Parse the snake_case name used in device config (e.g. `quad_landscape`).
+    pub fn from_config_name(s: &str) -> Option<Self> {
+        match s.trim().to_lowercase().replace('-', "_").as_str() {
+            "single" => Some(SlideLayoutKind::Single),
+            "dual_portrait" => Some(SlideLayoutKind::DualPortrait),
+            "portrait_dual_landscape" => Some(SlideLayoutKind::PortraitDualLandscape),
+            "quad_landscape" => Some(SlideLayoutKind::QuadLandscape),
+            "dual_square" => Some(SlideLayoutKind::DualSquare),
+            "square_portrait" => Some(SlideLayoutKind::SquarePortrait),
+            "dual_landscape" => Some(SlideLayoutKind::DualLandscape),
+            "triple_portrait" => Some(SlideLayoutKind::TriplePortrait),
+            "mosaic" => Some(SlideLayoutKind::Mosaic),
+            _ => None,
+        }
+    }
 }
 
 /// Active slide layout — captures kind and which slot goes on which side.
@@ -61,6 +253,12 @@ pub enum SlideLayout {
     DualSquare { flipped: bool },
     /// One square image + one portrait image. `square_right`: square on the right.
     SquarePortrait { square_right: bool },
+    /// Two landscape images stacked, for portrait screens. `flipped`: slot 0 at the bottom.
+    DualLandscape { flipped: bool },
+    /// Three portrait images side by side, for ultrawide screens.
+    TriplePortrait,
+    /// A grid of equal tiles, filled row by row, each image cropped to its tile.
+    Mosaic { columns: u8, rows: u8 },
 }
 
 impl SlideLayout {
@@ -72,6 +270,9 @@ impl SlideLayout {
             SlideLayout::QuadLandscape { .. } => SlideLayoutKind::QuadLandscape,
             SlideLayout::DualSquare { .. } => SlideLayoutKind::DualSquare,
             SlideLayout::SquarePortrait { .. } => SlideLayoutKind::SquarePortrait,
+            SlideLayout::DualLandscape { .. } => SlideLayoutKind::DualLandscape,
+            SlideLayout::TriplePortrait => SlideLayoutKind::TriplePortrait,
+            SlideLayout::Mosaic { .. } => SlideLayoutKind::Mosaic,
         }
     }
 
@@ -82,8 +283,11 @@ impl SlideLayout {
             SlideLayout::DualPortrait { .. } => 2,
             SlideLayout::DualSquare { .. } => 2,
             SlideLayout::SquarePortrait { .. } => 2,
+            SlideLayout::DualLandscape { .. } => 2,
             SlideLayout::PortraitDualLandscape { .. } => 3,
+            SlideLayout::TriplePortrait => 3,
             SlideLayout::QuadLandscape { .. } => 4,
+            SlideLayout::Mosaic { columns, rows } => *columns as usize * *rows as usize,
         }
     }
 
@@ -111,6 +315,8 @@ pub struct MediaTextures<'a> {
     pub blur: Option<Texture<'a>>,
     /// Original dimensions of the display image.
     pub display_size: Option<(u32, u32)>,
+    /// Pan-and-zoom path for still images; drawn only when Ken Burns mode is on.
+    pub ken_burns: Option<KenBurns>,
 }
 
 impl<'a> MediaTextures<'a> {
@@ -119,10 +325,91 @@ impl<'a> MediaTextures<'a> {
             display: None,
             blur: None,
             display_size: None,
+            ken_burns: None,
         }
     }
 }
 
+/// Ken Burns pan-and-zoom path for one still image.
+///
+/// The zoom is relative to the image's resting framing in its panel, so the
+/// moving window always stays inside the image and the panel is always fully
+/// covered. The clock starts the first time the image is drawn, which for the
+/// incoming slide is the start of its transition.
+#[derive(Debug, Clone, Copy)]
+pub struct KenBurns {
+    zoom_from: f32,
+    zoom_to: f32,
+    /// Normalized (0–1) image points the window is centred on at each end.
+    focus_from: (f32, f32),
+    focus_to: (f32, f32),
+    started: Option<Instant>,
+}
+
+impl KenBurns {
+    /// Pick a random path: a gentle zoom in or out drifting between two
+    /// points near the middle of the image.
+    pub fn random() -> Self {
+        use rand::Rng;
+        let mut rng = rand::thread_rng();
+        let near = rng.gen_range(1.04..1.10);
+        let far = near + rng.gen_range(0.08..0.14);
+        let (zoom_from, zoom_to) = if rng.gen_bool(0.5) {
+            (near, far)
+        } else {
+            (far, near)
+        };
+        let mut point = || (rng.gen_range(0.3..0.7), rng.gen_range(0.3..0.7));
+        Self {
+            zoom_from,
+            zoom_to,
+            focus_from: point(),
+            focus_to: point(),
+            started: None,
+        }
+    }
+
+    /// Eased progress along the path, starting the clock on the first call.
+    fn progress(&mut self, duration: Duration) -> f32 {
+        let started = *self.started.get_or_insert_with(Instant::now);
+        if duration.is_zero() {
+            return 1.0;
+        }
+        let t = (started.elapsed().as_secs_f32() / duration.as_secs_f32()).min(1.0);
+        // Sine ease-in-out: no jolt as the slide starts or settles.
+        0.5 - 0.5 * (std::f32::consts::PI * t).cos()
+    }
+
+    /// Where to draw the whole `tex_w`×`tex_h` texture at progress `t` so that
+    /// the zoomed window over `base` (the resting source window, in texture
+    /// coordinates) lands exactly on `dst`. Float coordinates keep slow pans
+    /// from stepping a whole source pixel at a time.
+    fn frame(&self, t: f32, tex_w: u32, tex_h: u32, base: Rect, dst: Rect) -> FRect {
+        let lerp = |a: f32, b: f32| a + (b - a) * t;
+        let zoom = lerp(self.zoom_from, self.zoom_to);
+        let (tw, th) = (tex_w as f32, tex_h as f32);
+        let win_w = base.width() as f32 / zoom;
+        let win_h = base.height() as f32 / zoom;
+        let centre = |focus: f32, win: f32, size: f32| {
+            if win >= size {
+                size / 2.0
+            } else {
+                (focus * size).clamp(win / 2.0, size - win / 2.0)
+            }
+        };
+        let cx = centre(lerp(self.focus_from.0, self.focus_to.0), win_w, tw);
+        let cy = centre(lerp(self.focus_from.1, self.focus_to.1), win_h, th);
+        let scale_x = dst.width() as f32 / win_w;
+        let scale_y = dst.height() as f32 / win_h;
+        FRect::new(
+            dst.x() as f32 - (cx - win_w / 2.0) * scale_x,
+            dst.y() as f32 - (cy - win_h / 2.0) * scale_y,
+            tw * scale_x,
+            th * scale_y,
+        )
+    }
+}
+
 /// Specific user actions from keyboard/remote input.
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum UserAction {
@@ -161,6 +448,21 @@ pub struct OverlayInfo {
     pub cache_max: u64,
     /// Cache item count.
     pub cache_items: usize,
+    /// Bytes held by pinned media, and the budget for them.
+    pub pinned_used: u64,
+    pub pinned_budget: u64,
+    /// Free bytes on the cache's filesystem.
+    pub disk_free: Option<u64>,
+    /// Free space is below the cache's floor.
+    pub disk_pressure: bool,
+    /// Downloads in flight, oldest first.
+    pub downloads: Vec<DownloadProgress>,
+    /// Background sync state.
+    pub sync: SyncProgress,
+    /// Failed downloads awaiting retry, most recent first.
+    pub failures: Vec<FailureRecord>,
+    /// Realtime connection attempts and the last error.
+    pub realtime: RealtimeStatus,
     /// Whether current media is a video.
     pub is_video: bool,
     /// Whether video is paused.
@@ -171,6 +473,10 @@ pub struct OverlayInfo {
     pub video_duration: Option<f32>,
     /// Video position in seconds.
     pub video_position: Option<f32>,
+    /// Brightness and warmth the frame is drawn at.
+    pub shade: Shade,
+    /// The shade comes from a `brightness` command rather than the schedule.
+    pub shade_overridden: bool,
 }
 
 /// Information shown in the media info overlay (title, description, tags, etc.).
@@ -190,17 +496,109 @@ pub struct MediaInfoOverlay {
     pub iso: Option<String>,
 }
 
-/// The main renderer struct.
-pub struct Renderer<'ttf> {
+/// Clock face settings (admin-controlled via device config).
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ClockStyle {
+    /// Show the time as 24-hour "HH:MM" instead of 12-hour.
+    pub use_24h: bool,
+    /// strftime-style pattern for a date line under the time; `None` hides it.
+    pub date_format: Option<String>,
+    /// Zone (e.g. "Europe/Bratislava") to show the time in instead of the
+    /// system zone.
+    pub timezone: Option<chrono_tz::Tz>,
+}
+
+/// Clock glyphs cached across frames. Rendered once in white per minute and
+/// tinted with color/alpha modulation at draw time, so neither the per-frame
+/// shadow passes nor a contrast flip ever go back through SDL_ttf.
+struct ClockFace<'tc> {
+    /// Wall-clock minute (Unix time / 60) these textures were rendered for.
+    minute: i64,
+    time: Texture<'tc>,
+    date: Option<Texture<'tc>>,
+}
+
+/// Offscreen copies of the outgoing and incoming frames, composited by the
+/// moving and masking transitions (slide, push, wipes).
+struct TransitionLayers<'tc> {
+    outgoing: Texture<'tc>,
+    incoming: Texture<'tc>,
+}
+
+/// How far the picture is turned, clockwise, to suit how the panel is
+/// mounted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Rotation {
+    #[default]
+    None,
+    Cw90,
+    Cw180,
+    Cw270,
+}
+
+impl Rotation {
+    /// Parse the `rotation` setting: 0, 90, 180 or 270.
+    pub fn from_degrees(degrees: u32) -> Option<Self> {
+        match degrees {
+            0 => Some(Rotation::None),
+            90 => Some(Rotation::Cw90),
+            180 => Some(Rotation::Cw180),
+            270 => Some(Rotation::Cw270),
+            _ => None,
+        }
+    }
+
+    fn degrees(self) -> f64 {
+        match self {
+            Rotation::None => 0.0,
+            Rotation::Cw90 => 90.0,
+            Rotation::Cw180 => 180.0,
+            Rotation::Cw270 => 270.0,
+        }
+    }
+
+    /// Whether the picture's width runs along the panel's height.
+    fn is_sideways(self) -> bool {
+        matches!(self, Rotation::Cw90 | Rotation::Cw270)
+    }
+}
+
+/// The main renderer struct. Borrows the TTF context and the texture
+/// creator its cached textures come from, both owned by the caller.
+pub struct Renderer<'ttf, 'tc> {
     canvas: Canvas<Window>,
     event_pump: sdl2::EventPump,
+    /// Size of the picture as drawn — the panel's size, with width and
+    /// height swapped when rotated sideways.
     screen_width: u32,
     screen_height: u32,
+    rotation: Rotation,
+    /// With a rotation, everything is drawn into this texture and turned
+    /// onto the window in `present`.
+    frame: Option<Texture<'tc>>,
+    /// Transition for the slide change in flight (or the last one).
     transition_type: Transition,
+    /// Configured single transition, used when no transition set applies.
+    base_transition: Transition,
+    /// Weighted per-slide transition choice; empty means `base_transition`.
+    transition_set: TransitionSet,
+    /// Per-layout overrides of `transition_set`, keyed by the incoming layout.
+    layout_transition_sets: HashMap<SlideLayoutKind, TransitionSet>,
     transition_duration_ms: u32,
     transition_state: TransitionState,
     transition_start: Option<Instant>,
+    /// Configured easing; `None` uses the transition's own default.
+    transition_easing: Option<Easing>,
+    /// Created on first use by a layered transition.
+    transition_layers: Option<TransitionLayers<'tc>>,
+    /// Whether the GPU renderer can draw into textures. Without it layered
+    /// transitions degrade to a crossfade.
+    render_targets: bool,
     pub blur_background: bool,
+    /// Slowly pan and zoom still images across their display time.
+    ken_burns: bool,
+    /// How long one Ken Burns path takes — a slide's full on-screen time.
+    ken_burns_duration: Duration,
     pub current_layout: SlideLayout,
     /// Layout for the incoming slide during a transition — `None` when idle.
     /// `current_layout` continues to describe the outgoing panels (still on
@@ -210,19 +608,57 @@ pub struct Renderer<'ttf> {
     show_clock: bool,
     clock_offset_x: i32,
     clock_offset_y: i32,
+    clock_style: ClockStyle,
+    clock_face: Option<ClockFace<'tc>>,
+    /// Dimming and warm shift applied over the whole frame in `present`.
+    shade: Shade,
+    /// Whether the clock currently draws dark-on-light over a bright background.
+    clock_dark_text: bool,
+    /// Set when the pixels under the clock changed wholesale (end of a
+    /// transition), so contrast is re-sampled right away instead of on the timer.
+    clock_luma_dirty: bool,
+    clock_luma_sampled_at: Option<Instant>,
+    /// Texture creator for textures kept on `self` (clock face, transition
+    /// layers, rotation frame).
+    overlay_tc: &'tc TextureCreator<WindowContext>,
     max_texture_dim: u32,
     font_overlay: Option<sdl2::ttf::Font<'ttf, 'static>>,
     font_info: Option<sdl2::ttf::Font<'ttf, 'static>>,
     font_clock: Option<sdl2::ttf::Font<'ttf, 'static>>,
+    font_clock_date: Option<sdl2::ttf::Font<'ttf, 'static>>,
     font_discovery_small: Option<sdl2::ttf::Font<'ttf, 'static>>,
     font_discovery_label: Option<sdl2::ttf::Font<'ttf, 'static>>,
     font_discovery_pin: Option<sdl2::ttf::Font<'ttf, 'static>>,
     /// Rects of the last rendered image(s), used to align info overlays.
-    last_image_rects: [Option<Rect>; 4],
+    last_image_rects: Vec<Rect>,
+    /// Burn-in protection: how far (in pixels) the whole picture wanders
+    /// from its resting position; 0 keeps it still.
+    orbit_radius: u32,
+    /// Offset the viewport is currently shifted by.
+    orbit_offset: (i32, i32),
+    orbit_start: Instant,
 }
 
 const CLOCK_FONT_BYTES: &[u8] = include_bytes!("../assets/fonts/BodoniModa-Regular.ttf");
 
+/// Opacity of the quiet-hours clock.
+const QUIET_CLOCK_ALPHA: u8 = 90;
+
+/// How long the picture rests at each orbit position before moving a pixel.
+const ORBIT_STEP: Duration = Duration::from_secs(60);
+
+/// How long each colour of the refresh pattern stays up.
+const REFRESH_PATTERN_STEP: Duration = Duration::from_secs(2);
+
+/// Colours the refresh pattern cycles through, exercising every subpixel.
+const REFRESH_PATTERN_COLORS: [(u8, u8, u8); 5] = [
+    (255, 0, 0),
+    (0, 255, 0),
+    (0, 0, 255),
+    (255, 255, 255),
+    (0, 0, 0),
+];
+
 /// System fonts used for utility overlays and discovery text.
 const FONT_PATHS: &[&str] = &[
     "/usr/share/fonts/truetype/dejavu/DejaVuSansMono.ttf",
@@ -234,10 +670,13 @@ const FONT_PATHS: &[&str] = &[
     "C:\\Windows\\Fonts\\arial.ttf",
 ];
 
-impl<'ttf> Renderer<'ttf> {
+impl<'ttf, 'tc> Renderer<'ttf, 'tc> {
     #[allow(clippy::too_many_arguments)]
+    /// `texture_creator` is filled with the creator for textures the
+    /// renderer keeps; the caller holds it so it outlives them.
     pub fn new(
         ttf_context: &'ttf Sdl2TtfContext,
+        texture_creator: &'tc mut Option<TextureCreator<WindowContext>>,
         transition: Transition,
         transition_duration_ms: u32,
         fullscreen: bool,
@@ -245,7 +684,9 @@ impl<'ttf> Renderer<'ttf> {
         show_clock: bool,
         clock_offset_x: i32,
         clock_offset_y: i32,
+        clock_style: ClockStyle,
         initial_layout: SlideLayout,
+        rotation: Rotation,
     ) -> Result<Self> {
         let sdl_context = sdl2::init().map_err(|e| anyhow::anyhow!("SDL init failed: {}", e))?;
 
@@ -294,6 +735,15 @@ impl<'ttf> Renderer<'ttf> {
         // Enable blending for overlay
         canvas.set_blend_mode(sdl2::render::BlendMode::Blend);
 
+        let overlay_tc: &'tc TextureCreator<WindowContext> =
+            texture_creator.insert(canvas.texture_creator());
+        let render_targets = canvas.render_target_supported();
+        if !render_targets {
+            tracing::warn!(
+                "Renderer has no render-target support; slide/push/wipe transitions will crossfade"
+            );
+        }
+
         // Cap decoded image dimensions to what the GPU can actually turn into a
         // texture. Oversized textures (e.g. modern phone photos with no
         // server-side downscale) silently fail create_texture_streaming on some
@@ -336,8 +786,32 @@ impl<'ttf> Renderer<'ttf> {
                 physical_height
             );
         }
-        let screen_width = physical_width;
-        let screen_height = physical_height;
+        let rotation = if rotation != Rotation::None && !render_targets {
+            tracing::warn!("Renderer has no render-target support; ignoring rotation");
+            Rotation::None
+        } else {
+            rotation
+        };
+        let (screen_width, screen_height) = if rotation.is_sideways() {
+            (physical_height, physical_width)
+        } else {
+            (physical_width, physical_height)
+        };
+        let frame = if rotation == Rotation::None {
+            None
+        } else {
+            tracing::info!(
+                "Rotating the picture {}° ({}x{} drawn)",
+                rotation.degrees(),
+                screen_width,
+                screen_height
+            );
+            let mut tex = overlay_tc
+                .create_texture_target(None, screen_width, screen_height)
+                .context("Failed to create rotation target")?;
+            tex.set_blend_mode(sdl2::render::BlendMode::None);
+            Some(tex)
+        };
 
         let event_pump = sdl_context
             .event_pump()
@@ -349,6 +823,9 @@ impl<'ttf> Renderer<'ttf> {
         let font_clock = sdl2::rwops::RWops::from_bytes(CLOCK_FONT_BYTES)
             .ok()
             .and_then(|rwops| ttf_context.load_font_from_rwops(rwops, 76).ok());
+        let font_clock_date = sdl2::rwops::RWops::from_bytes(CLOCK_FONT_BYTES)
+            .ok()
+            .and_then(|rwops| ttf_context.load_font_from_rwops(rwops, 28).ok());
 
         let (
             font_info,
@@ -368,30 +845,54 @@ impl<'ttf> Renderer<'ttf> {
             (None, None, None, None, None)
         };
 
-        Ok(Self {
+        let mut renderer = Self {
             canvas,
             event_pump,
             screen_width,
             screen_height,
+            rotation,
+            frame,
             transition_type: transition,
+            base_transition: transition,
+            transition_set: TransitionSet::default(),
+            layout_transition_sets: HashMap::new(),
             transition_duration_ms,
             transition_state: TransitionState::Idle,
             transition_start: None,
+            transition_easing: None,
+            transition_layers: None,
+            render_targets,
             blur_background,
+            ken_burns: false,
+            ken_burns_duration: Duration::ZERO,
             current_layout: initial_layout,
             incoming_layout: None,
             show_clock,
             clock_offset_x,
             clock_offset_y,
+            clock_style,
+            clock_face: None,
+            shade: Shade::FULL,
+            clock_dark_text: false,
+            clock_luma_dirty: true,
+            clock_luma_sampled_at: None,
+            overlay_tc,
             max_texture_dim,
             font_overlay,
             font_info,
             font_clock,
+            font_clock_date,
             font_discovery_small,
             font_discovery_label,
             font_discovery_pin,
-            last_image_rects: [None; 4],
-        })
+            last_image_rects: Vec::new(),
+            orbit_radius: 0,
+            orbit_offset: (0, 0),
+            orbit_start: Instant::now(),
+        };
+        let target = renderer.screen_target();
+        renderer.set_render_target(target)?;
+        Ok(renderer)
     }
 
     /// Try to find a system font path.
@@ -408,138 +909,163 @@ impl<'ttf> Renderer<'ttf> {
         None
     }
 
-    /// Get the texture creator for loading textures.
-    pub fn texture_creator(&self) -> TextureCreator<WindowContext> {
-        self.canvas.texture_creator()
+    /// Turn Ken Burns mode on or off. `interval_ms` is the slide interval;
+    /// each path spans the whole time an image is on screen, transitions in
+    /// and out included, so the motion never visibly stops.
+    pub fn set_ken_burns(&mut self, enabled: bool, interval_ms: u64) {
+        self.ken_burns = enabled;
+        self.ken_burns_duration =
+            Duration::from_millis(interval_ms + 2 * self.transition_duration_ms as u64);
     }
 
-    /// Load an image from a file path into a texture.
-    pub fn load_texture_from_file<'a>(
-        &self,
-        texture_creator: &'a TextureCreator<WindowContext>,
-        path: &Path,
-    ) -> Result<(Texture<'a>, u32, u32)> {
-        let img = image::ImageReader::open(path)
-            .context("Failed to open image")?
-            .with_guessed_format()
-            .context("Failed to guess image format")?
-            .decode()
-            .context("Failed to open image")?;
-        let mut rgba = img.to_rgba8();
-        let (orig_width, orig_height) = rgba.dimensions();
-
-        // Downscale if the decoded image exceeds what the GPU can texture —
-        // otherwise create_texture_streaming fails below and only the (always
-        // screen-capped) blur background ends up visible.
-        if orig_width.max(orig_height) > self.max_texture_dim {
-            use image::imageops::{resize, FilterType};
-            let scale = self.max_texture_dim as f32 / orig_width.max(orig_height) as f32;
-            let new_width = ((orig_width as f32 * scale).round() as u32).max(1);
-            let new_height = ((orig_height as f32 * scale).round() as u32).max(1);
-            tracing::info!(
-                "Downscaling oversized image {} from {}x{} to {}x{} (max texture dim {})",
-                path.display(),
-                orig_width,
-                orig_height,
-                new_width,
-                new_height,
-                self.max_texture_dim
-            );
-            rgba = resize(&rgba, new_width, new_height, FilterType::Triangle);
+    /// Set the weighted transition sets picked from per slide: `default` for
+    /// any layout without its own entry in `per_layout`. Empty sets fall back
+    /// to the single configured transition.
+    pub fn set_transition_sets(
+        &mut self,
+        default: TransitionSet,
+        per_layout: HashMap<SlideLayoutKind, TransitionSet>,
+    ) {
+        self.transition_set = default;
+        self.layout_transition_sets = per_layout;
+    }
+
+    /// Choose the transition for the next slide change into `incoming` and
+    /// make it current. Call before `start_transition`; a `Cut` result means
+    /// the caller should swap immediately instead.
+    pub fn choose_transition(&mut self, incoming: SlideLayout) -> Transition {
+        let set = self
+            .layout_transition_sets
+            .get(&incoming.kind())
+            .filter(|s| !s.is_empty())
+            .unwrap_or(&self.transition_set);
+        self.transition_type = set
+            .pick(Some(self.transition_type))
+            .unwrap_or(self.base_transition);
+        if self.transition_type == Transition::Cut {
+            // A skip can land mid-transition; a cut has nothing to finish.
+            self.transition_state = TransitionState::Idle;
+            self.transition_start = None;
+            self.incoming_layout = None;
         }
-        let (width, height) = rgba.dimensions();
+        self.transition_type
+    }
 
-        let mut texture = texture_creator
-            .create_texture_streaming(PixelFormatEnum::ABGR8888, width, height)
-            .context("Failed to create texture")?;
+    /// Change the configured transition and its duration, from the next
+    /// slide change on.
+    pub fn set_transition(&mut self, transition: Transition, duration_ms: u32) {
+        self.base_transition = transition;
+        self.transition_duration_ms = duration_ms;
+    }
 
-        texture
-            .with_lock(None, |buffer: &mut [u8], pitch: usize| {
-                for y in 0..height as usize {
-                    for x in 0..width as usize {
-                        let pixel = rgba.get_pixel(x as u32, y as u32);
-                        let offset = y * pitch + x * 4;
-                        buffer[offset] = pixel[0]; // R
-                        buffer[offset + 1] = pixel[1]; // G
-                        buffer[offset + 2] = pixel[2]; // B
-                        buffer[offset + 3] = pixel[3]; // A
-                    }
-                }
-            })
-            .map_err(|e| anyhow::anyhow!("Failed to update texture: {}", e))?;
+    /// Change the clock settings; the face is redrawn on the next frame.
+    pub fn set_clock(&mut self, show: bool, offset_x: i32, offset_y: i32, style: ClockStyle) {
+        self.show_clock = show;
+        self.clock_offset_x = offset_x;
+        self.clock_offset_y = offset_y;
+        self.clock_style = style;
+        self.clock_face = None;
+        self.clock_luma_dirty = true;
+    }
 
-        // Enable alpha blending for transitions
-        texture.set_blend_mode(sdl2::render::BlendMode::Blend);
+    /// Override the easing curve for transitions; `None` restores each
+    /// transition's default (see `Transition::default_easing`).
+    pub fn set_transition_easing(&mut self, easing: Option<Easing>) {
+        self.transition_easing = easing;
+    }
 
-        Ok((texture, width, height))
+    /// Set how many pixels the picture may drift to spread screen wear;
+    /// 0 turns it off.
+    pub fn set_orbit(&mut self, radius: u32) {
+        self.orbit_radius = radius;
     }
 
-    /// Generate a proper Gaussian-blurred background texture from an image file.
-    ///
-    /// This runs entirely on the CPU using `image::imageops::blur` (true Gaussian),
-    /// producing a clean, smooth result with zero visible pattern or banding.
-    /// The result is sized to the full screen so the GPU just blits it directly.
+    /// Shift the viewport to the orbit position for the current time.
     ///
-    /// Performance on Raspberry Pi 4 (estimated):
-    ///   - Downscale to 480x270:  ~3ms
-    ///   - Gaussian blur σ=8:     ~4ms
-    ///   - Upscale to 1080p:      ~9ms
-    ///     Total: ~16ms, called once per image transition — imperceptible to the user.
-    pub fn generate_blur_texture<'a>(
-        &self,
-        texture_creator: &'a TextureCreator<WindowContext>,
-        path: &Path,
-    ) -> Result<Texture<'a>> {
-        use image::imageops::{self, FilterType};
-
-        let img = image::ImageReader::open(path)
-            .context("Failed to open image for blur")?
-            .with_guessed_format()
-            .context("Failed to guess image format for blur")?
-            .decode()
-            .context("Failed to open image for blur")?;
-
-        // Step 1: Downscale to quarter-resolution to strip fine detail while
-        // retaining enough colour structure for a faithful background.
-        let small = imageops::resize(&img, 480, 270, FilterType::Triangle);
-
-        // Step 2: Real Gaussian blur — σ=8 at 480x270 gives the same visual
-        // softness as σ=12 at 256x144 with better colour fidelity.
-        let blurred = imageops::blur(&small, 8.0);
-
-        // Step 3: Upscale to full screen resolution with Triangle (bilinear) filter.
-        // Since the blurred image has no detail, the upscale is perfectly smooth.
-        let final_img = imageops::resize(
-            &blurred,
+    /// Everything drawn to the screen (media, letterbox, clock, overlays)
+    /// moves with it; a strip as wide as the shift stays black along the
+    /// opposite edge.
+    fn apply_orbit(&mut self) {
+        let step = self.orbit_start.elapsed().as_secs() / ORBIT_STEP.as_secs();
+        let offset = Self::orbit_position(self.orbit_radius as i32, step);
+        if offset == self.orbit_offset {
+            return;
+        }
+        self.orbit_offset = offset;
+        self.canvas.set_viewport(Rect::new(
+            offset.0,
+            offset.1,
             self.screen_width,
             self.screen_height,
-            FilterType::Triangle,
-        );
+        ));
+    }
 
-        // Step 4: Upload to a streaming SDL2 texture
-        let rgba = image::DynamicImage::ImageRgba8(final_img).to_rgba8();
-        let (w, h) = rgba.dimensions();
+    /// Position `step` of a walk over every offset within `radius`, row by
+    /// row in alternating directions and then back, so each move is one
+    /// pixel and every position is visited equally often.
+    fn orbit_position(radius: i32, step: u64) -> (i32, i32) {
+        if radius <= 0 {
+            return (0, 0);
+        }
+        let side = 2 * radius as u64 + 1;
+        let cells = side * side;
+        let k = step % (2 * cells - 2);
+        let i = if k < cells { k } else { 2 * cells - 2 - k };
+        let row = i / side;
+        let col = if row.is_multiple_of(2) {
+            i % side
+        } else {
+            side - 1 - i % side
+        };
+        (col as i32 - radius, row as i32 - radius)
+    }
 
+    /// Get the texture creator for loading textures.
+    pub fn texture_creator(&self) -> TextureCreator<WindowContext> {
+        self.canvas.texture_creator()
+    }
+
+    /// Sizing the decode pool should produce images at for this screen.
+    pub fn decode_target(&self) -> DecodeTarget {
+        DecodeTarget {
+            screen_width: self.screen_width,
+            screen_height: self.screen_height,
+            max_texture_dim: self.max_texture_dim,
+        }
+    }
+
+    /// Upload decoded RGBA pixels into a new texture. Decoding and resizing
+    /// already happened on a decode worker; this is a row-by-row bulk copy
+    /// (a single one when the texture pitch matches the row length).
+    pub fn upload_image<'a>(
+        &self,
+        texture_creator: &'a TextureCreator<WindowContext>,
+        image: &DecodedImage,
+    ) -> Result<Texture<'a>> {
+        let (width, height) = (image.width, image.height);
         let mut texture = texture_creator
-            .create_texture_streaming(PixelFormatEnum::ABGR8888, w, h)
-            .context("Failed to create blur texture")?;
+            .create_texture_streaming(PixelFormatEnum::ABGR8888, width, height)
+            .context("Failed to create texture")?;
 
+        let row_len = width as usize * 4;
         texture
             .with_lock(None, |buffer: &mut [u8], pitch: usize| {
-                for y in 0..h as usize {
-                    for x in 0..w as usize {
-                        let pixel = rgba.get_pixel(x as u32, y as u32);
-                        let offset = y * pitch + x * 4;
-                        buffer[offset] = pixel[0]; // R
-                        buffer[offset + 1] = pixel[1]; // G
-                        buffer[offset + 2] = pixel[2]; // B
-                        buffer[offset + 3] = pixel[3]; // A
+                if pitch == row_len {
+                    buffer[..image.pixels.len()].copy_from_slice(&image.pixels);
+                } else {
+                    for (dst, src) in buffer
+                        .chunks_mut(pitch)
+                        .zip(image.pixels.chunks_exact(row_len))
+                    {
+                        dst[..row_len].copy_from_slice(src);
                     }
                 }
             })
-            .map_err(|e| anyhow::anyhow!("Failed to upload blur texture: {}", e))?;
+            .map_err(|e| anyhow::anyhow!("Failed to update texture: {}", e))?;
 
+        // Enable alpha blending for transitions
         texture.set_blend_mode(sdl2::render::BlendMode::Blend);
+
         Ok(texture)
     }
 
@@ -709,7 +1235,8 @@ impl<'ttf> Renderer<'ttf> {
 
     /// Compute display rects for all panels in the given layout.
     ///
-    /// Returns up to 4 Rects corresponding to slots 0–(image_count-1).
+    /// Returns one Rect per slot, 0–(image_count-1); `sizes` holds the slots'
+    /// image sizes, where known (missing trailing slots count as unknown).
     /// For layouts with a side option (portrait_right, flipped, etc.) the slots
     /// are always ordered by playlist position; the side flag only controls which
     /// screen column they land in.
@@ -717,9 +1244,12 @@ impl<'ttf> Renderer<'ttf> {
         layout: SlideLayout,
         sw: u32,
         sh: u32,
-        sizes: &[Option<(u32, u32)>; 4],
+        sizes: &[Option<(u32, u32)>],
     ) -> Vec<(Rect, Option<Rect>)> {
         const GAP: u32 = 8;
+        let sizes: Vec<Option<(u32, u32)>> = (0..layout.image_count())
+            .map(|i| sizes.get(i).copied().flatten())
+            .collect();
         let natural_w_at_h = |size: Option<(u32, u32)>, h: u32| -> u32 {
             let (w, ih) = size.unwrap_or((h, h));
             if ih == 0 {
@@ -807,6 +1337,102 @@ impl<'ttf> Renderer<'ttf> {
                 }
             }
 
+            SlideLayout::DualLandscape { flipped } => {
+                // Two images stacked, both at full screen width (DualPortrait on its side)
+                let natural_h_at_w = |size: Option<(u32, u32)>| -> u32 {
+                    let (w, h) = size.unwrap_or((sw, sw));
+                    if w == 0 {
+                        sw
+                    } else {
+                        ((sw as f64 * h as f64 / w as f64).round() as u32).max(1)
+                    }
+                };
+                let nh0 = natural_h_at_w(sizes[0]);
+                let nh1 = natural_h_at_w(sizes[1]);
+                let total_natural = nh0 + GAP + nh1;
+                let (dh0, dh1, dw) = if total_natural <= sh {
+                    (nh0, nh1, sw)
+                } else {
+                    let scale = sh as f64 / total_natural as f64;
+                    (
+                        (nh0 as f64 * scale).round() as u32,
+                        (nh1 as f64 * scale).round() as u32,
+                        (sw as f64 * scale).round() as u32,
+                    )
+                };
+                let pair_h = dh0 + GAP + dh1;
+                let x0 = ((sw.saturating_sub(dw)) / 2) as i32;
+                let y0 = ((sh.saturating_sub(pair_h)) / 2) as i32;
+                if flipped {
+                    vec![
+                        (Rect::new(x0, y0 + dh1 as i32 + GAP as i32, dw, dh0), None),
+                        (Rect::new(x0, y0, dw, dh1), None),
+                    ]
+                } else {
+                    vec![
+                        (Rect::new(x0, y0, dw, dh0), None),
+                        (Rect::new(x0, y0 + dh0 as i32 + GAP as i32, dw, dh1), None),
+                    ]
+                }
+            }
+
+            SlideLayout::TriplePortrait => {
+                // Three images side by side at full screen height, scaled down together
+                // when they don't fit the width (DualPortrait with a third column)
+                let natural: Vec<u32> = sizes.iter().map(|&s| natural_w_at_h(s, sh)).collect();
+                let total_natural: u32 = natural.iter().sum();
+                let available = sw.saturating_sub(2 * GAP);
+                let scale = if total_natural <= available {
+                    1.0
+                } else {
+                    available as f64 / total_natural as f64
+                };
+                let dh = ((sh as f64 * scale).round() as u32).max(1);
+                let widths: Vec<u32> = natural
+                    .iter()
+                    .map(|&w| ((w as f64 * scale).round() as u32).max(1))
+                    .collect();
+                let row_w = widths.iter().sum::<u32>() + 2 * GAP;
+                let mut x = ((sw.saturating_sub(row_w)) / 2) as i32;
+                let y0 = ((sh.saturating_sub(dh)) / 2) as i32;
+                widths
+                    .iter()
+                    .map(|&w| {
+                        let r = Rect::new(x, y0, w, dh);
+                        x += (w + GAP) as i32;
+                        (r, None)
+                    })
+                    .collect()
+            }
+
+            SlideLayout::Mosaic { columns, rows } => {
+                // Equal tiles over the whole screen, filled row by row. Each image covers
+                // its tile, cropped as needed, or sits centred in it when that would crop
+                // too much.
+                const MAX_SCALE: f32 = 1.5;
+                const MAX_CROP: f32 = 0.35;
+                let (columns, rows) = (columns.max(1) as u32, rows.max(1) as u32);
+                let tile_w = (sw.saturating_sub(GAP * (columns - 1)) / columns).max(1);
+                let tile_h = (sh.saturating_sub(GAP * (rows - 1)) / rows).max(1);
+                // Centre the grid on what the division leaves over
+                let x0 = (sw.saturating_sub(tile_w * columns + GAP * (columns - 1)) / 2) as i32;
+                let y0 = (sh.saturating_sub(tile_h * rows + GAP * (rows - 1)) / 2) as i32;
+                sizes
+                    .iter()
+                    .enumerate()
+                    .map(|(i, &size)| {
+                        let (col, row) = (i as u32 % columns, i as u32 / columns);
+                        let bx = x0 + (col * (tile_w + GAP)) as i32;
+                        let by = y0 + (row * (tile_h + GAP)) as i32;
+                        let (iw, ih) = size.unwrap_or((tile_w, tile_h));
+                        match Self::fit_cover(iw, ih, bx, by, tile_w, tile_h, MAX_SCALE, MAX_CROP) {
+                            Some((dst, src)) => (dst, Some(src)),
+                            None => (Self::fit_in_box(iw, ih, bx, by, tile_w, tile_h), None),
+                        }
+                    })
+                    .collect()
+            }
+
             SlideLayout::PortraitDualLandscape { portrait_right } => {
                 // Slot 0 = portrait, slots 1+2 = two landscapes stacked in the remaining column.
                 // All three panels share the same layout height. After determining that height,
@@ -1079,37 +1705,37 @@ impl<'ttf> Renderer<'ttf> {
         let sw = self.screen_width;
         let sh = self.screen_height;
 
-        let mut sizes: [Option<(u32, u32)>; 4] = [None; 4];
-        for (i, p) in panels.iter().enumerate() {
-            sizes[i] = p.display_size;
-        }
+        let sizes: Vec<Option<(u32, u32)>> = panels.iter().map(|p| p.display_size).collect();
 
-        let rects = Self::compute_panel_rects(layout, sw, sh, &sizes);
+        let mut rects = Self::compute_panel_rects(layout, sw, sh, &sizes);
+        // Slots without textures (a panel that failed to load) are left empty.
+        rects.truncate(panels.len());
         let n = rects.len();
 
         // Record rects for info overlay
-        self.last_image_rects = [None; 4];
-        for (i, (r, _)) in rects.iter().enumerate() {
-            if i < 4 {
-                self.last_image_rects[i] = Some(*r);
-            }
-        }
+        self.last_image_rects = rects.iter().map(|(r, _)| *r).collect();
 
         if n == 1 {
             // Single image — use the standard single-panel background render
+            let p = &mut *panels[0];
+            let (dst, src) = match p.display_size {
+                Some((w, h)) if self.ken_burns && p.ken_burns.is_some() => {
+                    self.ken_burns_single_rect(w, h)
+                }
+                _ => rects[0],
+            };
+            self.last_image_rects[0] = dst;
             if self.blur_background {
-                if let Some(ref mut blur) = panels[0].blur {
+                if let Some(ref mut blur) = p.blur {
                     blur.set_alpha_mod(alpha);
                     self.canvas
                         .copy(blur, None, None)
                         .map_err(|e| anyhow::anyhow!("blur: {}", e))?;
                 }
             }
-            if let Some(ref mut display) = panels[0].display {
+            if let Some(ref mut display) = p.display {
                 display.set_alpha_mod(alpha);
-                self.canvas
-                    .copy(display, None, rects[0].0)
-                    .map_err(|e| anyhow::anyhow!("display: {}", e))?;
+                self.copy_display(display, p.ken_burns.as_mut(), src, dst)?;
             }
             return Ok(());
         }
@@ -1142,7 +1768,12 @@ impl<'ttf> Renderer<'ttf> {
             //
             // seam direction encoding: positive = this panel is to the RIGHT of / BELOW the seam
             //                          negative = this panel is to the LEFT of / ABOVE the seam
-            for i in 1..n {
+            //
+            // Mosaic tiles nearly cover the screen and have seams on every side; blending
+            // each of them would cost far more than it shows, so panel 0's blur stands in
+            // for all of them.
+            let blended = if n > 4 { 1 } else { n };
+            for i in 1..blended {
                 let p = &mut panels[i];
                 if p.blur.is_none() {
                     continue;
@@ -1401,11 +2032,11 @@ impl<'ttf> Renderer<'ttf> {
 
         // Step 2: Render each image clipped to its rect
         for (i, (dst, src)) in rects.iter().enumerate() {
-            if let Some(ref mut display) = panels[i].display {
+            let p = &mut *panels[i];
+            if let Some(ref mut display) = p.display {
                 self.canvas.set_clip_rect(*dst);
                 display.set_alpha_mod(alpha);
-                self.canvas
-                    .copy(display, *src, *dst)
+                self.copy_display(display, p.ken_burns.as_mut(), *src, *dst)
                     .map_err(|e| anyhow::anyhow!("img {}: {}", i, e))?;
             }
         }
@@ -1498,24 +2129,48 @@ impl<'ttf> Renderer<'ttf> {
         panels: &mut [&mut MediaTextures<'a>],
         next_panels: Option<&mut [&mut MediaTextures<'a>]>,
     ) -> Result<()> {
+        // The outgoing panels always use current_layout (unchanged until the
+        // swap point below flips it). The incoming panels use whatever layout
+        // was picked for the next slide, which may have a different shape
+        // (e.g. Single -> DualPortrait) — using the wrong one here is what
+        // caused stale/mismatched panels to flash during a transition.
+        let incoming_layout = self.incoming_layout.unwrap_or(self.current_layout);
+        self.apply_orbit();
+
+        if let Some(progress) = self.layered_progress() {
+            // Multi-panel slides transition panel by panel, so note where
+            // the incoming images land before the outgoing render overwrites
+            // last_image_rects.
+            self.with_layers(|r| {
+                let mut panel_areas = Vec::new();
+                r.begin_layer(true)?;
+                if let Some(np) = next_panels {
+                    r.render_layout_panels(tc, np, incoming_layout, 255)?;
+                    if incoming_layout.is_multi() {
+                        panel_areas = r.last_image_rects.clone();
+                    }
+                }
+                r.begin_layer(false)?;
+                r.render_layout_panels(tc, panels, r.current_layout, 255)?;
+                r.composite_layers(progress, &panel_areas)
+            })?;
+            if self.show_clock {
+                self.render_clock()?;
+            }
+            return Ok(());
+        }
+
         self.canvas.set_draw_color(Color::RGB(0, 0, 0));
         self.canvas.clear();
 
-        let alpha = match (self.transition_type, self.transition_state) {
+        let alpha = match (self.transition_type, self.eased_state()) {
             (Transition::Cut, _) | (_, TransitionState::Idle) => 255u8,
             (_, TransitionState::TransitioningOut { progress }) => ((1.0 - progress) * 255.0) as u8,
             (_, TransitionState::TransitioningIn { progress }) => (progress * 255.0) as u8,
         };
 
-        // The outgoing panels always use current_layout (unchanged until the
-        // swap point below flips it). The incoming panels use whatever layout
-        // was picked for the next slide, which may have a different shape
-        // (e.g. Single -> DualPortrait) — using the wrong one here is what
-        // caused stale/mismatched panels to flash during a transition.
-        let incoming_layout = self.incoming_layout.unwrap_or(self.current_layout);
-
-        if self.transition_type == Transition::Crossfade {
-            if let TransitionState::TransitioningOut { progress } = self.transition_state {
+        if self.blends_under() {
+            if let TransitionState::TransitioningOut { progress } = self.eased_state() {
                 let next_alpha = (progress * 255.0) as u8;
                 if let Some(np) = next_panels {
                     self.render_layout_panels(tc, np, incoming_layout, next_alpha)?;
@@ -1526,7 +2181,7 @@ impl<'ttf> Renderer<'ttf> {
         self.render_layout_panels(tc, panels, self.current_layout, alpha)?;
 
         if self.show_clock {
-            self.render_clock(tc)?;
+            self.render_clock()?;
         }
 
         Ok(())
@@ -1559,8 +2214,9 @@ impl<'ttf> Renderer<'ttf> {
         };
 
         let elapsed = start.elapsed().as_millis() as f32;
-        let is_crossfade = self.transition_type == Transition::Crossfade;
-        // Crossfade uses the full duration as one continuous blend; Fade splits into two halves.
+        let is_crossfade = self.transition_type.is_single_phase();
+        // Crossfade (and every moving/masking transition) uses the full
+        // duration as one continuous pass; Fade splits into two halves.
         let out_duration = if is_crossfade {
             self.transition_duration_ms as f32
         } else {
@@ -1583,6 +2239,7 @@ impl<'ttf> Renderer<'ttf> {
                         // Crossfade is done — swap and go idle immediately.
                         self.transition_state = TransitionState::Idle;
                         self.transition_start = None;
+                        self.clock_luma_dirty = true;
                     } else {
                         self.transition_state = TransitionState::TransitioningIn { progress: 0.0 };
                     }
@@ -1598,6 +2255,7 @@ impl<'ttf> Renderer<'ttf> {
                 if progress >= 1.0 {
                     self.transition_state = TransitionState::Idle;
                     self.transition_start = None;
+                    self.clock_luma_dirty = true;
                 } else {
                     self.transition_state = TransitionState::TransitioningIn { progress };
                 }
@@ -1606,6 +2264,259 @@ impl<'ttf> Renderer<'ttf> {
         }
     }
 
+    /// The transition state with the easing curve applied to its progress.
+    fn eased_state(&self) -> TransitionState {
+        let easing = self
+            .transition_easing
+            .unwrap_or(self.transition_type.default_easing());
+        match self.transition_state {
+            TransitionState::Idle => TransitionState::Idle,
+            TransitionState::TransitioningOut { progress } => TransitionState::TransitioningOut {
+                progress: easing.apply(progress),
+            },
+            TransitionState::TransitioningIn { progress } => TransitionState::TransitioningIn {
+                progress: easing.apply(progress),
+            },
+        }
+    }
+
+    /// Whether the incoming slide is drawn underneath the fading outgoing
+    /// one: crossfade, and layered transitions on hardware without render
+    /// targets.
+    fn blends_under(&self) -> bool {
+        self.transition_type == Transition::Crossfade
+            || (self.transition_type.is_layered() && !self.render_targets)
+    }
+
+    /// Linear progress of a layered transition in flight, if any.
+    fn layered_progress(&self) -> Option<f32> {
+        if !self.transition_type.is_layered() || !self.render_targets {
+            return None;
+        }
+        match self.transition_state {
+            TransitionState::TransitioningOut { progress } => Some(progress),
+            _ => None,
+        }
+    }
+
+    /// Run `draw`, which renders into the transition layers and composites
+    /// them. If it fails part way, the screen is made the render target
+    /// again before the error is returned, so later frames aren't drawn
+    /// offscreen.
+    fn with_layers(&mut self, draw: impl FnOnce(&mut Self) -> Result<()>) -> Result<()> {
+        let result = draw(self);
+        if result.is_err() {
+            if let Err(e) = self.set_render_target(self.screen_target()) {
+                tracing::warn!("Failed to restore the render target: {}", e);
+            }
+        }
+        result
+    }
+
+    /// Point subsequent drawing at one of the transition layers (creating
+    /// them on first use) and clear it to black.
+    fn begin_layer(&mut self, incoming: bool) -> Result<()> {
+        if self.transition_layers.is_none() {
+            let create = || -> Result<Texture<'tc>> {
+                let mut tex = self
+                    .overlay_tc
+                    .create_texture_target(None, self.screen_width, self.screen_height)
+                    .context("Failed to create transition layer")?;
+                tex.set_blend_mode(sdl2::render::BlendMode::None);
+                Ok(tex)
+            };
+            self.transition_layers = Some(TransitionLayers {
+                outgoing: create()?,
+                incoming: create()?,
+            });
+        }
+        let target = self.transition_layers.as_ref().map(|l| {
+            if incoming {
+                l.incoming.raw()
+            } else {
+                l.outgoing.raw()
+            }
+        });
+        let target = target.unwrap_or_else(|| self.screen_target());
+        self.set_render_target(target)?;
+        self.canvas.set_draw_color(Color::RGB(0, 0, 0));
+        self.canvas.clear();
+        Ok(())
+    }
+
+    /// Where a frame is drawn: the rotation target, or the window itself.
+    fn screen_target(&self) -> *mut sdl2::sys::SDL_Texture {
+        self.frame
+            .as_ref()
+            .map_or(std::ptr::null_mut(), |frame| frame.raw())
+    }
+
+    fn set_render_target(&mut self, target: *mut sdl2::sys::SDL_Texture) -> Result<()> {
+        // SAFETY: the sdl2 crate only exposes render targets through a
+        // closure that holds the canvas borrowed, which rules out reusing
+        // the panel renderers on `self`. `target` is null (the window) or a
+        // target texture held by `self` and created for this canvas: a
+        // transition layer or the rotation `frame`, neither of which is
+        // dropped before `self`. Between frames the target is
+        // `screen_target()`: the `frame` with a rotation, else the window.
+        // `with_layers` puts it back after drawing the layers, even on an
+        // error. `present` switches to the window to draw the frame turned
+        // and present it, then makes the `frame` the target again.
+        let rc = unsafe { sdl2::sys::SDL_SetRenderTarget(self.canvas.raw(), target) };
+        if rc != 0 {
+            anyhow::bail!("Failed to set render target: {}", sdl2::get_error());
+        }
+        // SDL resets the viewport when switching targets; the screen keeps
+        // its orbit shift.
+        if target == self.screen_target() && self.orbit_offset != (0, 0) {
+            self.canvas.set_viewport(Rect::new(
+                self.orbit_offset.0,
+                self.orbit_offset.1,
+                self.screen_width,
+                self.screen_height,
+            ));
+        }
+        Ok(())
+    }
+
+    /// Switch back to the window and draw the transition between the two
+    /// layers at linear `progress`.
+    ///
+    /// With `panel_areas` (the incoming multi-panel layout's image rects) the
+    /// background and gaps crossfade while each panel runs the effect on its
+    /// own, staggered in slot order.
+    fn composite_layers(&mut self, progress: f32, panel_areas: &[Rect]) -> Result<()> {
+        /// Delay between consecutive panels starting, as a fraction of the duration.
+        const PANEL_STAGGER: f32 = 0.12;
+
+        self.set_render_target(self.screen_target())?;
+        self.canvas.set_draw_color(Color::RGB(0, 0, 0));
+        self.canvas.clear();
+
+        let easing = self
+            .transition_easing
+            .unwrap_or(self.transition_type.default_easing());
+        let effect = self.transition_type;
+        let screen = Rect::new(0, 0, self.screen_width, self.screen_height);
+        let Some(layers) = self.transition_layers.as_mut() else {
+            return Ok(());
+        };
+
+        if panel_areas.len() <= 1 {
+            Self::composite_area(
+                &mut self.canvas,
+                layers,
+                effect,
+                easing.apply(progress),
+                screen,
+            )?;
+        } else {
+            self.canvas
+                .copy(&layers.outgoing, None, None)
+                .map_err(|e| anyhow::anyhow!("Failed to draw outgoing layer: {}", e))?;
+            layers
+                .incoming
+                .set_blend_mode(sdl2::render::BlendMode::Blend);
+            layers
+                .incoming
+                .set_alpha_mod((easing.apply(progress) * 255.0) as u8);
+            let blended = self.canvas.copy(&layers.incoming, None, None);
+            layers
+                .incoming
+                .set_blend_mode(sdl2::render::BlendMode::None);
+            layers.incoming.set_alpha_mod(255);
+            blended.map_err(|e| anyhow::anyhow!("Failed to draw incoming layer: {}", e))?;
+
+            let span = 1.0 - PANEL_STAGGER * (panel_areas.len() - 1) as f32;
+            for (i, area) in panel_areas.iter().enumerate() {
+                let local = ((progress - PANEL_STAGGER * i as f32) / span).clamp(0.0, 1.0);
+                Self::composite_area(&mut self.canvas, layers, effect, easing.apply(local), *area)?;
+            }
+        }
+        self.canvas.set_clip_rect(None::<Rect>);
+        Ok(())
+    }
+
+    /// Draw `effect` at eased progress `p` within `area`, which is the same
+    /// rect in both layers and on screen.
+    fn composite_area(
+        canvas: &mut Canvas<Window>,
+        layers: &TransitionLayers,
+        effect: Transition,
+        p: f32,
+        area: Rect,
+    ) -> Result<()> {
+        let copy = |canvas: &mut Canvas<Window>, tex: &Texture, src: Rect, dst: Rect| {
+            canvas
+                .copy(tex, src, dst)
+                .map_err(|e| anyhow::anyhow!("Failed to composite transition: {}", e))
+        };
+        // `area` moved by `amount` of its own size along `dir`.
+        let shifted = |dir: Direction, amount: f32| {
+            let (ux, uy) = dir.unit();
+            Rect::new(
+                area.x() + (ux * amount * area.width() as f32).round() as i32,
+                area.y() + (uy * amount * area.height() as f32).round() as i32,
+                area.width(),
+                area.height(),
+            )
+        };
+
+        canvas.set_clip_rect(area);
+        match effect {
+            Transition::Slide(dir) => {
+                copy(canvas, &layers.outgoing, area, area)?;
+                copy(canvas, &layers.incoming, area, shifted(dir, p - 1.0))?;
+            }
+            Transition::Push(dir) => {
+                copy(canvas, &layers.outgoing, area, shifted(dir, p))?;
+                copy(canvas, &layers.incoming, area, shifted(dir, p - 1.0))?;
+            }
+            Transition::Wipe(dir) => {
+                copy(canvas, &layers.outgoing, area, area)?;
+                let rw = (area.width() as f32 * p).round() as u32;
+                let rh = (area.height() as f32 * p).round() as u32;
+                let (x, y, w, h) = match dir {
+                    Direction::Right => (area.x(), area.y(), rw, area.height()),
+                    Direction::Left => (area.right() - rw as i32, area.y(), rw, area.height()),
+                    Direction::Down => (area.x(), area.y(), area.width(), rh),
+                    Direction::Up => (area.x(), area.bottom() - rh as i32, area.width(), rh),
+                };
+                if w > 0 && h > 0 {
+                    let revealed = Rect::new(x, y, w, h);
+                    copy(canvas, &layers.incoming, revealed, revealed)?;
+                }
+            }
+            Transition::RadialWipe => {
+                copy(canvas, &layers.outgoing, area, area)?;
+                // Rects only, so the circle is built from thin horizontal strips.
+                const STRIP: i32 = 4;
+                let cx = area.x() as f32 + area.width() as f32 / 2.0;
+                let cy = area.y() as f32 + area.height() as f32 / 2.0;
+                let radius = p * (area.width() as f32).hypot(area.height() as f32) / 2.0;
+                let mut y = area.y();
+                while y < area.bottom() {
+                    let h = STRIP.min(area.bottom() - y);
+                    let dy = y as f32 + h as f32 / 2.0 - cy;
+                    if dy.abs() < radius {
+                        let half = (radius * radius - dy * dy).sqrt();
+                        let x0 = ((cx - half).floor() as i32).max(area.x());
+                        let x1 = ((cx + half).ceil() as i32).min(area.right());
+                        if x1 > x0 {
+                            let strip = Rect::new(x0, y, (x1 - x0) as u32, h as u32);
+                            copy(canvas, &layers.incoming, strip, strip)?;
+                        }
+                    }
+                    y += STRIP;
+                }
+            }
+            Transition::Cut | Transition::Fade | Transition::Crossfade => {
+                copy(canvas, &layers.incoming, area, area)?;
+            }
+        }
+        Ok(())
+    }
+
     /// Render the current frame with optional transition effects.
     ///
     /// Takes mutable references to properly set alpha modulation on textures
@@ -1616,20 +2527,37 @@ impl<'ttf> Renderer<'ttf> {
         current: &mut MediaTextures<'a>,
         mut next: Option<&mut MediaTextures<'a>>,
     ) -> Result<()> {
+        self.apply_orbit();
+        if let Some(progress) = self.layered_progress() {
+            self.with_layers(|r| {
+                r.begin_layer(true)?;
+                if let Some(next_tex) = next {
+                    r.render_media_textures(texture_creator, next_tex, 255)?;
+                }
+                r.begin_layer(false)?;
+                r.render_media_textures(texture_creator, current, 255)?;
+                r.composite_layers(progress, &[])
+            })?;
+            if self.show_clock {
+                self.render_clock()?;
+            }
+            return Ok(());
+        }
+
         // Clear to black
         self.canvas
             .set_draw_color(sdl2::pixels::Color::RGB(0, 0, 0));
         self.canvas.clear();
 
-        let alpha = match (self.transition_type, self.transition_state) {
+        let alpha = match (self.transition_type, self.eased_state()) {
             (Transition::Cut, _) | (_, TransitionState::Idle) => 255u8,
             (_, TransitionState::TransitioningOut { progress }) => ((1.0 - progress) * 255.0) as u8,
             (_, TransitionState::TransitioningIn { progress }) => (progress * 255.0) as u8,
         };
 
         // For crossfade, we need to render next image underneath first
-        if self.transition_type == Transition::Crossfade {
-            if let TransitionState::TransitioningOut { progress } = self.transition_state {
+        if self.blends_under() {
+            if let TransitionState::TransitioningOut { progress } = self.eased_state() {
                 // Render next image underneath with increasing alpha
                 if let Some(ref mut next_tex) = next {
                     self.render_media_textures(
@@ -1645,7 +2573,7 @@ impl<'ttf> Renderer<'ttf> {
         self.render_media_textures(texture_creator, current, alpha)?;
 
         if self.show_clock {
-            self.render_clock(texture_creator)?;
+            self.render_clock()?;
         }
 
         Ok(())
@@ -1654,41 +2582,228 @@ impl<'ttf> Renderer<'ttf> {
     /// Present the rendered frame to the screen.
     /// Must be called after all overlays have been drawn.
     pub fn present(&mut self) {
+        if !self.shade.is_full() {
+            // Multiply everything drawn so far (media, clock, overlays) by
+            // the shade colour.
+            let (r, g, b) = self.shade.modulation();
+            self.canvas.set_blend_mode(sdl2::render::BlendMode::Mod);
+            self.canvas.set_draw_color(Color::RGB(r, g, b));
+            if let Err(e) = self.canvas.fill_rect(None) {
+                tracing::warn!("Failed to apply dimming: {}", e);
+            }
+            self.canvas.set_blend_mode(sdl2::render::BlendMode::Blend);
+        }
+        if let Err(e) = self.present_rotated() {
+            tracing::warn!("Failed to rotate frame: {}", e);
+        }
         self.canvas.present();
+        if self.frame.is_some() {
+            let target = self.screen_target();
+            if let Err(e) = self.set_render_target(target) {
+                tracing::warn!("{}", e);
+            }
+        }
+    }
+
+    /// With a rotation, switch to the window and draw the frame onto it,
+    /// turned. A quarter turn keeps the frame centred, so it's placed with
+    /// its unrotated size around the window's centre.
+    fn present_rotated(&mut self) -> Result<()> {
+        if self.frame.is_none() {
+            return Ok(());
+        }
+        self.set_render_target(std::ptr::null_mut())?;
+        let (out_w, out_h) = self
+            .canvas
+            .output_size()
+            .map_err(|e| anyhow::anyhow!("Failed to get output size: {}", e))?;
+        self.canvas.set_viewport(None);
+        self.canvas.set_draw_color(Color::RGB(0, 0, 0));
+        self.canvas.clear();
+        let dst = Rect::from_center(
+            ((out_w / 2) as i32, (out_h / 2) as i32),
+            self.screen_width,
+            self.screen_height,
+        );
+        if let Some(frame) = &self.frame {
+            self.canvas
+                .copy_ex(
+                    frame,
+                    None,
+                    dst,
+                    self.rotation.degrees(),
+                    None,
+                    false,
+                    false,
+                )
+                .map_err(|e| anyhow::anyhow!("Failed to copy rotated frame: {}", e))?;
+        }
+        Ok(())
+    }
+
+    /// Set the brightness and warmth frames are drawn at.
+    pub fn set_shade(&mut self, shade: Shade) {
+        self.shade = shade;
     }
 
-    fn render_clock(&mut self, texture_creator: &TextureCreator<WindowContext>) -> Result<()> {
-        let Some(font) = &self.font_clock else {
+    pub fn shade(&self) -> Shade {
+        self.shade
+    }
+
+    /// Draw the quiet-hours screen: black, with a dim clock in the middle
+    /// if `clock` is set. Needs `present()` like any other frame.
+    pub fn render_quiet_screen(&mut self, clock: bool) -> Result<()> {
+        self.apply_orbit();
+        self.canvas.set_draw_color(Color::RGB(0, 0, 0));
+        self.canvas.clear();
+        if !clock || self.font_clock.is_none() {
+            return Ok(());
+        }
+        let minute = Self::current_minute();
+        if self.clock_face.as_ref().map(|f| f.minute) != Some(minute) {
+            self.clock_face = Some(self.build_clock_face(minute)?);
+        }
+        let Some(face) = self.clock_face.as_mut() else {
             return Ok(());
         };
-        let clock_text = Self::format_clock_time();
-        let surface = font
-            .render(&clock_text)
-            .blended(Color::RGBA(255, 255, 255, 200))
-            .map_err(|e| anyhow::anyhow!("Failed to render clock: {}", e))?;
-        let texture = texture_creator
-            .create_texture_from_surface(&surface)
-            .map_err(|e| anyhow::anyhow!("Failed to create clock texture: {}", e))?;
-        let query = texture.query();
+
+        let time_q = face.time.query();
+        let date_q = face.date.as_ref().map(|d| d.query());
+        let date_gap = 8;
+        let block_h = time_q.height + date_q.map_or(0, |q| q.height + date_gap);
+        let center_x = self.screen_width as i32 / 2;
+        let top = (self.screen_height as i32 - block_h as i32) / 2;
+
+        // Warm and faint: readable across a dark room without lighting it.
+        let (fg, alpha) = ((255, 214, 170), QUIET_CLOCK_ALPHA);
+        face.time.set_color_mod(fg.0, fg.1, fg.2);
+        face.time.set_alpha_mod(alpha);
+        self.canvas
+            .copy(
+                &face.time,
+                None,
+                Rect::new(
+                    center_x - time_q.width as i32 / 2,
+                    top,
+                    time_q.width,
+                    time_q.height,
+                ),
+            )
+            .map_err(|e| anyhow::anyhow!("Failed to copy clock: {}", e))?;
+        if let (Some(tex), Some(q)) = (face.date.as_mut(), date_q) {
+            tex.set_color_mod(fg.0, fg.1, fg.2);
+            tex.set_alpha_mod(alpha);
+            self.canvas
+                .copy(
+                    tex,
+                    None,
+                    Rect::new(
+                        center_x - q.width as i32 / 2,
+                        top + (time_q.height + date_gap) as i32,
+                        q.width,
+                        q.height,
+                    ),
+                )
+                .map_err(|e| anyhow::anyhow!("Failed to copy clock date: {}", e))?;
+        }
+        Ok(())
+    }
+
+    /// Draw the burn-in refresh pattern `elapsed` into its run: full-screen
+    /// colours in turn, so every subpixel gets lit for a while.
+    pub fn render_refresh_pattern(&mut self, elapsed: Duration) {
+        let step = (elapsed.as_millis() / REFRESH_PATTERN_STEP.as_millis()) as usize;
+        let (r, g, b) = REFRESH_PATTERN_COLORS[step % REFRESH_PATTERN_COLORS.len()];
+        self.canvas.set_draw_color(Color::RGB(r, g, b));
+        self.canvas.clear();
+    }
+
+    fn render_clock(&mut self) -> Result<()> {
+        if self.font_clock.is_none() {
+            return Ok(());
+        }
+        let minute = Self::current_minute();
+        if self.clock_face.as_ref().map(|f| f.minute) != Some(minute) {
+            self.clock_face = Some(self.build_clock_face(minute)?);
+        }
+        let Some(face) = self.clock_face.as_ref() else {
+            return Ok(());
+        };
+        let time_q = face.time.query();
+        let date_q = face.date.as_ref().map(|d| d.query());
 
         let base_margin = (self.screen_width.min(self.screen_height) as f32 * 0.035).round() as i32;
         // The raw corner margin sat the clock too close to the edge in practice —
         // this is the horizontal inset admins converged on via clockOffsetX before
         // it became the default resting position (clockOffsetX now adjusts from here).
         let default_x_inset = 101;
-        let x = self.screen_width as i32
-            - query.width as i32
-            - base_margin
-            - default_x_inset
-            - self.clock_offset_x;
-        let y = self.screen_height as i32 - query.height as i32 - base_margin - self.clock_offset_y;
-        let dest = Rect::new(x, y, query.width, query.height);
+        let right = self.screen_width as i32 - base_margin - default_x_inset - self.clock_offset_x;
+        let bottom = self.screen_height as i32 - base_margin - self.clock_offset_y;
+
+        // The date line hangs right-aligned under the time; the block as a
+        // whole keeps the time-only resting position's bottom-right corner.
+        let date_gap = 2;
+        let block_w = time_q.width.max(date_q.map_or(0, |q| q.width));
+        let block_h = time_q.height + date_q.map_or(0, |q| q.height + date_gap);
+        let time_rect = Rect::new(
+            right - time_q.width as i32,
+            bottom - block_h as i32,
+            time_q.width,
+            time_q.height,
+        );
+        let date_rect = date_q.map(|q| {
+            Rect::new(
+                right - q.width as i32,
+                bottom - q.height as i32,
+                q.width,
+                q.height,
+            )
+        });
+
+        self.update_clock_contrast(Rect::new(
+            right - block_w as i32,
+            bottom - block_h as i32,
+            block_w,
+            block_h,
+        ));
+
+        // Light text gets the warm dark brown shadow — softer and less harsh
+        // than pure black. Over bright photos the roles flip: dark text with a
+        // pale halo stays legible where white-on-white would vanish.
+        let (fg, fg_alpha, halo) = if self.clock_dark_text {
+            ((34, 24, 16), 220, (255, 248, 236))
+        } else {
+            ((255, 255, 255), 200, (28, 14, 6))
+        };
 
-        // Warm dark brown shadow — softer and less harsh than pure black
-        let shadow_surface = font
-            .render(&clock_text)
-            .blended(Color::RGBA(28, 14, 6, 200))
-            .map_err(|e| anyhow::anyhow!("Failed to render clock shadow: {}", e))?;
+        let Some(face) = self.clock_face.as_mut() else {
+            return Ok(());
+        };
+        Self::draw_clock_line(
+            &mut self.canvas,
+            &mut face.time,
+            time_rect,
+            fg,
+            fg_alpha,
+            halo,
+        )?;
+        if let (Some(tex), Some(rect)) = (face.date.as_mut(), date_rect) {
+            Self::draw_clock_line(&mut self.canvas, tex, rect, fg, fg_alpha, halo)?;
+        }
+
+        Ok(())
+    }
+
+    /// Draw one white clock glyph texture as an 8-way halo plus the text on
+    /// top, tinting via color/alpha modulation.
+    fn draw_clock_line(
+        canvas: &mut Canvas<Window>,
+        texture: &mut Texture,
+        rect: Rect,
+        fg: (u8, u8, u8),
+        fg_alpha: u8,
+        halo: (u8, u8, u8),
+    ) -> Result<()> {
         let offsets: &[(i32, i32, u8)] = &[
             (-2, 0, 60),
             (2, 0, 60),
@@ -1699,53 +2814,196 @@ impl<'ttf> Renderer<'ttf> {
             (-2, 2, 35),
             (2, 2, 35),
         ];
+        texture.set_color_mod(halo.0, halo.1, halo.2);
         for &(dx, dy, a) in offsets {
-            let mut shadow = texture_creator
-                .create_texture_from_surface(&shadow_surface)
-                .map_err(|e| anyhow::anyhow!("Failed to create clock shadow texture: {}", e))?;
-            shadow.set_alpha_mod(a);
-            self.canvas
+            // The halo glyphs used to be rendered at alpha 200 and then
+            // modulated; fold that into the modulation directly.
+            texture.set_alpha_mod((a as u16 * 200 / 255) as u8);
+            canvas
                 .copy(
-                    &shadow,
+                    texture,
                     None,
-                    Rect::new(x + dx, y + dy, query.width, query.height),
+                    Rect::new(rect.x() + dx, rect.y() + dy, rect.width(), rect.height()),
                 )
                 .map_err(|e| anyhow::anyhow!("Failed to copy clock shadow: {}", e))?;
         }
-        self.canvas
-            .copy(&texture, None, dest)
+        texture.set_color_mod(fg.0, fg.1, fg.2);
+        texture.set_alpha_mod(fg_alpha);
+        canvas
+            .copy(texture, None, rect)
             .map_err(|e| anyhow::anyhow!("Failed to copy clock: {}", e))?;
-
         Ok(())
     }
 
-    fn format_clock_time() -> String {
-        #[cfg(unix)]
-        {
-            let mut now: libc::time_t = 0;
-            let mut local: libc::tm = unsafe { std::mem::zeroed() };
-            unsafe {
-                libc::time(&mut now);
-                if libc::localtime_r(&now, &mut local).is_null() {
-                    return "12:00".to_string();
-                }
+    /// Render the clock glyphs for `minute` in opaque white.
+    fn build_clock_face(&self, minute: i64) -> Result<ClockFace<'tc>> {
+        let (time_text, date_text) = Self::format_clock_lines(&self.clock_style);
+        let font = self.font_clock.as_ref().context("Clock font not loaded")?;
+        let time = Self::render_white_text(self.overlay_tc, font, &time_text)?;
+        let date = match (date_text, self.font_clock_date.as_ref()) {
+            (Some(text), Some(font)) if !text.trim().is_empty() => {
+                Some(Self::render_white_text(self.overlay_tc, font, &text)?)
             }
-            let hour_24 = local.tm_hour;
-            let hour_12 = match hour_24 % 12 {
-                0 => 12,
-                hour => hour,
-            };
-            format!("{:02}:{:02}", hour_12, local.tm_min)
+            _ => None,
+        };
+        Ok(ClockFace { minute, time, date })
+    }
+
+    fn render_white_text<'t>(
+        texture_creator: &'t TextureCreator<WindowContext>,
+        font: &sdl2::ttf::Font,
+        text: &str,
+    ) -> Result<Texture<'t>> {
+        let surface = font
+            .render(text)
+            .blended(Color::RGBA(255, 255, 255, 255))
+            .map_err(|e| anyhow::anyhow!("Failed to render clock: {}", e))?;
+        let mut texture = texture_creator
+            .create_texture_from_surface(&surface)
+            .map_err(|e| anyhow::anyhow!("Failed to create clock texture: {}", e))?;
+        texture.set_blend_mode(sdl2::render::BlendMode::Blend);
+        Ok(texture)
+    }
+
+    /// Re-sample the brightness behind the clock and pick light or dark text.
+    ///
+    /// Reads back only the clock's own rect, at most once a second while the
+    /// slide is static (and immediately after a transition lands), so the
+    /// cost stays negligible next to the frame itself.
+    fn update_clock_contrast(&mut self, area: Rect) {
+        const SAMPLE_INTERVAL: Duration = Duration::from_secs(1);
+        if self.is_transitioning() {
+            return;
+        }
+        let due = self.clock_luma_dirty
+            || self
+                .clock_luma_sampled_at
+                .is_none_or(|t| t.elapsed() >= SAMPLE_INTERVAL);
+        if !due {
+            return;
         }
+        self.clock_luma_dirty = false;
+        self.clock_luma_sampled_at = Some(Instant::now());
 
-        #[cfg(not(unix))]
-        {
-            "12:00".to_string()
+        let screen = Rect::new(0, 0, self.screen_width, self.screen_height);
+        let Some(area) = area.intersection(screen) else {
+            return;
+        };
+        let pixels = match self.canvas.read_pixels(area, PixelFormatEnum::ABGR8888) {
+            Ok(p) => p,
+            Err(e) => {
+                tracing::debug!("Clock contrast sample failed: {}", e);
+                return;
+            }
+        };
+        // Every 4th pixel is plenty for an average over a few thousand.
+        let (sum, count) =
+            pixels
+                .chunks_exact(4)
+                .step_by(4)
+                .fold((0.0f32, 0u32), |(sum, n), px| {
+                    let luma =
+                        0.2126 * px[0] as f32 + 0.7152 * px[1] as f32 + 0.0722 * px[2] as f32;
+                    (sum + luma, n + 1)
+                });
+        if count == 0 {
+            return;
         }
+        let luma = sum / count as f32 / 255.0;
+        // Hysteresis so a background hovering near the threshold doesn't
+        // flip the clock back and forth.
+        if self.clock_dark_text {
+            if luma < 0.50 {
+                self.clock_dark_text = false;
+            }
+        } else if luma > 0.62 {
+            self.clock_dark_text = true;
+        }
+    }
+
+    /// Current wall-clock minute (Unix time / 60), keying the cached face.
+    fn current_minute() -> i64 {
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| (d.as_secs() / 60) as i64)
+            .unwrap_or(0)
+    }
+
+    /// Format the time line and, if configured, the date line, in the
+    /// clock's zone.
+    fn format_clock_lines(style: &ClockStyle) -> (String, Option<String>) {
+        let now = chrono::Utc::now();
+        match style.timezone {
+            Some(tz) => Self::format_clock_at(&now.with_timezone(&tz), style),
+            None => Self::format_clock_at(&now.with_timezone(&chrono::Local), style),
+        }
+    }
+
+    fn format_clock_at<Tz>(
+        now: &chrono::DateTime<Tz>,
+        style: &ClockStyle,
+    ) -> (String, Option<String>)
+    where
+        Tz: chrono::TimeZone,
+        Tz::Offset: std::fmt::Display,
+    {
+        use std::fmt::Write;
+        let time = now
+            .format(if style.use_24h { "%H:%M" } else { "%I:%M" })
+            .to_string();
+        // An invalid pattern fails the write rather than panicking.
+        let date = style.date_format.as_deref().and_then(|fmt| {
+            let mut date = String::new();
+            write!(date, "{}", now.format(fmt)).ok().map(|_| date)
+        });
+        (time, date)
+    }
+
+    /// Full-screen framing for a single still in Ken Burns mode: cover the
+    /// screen when that crops little of the image, otherwise keep the
+    /// aspect-fit rect (the blur fills the rest) and zoom within it.
+    fn ken_burns_single_rect(&self, img_w: u32, img_h: u32) -> (Rect, Option<Rect>) {
+        const MAX_COVER_CROP: f32 = 0.26; // 4:3 on 16:9 still covers
+        let (sw, sh) = (self.screen_width, self.screen_height);
+        match Self::fit_cover(img_w, img_h, 0, 0, sw, sh, f32::MAX, MAX_COVER_CROP) {
+            Some((dst, src)) => (dst, Some(src)),
+            None => (self.calculate_aspect_fit(img_w, img_h), None),
+        }
+    }
+
+    /// Copy a display texture into `dst`, sourcing `src` (whole texture if
+    /// `None`), panning and zooming along `motion` when Ken Burns is on.
+    fn copy_display(
+        &mut self,
+        display: &Texture,
+        motion: Option<&mut KenBurns>,
+        src: Option<Rect>,
+        dst: Rect,
+    ) -> Result<()> {
+        let Some(motion) = motion.filter(|_| self.ken_burns) else {
+            return self
+                .canvas
+                .copy(display, src, dst)
+                .map_err(|e| anyhow::anyhow!("Failed to render display: {}", e));
+        };
+        let query = display.query();
+        let base = src.unwrap_or_else(|| Rect::new(0, 0, query.width, query.height));
+        let t = motion.progress(self.ken_burns_duration);
+        let frame = motion.frame(t, query.width, query.height, base, dst);
+        // Callers may already be clipping to this panel; the zoomed frame
+        // overhangs `dst`, so clip to it here and restore afterwards.
+        let previous_clip = self.canvas.clip_rect();
+        self.canvas.set_clip_rect(dst);
+        let result = self
+            .canvas
+            .copy_f(display, None, frame)
+            .map_err(|e| anyhow::anyhow!("Failed to render display: {}", e));
+        self.canvas.set_clip_rect(previous_clip);
+        result
     }
 
     /// Render media textures (blur background + aspect-fit display).
-    /// Blur texture is pre-generated by `generate_blur_texture`; this just blits it.
+    /// Blur texture is pre-rendered (server or decode pool); this just stretches it.
     ///
     /// The blur is drawn only in the letterbox area *outside* the display's
     /// aspect-fit rect, never underneath it. Video's blur is generated once
@@ -1762,9 +3020,15 @@ impl<'ttf> Renderer<'ttf> {
         textures: &mut MediaTextures<'a>,
         alpha: u8,
     ) -> Result<()> {
-        let dest_rect = textures
-            .display_size
-            .map(|(w, h)| self.calculate_aspect_fit(w, h));
+        let ken_burns = self.ken_burns && textures.ken_burns.is_some();
+        let (dest_rect, src_rect) = match textures.display_size {
+            Some((w, h)) if ken_burns => {
+                let (dst, src) = self.ken_burns_single_rect(w, h);
+                (Some(dst), src)
+            }
+            Some((w, h)) => (Some(self.calculate_aspect_fit(w, h)), None),
+            None => (None, None),
+        };
 
         if self.blur_background {
             if let Some(ref mut blur) = textures.blur {
@@ -1791,11 +3055,9 @@ impl<'ttf> Renderer<'ttf> {
         // Render main display image with aspect-fit
         if let Some(ref mut display) = textures.display {
             if let Some(r) = dest_rect {
-                self.last_image_rects = [Some(r), None, None, None];
+                self.last_image_rects = vec![r];
                 display.set_alpha_mod(alpha);
-                self.canvas
-                    .copy(display, None, r)
-                    .map_err(|e| anyhow::anyhow!("Failed to render display: {}", e))?;
+                self.copy_display(display, textures.ken_burns.as_mut(), src_rect, r)?;
             }
         }
 
@@ -1903,7 +3165,7 @@ impl<'ttf> Renderer<'ttf> {
     /// Render the overlay with status information.
     pub fn render_overlay(&mut self, info: &OverlayInfo) -> Result<()> {
         // Semi-transparent background bar at top
-        let bar_height = 60u32;
+        let bar_height = 85u32;
         self.canvas.set_draw_color(Color::RGBA(0, 0, 0, 180));
         self.canvas
             .fill_rect(Rect::new(0, 0, self.screen_width, bar_height))
@@ -1959,10 +3221,46 @@ impl<'ttf> Renderer<'ttf> {
             // Cache info
             let cache_used_mb = info.cache_used as f64 / 1024.0 / 1024.0;
             let cache_max_mb = info.cache_max as f64 / 1024.0 / 1024.0;
-            let cache_text = format!(
+            let mut cache_text = format!(
                 "Cache: {:.1}MB / {:.1}MB ({} items)",
                 cache_used_mb, cache_max_mb, info.cache_items
             );
+            if info.pinned_used > 0 {
+                cache_text.push_str(&format!(
+                    " | Pinned: {:.1}MB / {:.1}MB",
+                    info.pinned_used as f64 / 1024.0 / 1024.0,
+                    info.pinned_budget as f64 / 1024.0 / 1024.0
+                ));
+            }
+            if let Some(free) = info.disk_free {
+                cache_text.push_str(&format!(
+                    " | Disk: {:.1}GB free{}",
+                    free as f64 / 1024.0 / 1024.0 / 1024.0,
+                    if info.disk_pressure { " (LOW)" } else { "" }
+                ));
+            }
+            if let Some(dl) = info.downloads.first() {
+                let mb = |b: u64| b as f64 / 1024.0 / 1024.0;
+                cache_text.push_str(&format!(
+                    " | Downloading {} {}: {:.1}MB",
+                    dl.asset,
+                    dl.media_id,
+                    mb(dl.downloaded)
+                ));
+                if let Some(total) = dl.total.filter(|&t| t > 0) {
+                    cache_text.push_str(&format!(
+                        " / {:.1}MB ({}%)",
+                        mb(total),
+                        dl.downloaded * 100 / total
+                    ));
+                }
+                if dl.resumed_from > 0 {
+                    cache_text.push_str(" (resumed)");
+                }
+                if info.downloads.len() > 1 {
+                    cache_text.push_str(&format!(" (+{} more)", info.downloads.len() - 1));
+                }
+            }
             Self::render_text(
                 &mut self.canvas,
                 font,
@@ -1970,27 +3268,106 @@ impl<'ttf> Renderer<'ttf> {
                 &cache_text,
                 50,
                 35,
+                if info.disk_pressure {
+                    Color::RGB(255, 200, 100)
+                } else {
+                    Color::RGB(200, 200, 200)
+                },
+            )?;
+
+            // Background sync
+            let mut sync_text = format!(
+                "Sync: {} - {}/{} cached",
+                info.sync.phase.as_str(),
+                info.sync.cached,
+                info.sync.total
+            );
+            if info.sync.retrying > 0 {
+                sync_text.push_str(&format!(", {} retrying", info.sync.retrying));
+            }
+            if let Some(f) = info.failures.first() {
+                sync_text.push_str(&format!(
+                    " | Failed: {} (latest: {} {} HTTP {} x{}, retry in {}m)",
+                    info.failures.len(),
+                    f.asset,
+                    f.media_id,
+                    f.status,
+                    f.attempts,
+                    f.retry_in_secs().div_ceil(60)
+                ));
+            }
+            Self::render_text(
+                &mut self.canvas,
+                font,
+                &texture_creator,
+                &sync_text,
+                50,
+                60,
                 Color::RGB(200, 200, 200),
             )?;
 
             // Connection status text (right side)
+            let rt = &info.realtime;
             let conn_text = if info.is_offline {
-                "OFFLINE"
+                "OFFLINE".to_string()
             } else if info.is_connected {
-                "CONNECTED"
+                "CONNECTED".to_string()
+            } else if rt.state == ConnectionState::Waiting {
+                let secs = rt
+                    .next_retry
+                    .map_or(0, |t| t.saturating_duration_since(Instant::now()).as_secs());
+                format!("RETRY #{} IN {}s", rt.attempts, secs)
             } else {
-                "CONNECTING..."
+                "CONNECTING...".to_string()
             };
             let text_width = (conn_text.len() * 12) as i32; // Approximate
             Self::render_text(
                 &mut self.canvas,
                 font,
                 &texture_creator,
-                conn_text,
+                &conn_text,
                 self.screen_width as i32 - text_width - 20,
                 20,
                 indicator_color,
             )?;
+            if let (false, Some((kind, message))) = (info.is_connected, &rt.last_error) {
+                let mut error_text = format!("{}: {}", kind.as_str(), message);
+                if error_text.chars().count() > 48 {
+                    error_text = error_text.chars().take(45).collect::<String>() + "...";
+                }
+                let text_width = (error_text.chars().count() * 12) as i32;
+                Self::render_text(
+                    &mut self.canvas,
+                    font,
+                    &texture_creator,
+                    &error_text,
+                    self.screen_width as i32 - text_width - 20,
+                    45,
+                    Color::RGB(255, 200, 100),
+                )?;
+            }
+            if !info.shade.is_full() || info.shade_overridden {
+                let shade_text = format!(
+                    "BRIGHTNESS {:.0}% WARM {:.0}%{}",
+                    info.shade.brightness * 100.0,
+                    info.shade.warmth * 100.0,
+                    if info.shade_overridden {
+                        " (OVERRIDE)"
+                    } else {
+                        ""
+                    }
+                );
+                let text_width = (shade_text.len() * 12) as i32;
+                Self::render_text(
+                    &mut self.canvas,
+                    font,
+                    &texture_creator,
+                    &shade_text,
+                    self.screen_width as i32 - text_width - 20,
+                    70,
+                    Color::RGB(200, 200, 200),
+                )?;
+            }
         }
 
         // Video progress bar (if playing video)
@@ -2023,18 +3400,19 @@ impl<'ttf> Renderer<'ttf> {
     /// Render info overlays for all panels in the current multi-image layout.
     pub fn render_info_overlay_multi(&mut self, infos: &[MediaInfoOverlay]) -> Result<()> {
         for (i, info) in infos.iter().enumerate() {
-            if i >= 4 {
+            let Some(&rect) = self.last_image_rects.get(i) else {
                 break;
-            }
-            let rect = self.last_image_rects[i]
-                .unwrap_or_else(|| Rect::new(0, 0, self.screen_width, self.screen_height));
+            };
             self.render_info_overlay_in_rect(info, rect)?;
         }
         Ok(())
     }
 
     pub fn render_info_overlay(&mut self, info: &MediaInfoOverlay) -> Result<()> {
-        let rect = self.last_image_rects[0]
+        let rect = self
+            .last_image_rects
+            .first()
+            .copied()
             .unwrap_or_else(|| Rect::new(0, 0, self.screen_width, self.screen_height));
         self.render_info_overlay_in_rect(info, rect)
     }
@@ -2254,13 +3632,13 @@ impl<'ttf> Renderer<'ttf> {
             )?;
         }
 
-        self.canvas.present();
+        self.present();
         Ok(())
     }
 
     /// Shown in place of the slideshow while an admin-triggered bulk upload is
     /// running (see BulkUploadStart/Progress/End handling in main.rs). Unlike
-    /// render_discovery_screen this does NOT call canvas.present() itself —
+    /// render_discovery_screen this does NOT call present() itself —
     /// it's invoked from inside the main render loop, which presents once per
     /// iteration regardless of which screen was drawn.
     pub fn render_bulk_upload_screen(
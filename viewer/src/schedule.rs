@@ -0,0 +1,205 @@
+//! Local time-of-day windows for scheduling work (sync hours, off-peak)
+//! and the display (quiet hours).
+
+use chrono::{Datelike, Duration, Local, Timelike};
+use serde::Deserialize;
+use std::collections::HashMap;
+
+/// Local time-of-day range, e.g. "01:00-06:00". A range that ends before it
+/// starts wraps past midnight; one that ends where it starts is all day.
+#[derive(Debug, Clone, Copy)]
+pub struct TimeWindow {
+    /// Minutes since local midnight; `end` is exclusive.
+    start: u32,
+    end: u32,
+}
+
+impl TimeWindow {
+    /// Parse "HH:MM-HH:MM".
+    pub fn parse(s: &str) -> Option<Self> {
+        let (start, end) = s.split_once('-')?;
+        Some(Self {
+            start: parse_hhmm(start)?,
+            end: parse_hhmm(end)?,
+        })
+    }
+
+    /// Parse an optional setting: empty means no window, and an invalid
+    /// one is logged (naming the setting) and ignored.
+    pub fn from_setting(name: &str, value: &str) -> Option<Self> {
+        let value = value.trim();
+        if value.is_empty() {
+            return None;
+        }
+        let window = Self::parse(value);
+        if window.is_none() {
+            tracing::warn!("Ignoring invalid {} {:?} (want HH:MM-HH:MM)", name, value);
+        }
+        window
+    }
+
+    /// Whether the window runs past midnight.
+    fn wraps(&self) -> bool {
+        self.start > self.end
+    }
+
+    fn contains(&self, minute: u32) -> bool {
+        if self.start == self.end {
+            true
+        } else if self.start < self.end {
+            minute >= self.start && minute < self.end
+        } else {
+            minute >= self.start || minute < self.end
+        }
+    }
+
+    /// Whether the local time is inside the window.
+    pub fn contains_now(&self) -> bool {
+        self.contains(local_day(0).minute)
+    }
+}
+
+/// What the screen shows during quiet hours.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QuietMode {
+    /// Nothing; the display is switched off too if a power backend is set.
+    Blank,
+    /// A dim clock on black.
+    Clock,
+}
+
+impl QuietMode {
+    /// Parse `quiet_mode`: "blank" or "clock"; "off" (or empty) disables
+    /// quiet hours.
+    pub fn from_setting(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "" | "off" => None,
+            "blank" => Some(Self::Blank),
+            "clock" => Some(Self::Clock),
+            other => {
+                tracing::warn!(
+                    "Ignoring unknown quiet_mode {:?} (want off, blank or clock)",
+                    other
+                );
+                None
+            }
+        }
+    }
+}
+
+/// A date whose quiet hours differ from its weekday's, e.g. a holiday.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct QuietException {
+    /// "YYYY-MM-DD", local time.
+    pub date: String,
+    /// Window starting that day, "HH:MM-HH:MM"; empty or "off" for none.
+    #[serde(default)]
+    pub hours: String,
+}
+
+/// Quiet hours for each weekday. A day's window starts that day and may run
+/// past midnight into the next, e.g. Friday's "23:30-08:00" ends Saturday
+/// morning.
+#[derive(Debug, Clone, Default)]
+pub struct QuietSchedule {
+    /// Indexed by weekday, Sunday first.
+    days: [Option<TimeWindow>; 7],
+    /// Windows replacing the weekday's on particular dates.
+    exceptions: HashMap<(i32, u32, u32), Option<TimeWindow>>,
+}
+
+const WEEKDAYS: [&str; 7] = ["sun", "mon", "tue", "wed", "thu", "fri", "sat"];
+
+impl QuietSchedule {
+    /// Build from `quiet_hours` (weekday name to window) and
+    /// `quiet_exceptions`, logging and skipping anything invalid.
+    pub fn from_settings(days: &HashMap<String, String>, exceptions: &[QuietException]) -> Self {
+        let mut schedule = Self::default();
+        for (day, hours) in days {
+            let key = day.trim().to_ascii_lowercase();
+            match WEEKDAYS.iter().position(|d| key.starts_with(d)) {
+                Some(i) => schedule.days[i] = TimeWindow::from_setting("quiet_hours", hours),
+                None => tracing::warn!("Ignoring quiet hours for unknown weekday {:?}", day),
+            }
+        }
+        for exception in exceptions {
+            let Some(date) = parse_date(&exception.date) else {
+                tracing::warn!(
+                    "Ignoring quiet hours exception with bad date {:?}",
+                    exception.date
+                );
+                continue;
+            };
+            let hours = exception.hours.trim();
+            let window = if hours.eq_ignore_ascii_case("off") {
+                None
+            } else {
+                TimeWindow::from_setting("quiet hours exception", hours)
+            };
+            schedule.exceptions.insert(date, window);
+        }
+        schedule
+    }
+
+    fn window_for(&self, day: &LocalDay) -> Option<TimeWindow> {
+        match self.exceptions.get(&day.date) {
+            Some(window) => *window,
+            None => self.days[day.weekday],
+        }
+    }
+
+    /// Whether it's quiet hours now.
+    pub fn is_quiet_now(&self) -> bool {
+        let (today, yesterday) = (local_day(0), local_day(1));
+        let minute = today.minute;
+        let today_quiet = self
+            .window_for(&today)
+            .is_some_and(|w| w.contains(minute) && (!w.wraps() || minute >= w.start));
+        let spill_over = self
+            .window_for(&yesterday)
+            .is_some_and(|w| w.wraps() && minute < w.end);
+        today_quiet || spill_over
+    }
+}
+
+/// Parse "YYYY-MM-DD".
+fn parse_date(s: &str) -> Option<(i32, u32, u32)> {
+    let mut parts = s.trim().splitn(3, '-');
+    let year = parts.next()?.parse().ok()?;
+    let month: u32 = parts.next()?.parse().ok()?;
+    let day: u32 = parts.next()?.parse().ok()?;
+    ((1..=12).contains(&month) && (1..=31).contains(&day)).then_some((year, month, day))
+}
+
+/// Parse "HH:MM" into minutes since midnight.
+pub fn parse_hhmm(s: &str) -> Option<u32> {
+    let (h, m) = s.trim().split_once(':')?;
+    let (h, m): (u32, u32) = (h.parse().ok()?, m.parse().ok()?);
+    (h < 24 && m < 60).then_some(h * 60 + m)
+}
+
+/// A local calendar day and the time of day within it.
+pub struct LocalDay {
+    /// (year, month, day of month)
+    pub date: (i32, u32, u32),
+    /// Days since 1 January, from 0.
+    pub year_day: u32,
+    /// Sunday = 0
+    pub weekday: usize,
+    /// Minutes since local midnight.
+    pub minute: u32,
+    /// Local time's offset from UTC, DST included.
+    pub utc_offset_secs: i64,
+}
+
+/// The local day now, or `days_back` days ago (at this time of day).
+pub fn local_day(days_back: i64) -> LocalDay {
+    let then = Local::now() - Duration::days(days_back);
+    LocalDay {
+        date: (then.year(), then.month(), then.day()),
+        year_day: then.ordinal0(),
+        weekday: then.weekday().num_days_from_sunday() as usize,
+        minute: then.hour() * 60 + then.minute(),
+        utc_offset_secs: then.offset().local_minus_utc() as i64,
+    }
+}
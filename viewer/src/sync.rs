@@ -0,0 +1,289 @@
+//! Background sync that keeps the playlist cached.
+//!
+//! `Preloader::preload_next` only looks a few slides ahead. This task walks
+//! the whole playlist in upcoming play order, so newly published media and
+//! media evicted earlier are fetched again before they're due — as far as
+//! the cache budget and the sync window allow. Bandwidth is shared with
+//! every other download through the `Throttle`.
+
+use crate::assets::{AssetManager, Media, Preloader};
+use crate::cache::CacheStatus;
+use crate::schedule::TimeWindow;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::Notify;
+
+/// Stop filling the cache at this share of its limit. Past it every fetch
+/// evicts something, quite possibly media due sooner than what was fetched.
+const FILL_PERCENT: u64 = 90;
+
+/// Delay before retrying an item that failed to sync; doubles with each
+/// further failure, up to `RETRY_MAX`.
+const RETRY_BASE: Duration = Duration::from_secs(60);
+const RETRY_MAX: Duration = Duration::from_secs(6 * 60 * 60);
+
+/// How often to check the clock while outside the sync window.
+const WINDOW_POLL: Duration = Duration::from_secs(60);
+
+/// Limits the sync task works within.
+#[derive(Debug, Clone)]
+pub struct SyncSettings {
+    /// Time between passes when nothing asks for one sooner.
+    pub interval: Duration,
+    /// When downloads are allowed; `None` for any time.
+    pub window: Option<TimeWindow>,
+}
+
+/// What the sync task is doing.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SyncPhase {
+    #[default]
+    Starting,
+    Syncing,
+    /// Everything that can be cached is.
+    Idle,
+    /// Stopped at the cache budget.
+    Full,
+    /// Outside the sync window.
+    Waiting,
+}
+
+impl SyncPhase {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SyncPhase::Starting => "starting",
+            SyncPhase::Syncing => "syncing",
+            SyncPhase::Idle => "idle",
+            SyncPhase::Full => "full",
+            SyncPhase::Waiting => "waiting",
+        }
+    }
+}
+
+/// Sync progress, for the debug overlay and the heartbeat.
+#[derive(Debug, Clone, Default)]
+pub struct SyncProgress {
+    pub phase: SyncPhase,
+    /// Playlist items with every asset cached.
+    pub cached: usize,
+    pub total: usize,
+    /// Items waiting out a retry delay after failing to sync.
+    pub retrying: usize,
+}
+
+/// Retry state of an item that failed to sync.
+struct Backoff {
+    failures: u32,
+    retry_at: Instant,
+}
+
+/// Reconciles the cache with the playlist in the background.
+pub struct SyncDaemon {
+    asset_manager: Arc<AssetManager>,
+    client: reqwest::Client,
+    settings: SyncSettings,
+    cache_status: Arc<Mutex<CacheStatus>>,
+    kick: Notify,
+    backoff: Mutex<HashMap<String, Backoff>>,
+    progress: Arc<Mutex<SyncProgress>>,
+}
+
+impl SyncDaemon {
+    pub fn new(
+        asset_manager: Arc<AssetManager>,
+        client: reqwest::Client,
+        settings: SyncSettings,
+        cache_status: Arc<Mutex<CacheStatus>>,
+    ) -> Self {
+        Self {
+            asset_manager,
+            client,
+            settings,
+            cache_status,
+            kick: Notify::new(),
+            backoff: Mutex::new(HashMap::new()),
+            progress: Arc::new(Mutex::new(SyncProgress::default())),
+        }
+    }
+
+    pub fn progress(&self) -> SyncProgress {
+        self.progress
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    /// Handle to the published progress.
+    pub fn progress_handle(&self) -> Arc<Mutex<SyncProgress>> {
+        self.progress.clone()
+    }
+
+    /// Start the next pass now rather than at the next interval, e.g. after
+    /// the playlist changed.
+    pub fn kick(&self) {
+        self.kick.notify_one();
+    }
+
+    /// Wait until the next pass is due: the interval elapsed or `kick` was
+    /// called, and the clock is inside the sync window.
+    pub async fn wait_turn(&self) {
+        let _ = tokio::time::timeout(self.settings.interval, self.kick.notified()).await;
+        while !self.in_window() {
+            self.set_phase(SyncPhase::Waiting);
+            tokio::time::sleep(WINDOW_POLL).await;
+        }
+    }
+
+    /// One pass: fetch what's missing, pinned media first and then in the
+    /// order it'll be shown starting after `current_index`.
+    pub async fn reconcile(&self, playlist: &[Media], current_index: usize, token: Option<&str>) {
+        if !self.in_window() {
+            self.set_phase(SyncPhase::Waiting);
+            return;
+        }
+
+        Preloader::new(self.asset_manager.clone(), self.client.clone())
+            .preload_pinned(playlist, token)
+            .await;
+
+        let total = playlist.len();
+        self.prune_backoff(playlist);
+        let mut cached = 0;
+        let mut missing = Vec::new();
+        for i in 1..=total {
+            let media = &playlist[(current_index + i) % total];
+            if self.asset_manager.is_fully_cached(media).await {
+                cached += 1;
+            } else {
+                missing.push(media);
+            }
+        }
+        self.publish(
+            if missing.is_empty() {
+                SyncPhase::Idle
+            } else {
+                SyncPhase::Syncing
+            },
+            cached,
+            total,
+        );
+        if missing.is_empty() {
+            return;
+        }
+
+        tracing::debug!("Sync: {} of {} items to fetch", missing.len(), total);
+        for media in missing {
+            if !self.in_window() {
+                self.publish(SyncPhase::Waiting, cached, total);
+                return;
+            }
+            if self.cache_full() {
+                tracing::debug!("Sync: cache budget reached, stopping pass");
+                self.publish(SyncPhase::Full, cached, total);
+                return;
+            }
+            if self.backing_off(&media.id) {
+                continue;
+            }
+
+            if let Err(e) = self
+                .asset_manager
+                .preload_media(media, &self.client, token)
+                .await
+            {
+                tracing::warn!("Sync: failed to fetch {}: {}", media.id, e);
+            }
+            // preload_media logs per-asset failures rather than returning
+            // them; what's in the cache afterwards is what counts.
+            let ok = self.asset_manager.is_fully_cached(media).await;
+            self.record_result(&media.id, ok);
+            if ok {
+                cached += 1;
+            }
+            self.publish(SyncPhase::Syncing, cached, total);
+        }
+        self.publish(SyncPhase::Idle, cached, total);
+    }
+
+    fn in_window(&self) -> bool {
+        self.settings.window.is_none_or(|w| w.contains_now())
+    }
+
+    /// The unpinned part of the cache is at its fill level, or the disk is
+    /// below its free-space floor.
+    fn cache_full(&self) -> bool {
+        let stats = &self
+            .cache_status
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .stats;
+        let unpinned = stats.current_size.saturating_sub(stats.pinned_size);
+        stats.disk_pressure || unpinned * 100 >= stats.max_size * FILL_PERCENT
+    }
+
+    fn backing_off(&self, media_id: &str) -> bool {
+        self.backoff
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(media_id)
+            .is_some_and(|b| b.retry_at > Instant::now())
+    }
+
+    fn record_result(&self, media_id: &str, ok: bool) {
+        let mut backoff = self.backoff.lock().unwrap_or_else(|e| e.into_inner());
+        if ok {
+            backoff.remove(media_id);
+            return;
+        }
+        let entry = backoff.entry(media_id.to_string()).or_insert(Backoff {
+            failures: 0,
+            retry_at: Instant::now(),
+        });
+        entry.failures += 1;
+        let delay = RETRY_BASE
+            .saturating_mul(1 << (entry.failures - 1).min(16))
+            .min(RETRY_MAX);
+        entry.retry_at = Instant::now() + delay;
+        tracing::info!(
+            "Sync: {} failed {} time(s), retrying in {}s",
+            media_id,
+            entry.failures,
+            delay.as_secs()
+        );
+    }
+
+    /// Forget retry state for media no longer in the playlist.
+    fn prune_backoff(&self, playlist: &[Media]) {
+        let ids: std::collections::HashSet<&str> = playlist.iter().map(|m| m.id.as_str()).collect();
+        self.backoff
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .retain(|id, _| ids.contains(id.as_str()));
+    }
+
+    fn set_phase(&self, phase: SyncPhase) {
+        self.progress
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .phase = phase;
+    }
+
+    fn publish(&self, phase: SyncPhase, cached: usize, total: usize) {
+        let retrying = {
+            let now = Instant::now();
+            self.backoff
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .values()
+                .filter(|b| b.retry_at > now)
+                .count()
+        };
+        *self.progress.lock().unwrap_or_else(|e| e.into_inner()) = SyncProgress {
+            phase,
+            cached,
+            total,
+            retrying,
+        };
+    }
+}
@@ -0,0 +1,110 @@
+//! Shared limits on asset downloads: a byte-rate limit with burst, a cap on
+//! concurrent downloads, metered mode, and a pause switch for quiet hours.
+//!
+//! Every download — preloading, background sync, or the current slide —
+//! goes through the same `Throttle`, so together they stay within what the
+//! household connection can spare.
+
+use crate::schedule::TimeWindow;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tokio::sync::{watch, Semaphore, SemaphorePermit};
+
+/// Download limits, from config.
+#[derive(Debug, Clone)]
+pub struct DownloadLimits {
+    /// Downloads allowed in flight at once (at least 1).
+    pub max_concurrent: usize,
+    /// Sustained rate in bytes/sec; 0 for unlimited.
+    pub bytes_per_sec: u64,
+    /// Bytes that may go through at full speed after an idle spell.
+    pub burst_bytes: u64,
+    /// Metered connection: only fetch video inside `off_peak`.
+    pub metered: bool,
+    /// When a metered connection is cheap; `None` means never.
+    pub off_peak: Option<TimeWindow>,
+}
+
+/// Token bucket state. `tokens` goes negative when a chunk overdraws it;
+/// the caller then waits off the debt.
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+/// Enforces `DownloadLimits` across all downloads.
+pub struct Throttle {
+    limits: DownloadLimits,
+    slots: Semaphore,
+    bucket: Mutex<Bucket>,
+    /// Set while downloads are paused (see `set_paused`).
+    paused: watch::Sender<bool>,
+}
+
+impl Throttle {
+    pub fn new(limits: DownloadLimits) -> Self {
+        let slots = Semaphore::new(limits.max_concurrent.max(1));
+        let bucket = Mutex::new(Bucket {
+            tokens: limits.burst_bytes as f64,
+            updated: Instant::now(),
+        });
+        Self {
+            limits,
+            slots,
+            bucket,
+            paused: watch::Sender::new(false),
+        }
+    }
+
+    /// Hold every download, including ones in flight, until unpaused.
+    pub fn set_paused(&self, paused: bool) {
+        self.paused.send_replace(paused);
+    }
+
+    async fn wait_unpaused(&self) {
+        let _ = self.paused.subscribe().wait_for(|paused| !paused).await;
+    }
+
+    /// Wait for a download slot; hold the permit for the whole download.
+    pub async fn slot(&self) -> SemaphorePermit<'_> {
+        self.wait_unpaused().await;
+        self.slots
+            .acquire()
+            .await
+            .expect("download semaphore is never closed")
+    }
+
+    /// Account for `bytes` just received, sleeping as long as it takes to
+    /// bring the average back down to the configured rate.
+    pub async fn consume(&self, bytes: u64) {
+        self.wait_unpaused().await;
+        let rate = self.limits.bytes_per_sec;
+        if rate == 0 {
+            return;
+        }
+        let wait = {
+            let mut bucket = self.bucket.lock().unwrap_or_else(|e| e.into_inner());
+            let now = Instant::now();
+            let refill = now.duration_since(bucket.updated).as_secs_f64() * rate as f64;
+            // A burst of 0 would stall on the first chunk; allow at least
+            // a second's worth.
+            let capacity = self.limits.burst_bytes.max(rate) as f64;
+            bucket.tokens = (bucket.tokens + refill).min(capacity) - bytes as f64;
+            bucket.updated = now;
+            if bucket.tokens < 0.0 {
+                Duration::from_secs_f64(-bucket.tokens / rate as f64)
+            } else {
+                Duration::ZERO
+            }
+        };
+        if !wait.is_zero() {
+            tokio::time::sleep(wait).await;
+        }
+    }
+
+    /// Whether video may be downloaded right now: always, unless metered
+    /// outside off-peak hours.
+    pub fn video_allowed(&self) -> bool {
+        !self.limits.metered || self.limits.off_peak.is_some_and(|w| w.contains_now())
+    }
+}